@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::level::Level;
+use crate::settings::Settings;
+
+// The green room of the first level, and what every room turns into with high contrast on
+pub const CLEAR_COLOR: Color = Color::srgb(0.5, 0.7, 0.5);
+pub const HIGH_CONTRAST_CLEAR_COLOR: Color = Color::BLACK;
+
+// Playback rate of the UIA spin with reduced motion on
+const REDUCED_MOTION_UIA_SPEED: f32 = 0.5;
+
+// Reduced motion and high contrast, edited in the settings and read from the `Accessibility`
+// resource by whatever they affect. Camera shake, parallax and particles should check
+// `reduced_motion` too once there are any.
+pub struct AccessibilityPlugin;
+
+impl Plugin for AccessibilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Accessibility>()
+            .register_type::<Accessibility>()
+            .add_systems(
+                Update,
+                (
+                    sync_accessibility.run_if(resource_changed::<Settings>),
+                    apply_clear_color
+                        .run_if(resource_changed::<Accessibility>.or(resource_changed::<Level>)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Accessibility {
+    // No squash and stretch, a slower UIA spin, and no shaking or drifting backgrounds
+    pub reduced_motion: bool,
+    // Black room and the high-contrast UI theme
+    pub high_contrast: bool,
+}
+
+impl Accessibility {
+    // Playback rate of `clip` on top of the animation's own speed: reduced motion slows the UIA
+    // spin down
+    pub fn clip_speed(&self, clip: &str) -> f32 {
+        if self.reduced_motion && clip == "uia" {
+            REDUCED_MOTION_UIA_SPEED
+        } else {
+            1.0
+        }
+    }
+}
+
+// Whether to leave out squash, stretch and other purely decorative motion. Works as a run
+// condition, and without the plugin, e.g. in tests, motion is left on.
+pub fn reduced_motion(accessibility: Option<Res<Accessibility>>) -> bool {
+    accessibility.is_some_and(|accessibility| accessibility.reduced_motion)
+}
+
+fn sync_accessibility(settings: Res<Settings>, mut accessibility: ResMut<Accessibility>) {
+    accessibility.set_if_neq(settings.accessibility);
+}
+
+// Each level tints the room its own way
+fn apply_clear_color(
+    accessibility: Res<Accessibility>,
+    level: Res<Level>,
+    mut clear_color: ResMut<ClearColor>,
+) {
+    clear_color.0 = if accessibility.high_contrast {
+        HIGH_CONTRAST_CLEAR_COLOR
+    } else {
+        level.definition.background
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduced_motion_only_slows_the_uia_spin() {
+        let accessibility = Accessibility {
+            reduced_motion: true,
+            ..default()
+        };
+        assert_eq!(accessibility.clip_speed("uia"), REDUCED_MOTION_UIA_SPEED);
+        assert_eq!(accessibility.clip_speed("walk"), 1.0);
+        assert_eq!(Accessibility::default().clip_speed("uia"), 1.0);
+    }
+}
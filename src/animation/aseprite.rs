@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::{AnimationClip, AnimationSheet, PlaybackDirection, PlaybackMode, TransitionRule};
+
+// Reads the JSON written by Aseprite's "Export Sprite Sheet" (array or hash frames) into an
+// `AnimationSheet`, with one clip per frame tag. Tags reaching past the last frame fail to load.
+#[derive(Default)]
+pub struct AsepriteLoader;
+
+#[derive(Debug, Error)]
+pub enum AsepriteLoaderError {
+    #[error("could not read aseprite sheet: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse aseprite sheet: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("aseprite tag {name:?} spans frames {from} to {to}, but the sheet has {frames} frames")]
+    TagOutOfRange {
+        name: String,
+        from: usize,
+        to: usize,
+        frames: usize,
+    },
+}
+
+#[derive(Deserialize)]
+struct AsepriteFile {
+    frames: AsepriteFrames,
+    meta: AsepriteMeta,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AsepriteFrames {
+    Array(Vec<AsepriteFrame>),
+    // Relies on serde_json's `preserve_order` to keep the frames in export order
+    Hash(serde_json::Map<String, serde_json::Value>),
+}
+
+#[derive(Deserialize)]
+struct AsepriteFrame {
+    frame: AsepriteRect,
+    // Milliseconds
+    duration: u32,
+}
+
+#[derive(Deserialize)]
+struct AsepriteRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct AsepriteSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct AsepriteMeta {
+    image: String,
+    size: AsepriteSize,
+    #[serde(default, rename = "frameTags")]
+    frame_tags: Vec<AsepriteTag>,
+}
+
+#[derive(Deserialize)]
+struct AsepriteTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: String,
+    // Only written by newer Aseprite versions, as a string
+    #[serde(default)]
+    repeat: Option<String>,
+}
+
+impl AsepriteTag {
+    fn to_clip(&self, frames: &[AsepriteFrame]) -> Result<AnimationClip, AsepriteLoaderError> {
+        // A clip past the end of the atlas would only fail once it's played
+        if self.from > self.to || self.to >= frames.len() {
+            return Err(AsepriteLoaderError::TagOutOfRange {
+                name: self.name.clone(),
+                from: self.from,
+                to: self.to,
+                frames: frames.len(),
+            });
+        }
+        let direction = match self.direction.as_str() {
+            "reverse" => PlaybackDirection::Reverse,
+            "pingpong" => PlaybackDirection::PingPong,
+            "pingpong_reverse" => PlaybackDirection::PingPongReverse,
+            _ => PlaybackDirection::Forward,
+        };
+        // Tags loop forever in Aseprite unless a repeat count is set
+        let mode = match self.repeat.as_deref().and_then(|n| n.parse().ok()) {
+            Some(count) if count > 0 => PlaybackMode::LoopN(count),
+            _ => PlaybackMode::Loop,
+        };
+        let fps = (1000 / frames[self.from].duration.max(1)).clamp(1, u8::MAX as u32) as u8;
+
+        let mut clip = AnimationClip::new(self.from, self.to, fps)
+            .with_name(&self.name)
+            .with_mode(mode)
+            .with_direction(direction);
+        for (index, frame) in frames.iter().enumerate().take(self.to + 1).skip(self.from) {
+            clip = clip.with_duration(index, frame.duration as f32 / 1000.0);
+        }
+        Ok(clip)
+    }
+}
+
+impl AssetLoader for AsepriteLoader {
+    type Asset = AnimationSheet;
+    type Settings = ();
+    type Error = AsepriteLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: AsepriteFile = serde_json::from_slice(&bytes)?;
+
+        let frames = match file.frames {
+            AsepriteFrames::Array(frames) => frames,
+            AsepriteFrames::Hash(map) => map
+                .into_iter()
+                .map(|(_, value)| serde_json::from_value(value))
+                .collect::<Result<_, _>>()?,
+        };
+
+        let mut layout =
+            TextureAtlasLayout::new_empty(UVec2::new(file.meta.size.w, file.meta.size.h));
+        for AsepriteFrame { frame, .. } in &frames {
+            layout.add_texture(URect::new(
+                frame.x,
+                frame.y,
+                frame.x + frame.w,
+                frame.y + frame.h,
+            ));
+        }
+
+        let clips = file
+            .meta
+            .frame_tags
+            .iter()
+            .map(|tag| Ok((tag.name.clone(), tag.to_clip(&frames)?)))
+            .collect::<Result<HashMap<_, _>, AsepriteLoaderError>>()?;
+
+        // The image path is relative to the JSON file
+        let image_path = load_context
+            .path()
+            .parent()
+            .map(|dir| dir.join(&file.meta.image))
+            .unwrap_or_else(|| file.meta.image.clone().into());
+        let texture = load_context.load(image_path);
+        let layout = load_context.add_labeled_asset("layout".into(), layout);
+        Ok(AnimationSheet {
+            texture,
+            layout,
+            clips,
+            frame_names: HashMap::new(),
+            frame_anchors: HashMap::new(),
+            default_transition: TransitionRule::default(),
+            transitions: Vec::new(),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aseprite.json"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Frames lasting `durations` milliseconds each
+    fn frames(durations: &[u32]) -> Vec<AsepriteFrame> {
+        durations
+            .iter()
+            .map(|&duration| AsepriteFrame {
+                frame: AsepriteRect {
+                    x: 0,
+                    y: 0,
+                    w: 16,
+                    h: 16,
+                },
+                duration,
+            })
+            .collect()
+    }
+
+    fn tag(from: usize, to: usize, direction: &str, repeat: Option<&str>) -> AsepriteTag {
+        AsepriteTag {
+            name: "walk".to_owned(),
+            from,
+            to,
+            direction: direction.to_owned(),
+            repeat: repeat.map(str::to_owned),
+        }
+    }
+
+    fn clip(tag: AsepriteTag) -> AnimationClip {
+        tag.to_clip(&frames(&[100; 4])).unwrap()
+    }
+
+    #[test]
+    fn direction_follows_the_tag() {
+        for (direction, expected) in [
+            ("", PlaybackDirection::Forward),
+            ("forward", PlaybackDirection::Forward),
+            ("reverse", PlaybackDirection::Reverse),
+            ("pingpong", PlaybackDirection::PingPong),
+            ("pingpong_reverse", PlaybackDirection::PingPongReverse),
+        ] {
+            assert_eq!(clip(tag(0, 3, direction, None)).direction, expected);
+        }
+    }
+
+    #[test]
+    fn tags_loop_unless_they_repeat_a_number_of_times() {
+        for (repeat, expected) in [
+            (None, PlaybackMode::Loop),
+            (Some("3"), PlaybackMode::LoopN(3)),
+            (Some("0"), PlaybackMode::Loop),
+            (Some("often"), PlaybackMode::Loop),
+        ] {
+            assert_eq!(clip(tag(0, 3, "forward", repeat)).mode, expected);
+        }
+    }
+
+    #[test]
+    fn frame_durations_are_converted_to_seconds() {
+        let clip = tag(1, 2, "forward", None)
+            .to_clip(&frames(&[100, 50, 250]))
+            .unwrap();
+        assert_eq!((clip.first, clip.last), (1, 2));
+        assert_eq!(clip.name, "walk");
+        // From the first frame of the tag
+        assert_eq!(clip.fps, 20);
+        assert_eq!(clip.durations, HashMap::from([(1, 0.05), (2, 0.25)]));
+    }
+
+    #[test]
+    fn tags_past_the_last_frame_fail() {
+        let error = tag(2, 4, "forward", None).to_clip(&frames(&[100; 4]));
+        assert!(matches!(
+            error,
+            Err(AsepriteLoaderError::TagOutOfRange { frames: 4, .. })
+        ));
+    }
+
+    #[test]
+    fn reversed_tags_fail() {
+        let error = tag(2, 1, "forward", None).to_clip(&frames(&[100; 4]));
+        assert!(matches!(
+            error,
+            Err(AsepriteLoaderError::TagOutOfRange { .. })
+        ));
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use super::{
+    AnimationClip, AnimationConfig, AnimationFinished, AnimationSheet, AnimationTransitions,
+    BlockedPolicy,
+};
+
+// Every clip an entity can play, by name
+#[derive(Component, Clone, Debug, Default)]
+pub struct AnimationClips {
+    clips: HashMap<String, AnimationClip>,
+    requested: Option<String>,
+    // Blocked by a higher priority clip, played once that one finishes
+    queued: Option<String>,
+}
+
+impl AnimationClips {
+    pub fn new(clips: impl IntoIterator<Item = AnimationClip>) -> Self {
+        Self {
+            clips: clips
+                .into_iter()
+                .map(|clip| (clip.name.clone(), clip))
+                .collect(),
+            requested: None,
+            queued: None,
+        }
+    }
+
+    pub fn from_sheet(sheet: &AnimationSheet) -> Self {
+        Self {
+            clips: sheet.clips.clone(),
+            requested: None,
+            queued: None,
+        }
+    }
+
+    // Swaps in the clips of a (reloaded) sheet, keeping any pending `play` request
+    pub fn replace_from_sheet(&mut self, sheet: &AnimationSheet) {
+        self.clips.clone_from(&sheet.clips);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&AnimationClip> {
+        self.clips.get(name)
+    }
+
+    pub fn insert(&mut self, clip: AnimationClip) {
+        self.clips.insert(clip.name.clone(), clip);
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.clips.keys().map(String::as_str)
+    }
+
+    // Starts `name` on the next animation update, following the entity's `AnimationTransitions`.
+    // If a clip with a higher priority is playing, `name` is queued or dropped instead.
+    pub fn play(&mut self, name: impl Into<String>) {
+        self.requested = Some(name.into());
+    }
+
+    pub fn has_queued(&self) -> bool {
+        self.queued.is_some()
+    }
+}
+
+pub(super) fn play_requested_clips(
+    mut query: Query<
+        (
+            &mut AnimationClips,
+            Option<&AnimationTransitions>,
+            &mut AnimationConfig,
+            &mut Sprite,
+        ),
+        Changed<AnimationClips>,
+    >,
+) {
+    for (mut clips, transitions, mut config, mut sprite) in &mut query {
+        let Some(name) = clips.bypass_change_detection().requested.take() else {
+            continue;
+        };
+        let Some(clip) = clips.get(&name) else {
+            warn!("no animation clip named {name:?}");
+            continue;
+        };
+        if config.is_playing && clip.priority < config.priority {
+            if clip.when_blocked == BlockedPolicy::Queue {
+                clips.bypass_change_detection().queued = Some(name);
+            }
+            continue;
+        }
+        let Some(atlas) = &mut sprite.texture_atlas else {
+            continue;
+        };
+        let rule = transitions
+            .map(|transitions| transitions.rule(&config.clip_name, &clip.name))
+            .unwrap_or_default();
+        config.transition_to(clip, rule, true, atlas);
+        clips.bypass_change_detection().queued = None;
+    }
+}
+
+pub(super) fn play_queued_clips(
+    mut finished: EventReader<AnimationFinished>,
+    mut query: Query<&mut AnimationClips>,
+) {
+    for event in finished.read() {
+        if let Ok(mut clips) = query.get_mut(event.entity)
+            && let Some(name) = clips.queued.take()
+        {
+            clips.play(name);
+        }
+    }
+}
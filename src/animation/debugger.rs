@@ -0,0 +1,151 @@
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+
+use super::AnimationConfig;
+use crate::console::console_is_open;
+use crate::theme::{Surface, TextRole, Theme};
+
+// F3 panel listing every animated entity. PageUp/PageDown pick an entity, [ and ] scrub its
+// frames, - and = change its fps and P pauses it.
+pub struct AnimationDebuggerPlugin;
+
+impl Plugin for AnimationDebuggerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AnimationDebugger>().add_systems(
+            Update,
+            (
+                toggle_debugger.run_if(input_just_pressed(KeyCode::F3)),
+                (
+                    debugger_controls.run_if(not(console_is_open)),
+                    update_debugger_text,
+                )
+                    .chain()
+                    .run_if(any_with_component::<DebuggerPanel>),
+            ),
+        );
+    }
+}
+
+#[derive(Resource, Default)]
+struct AnimationDebugger {
+    selected: usize,
+}
+
+#[derive(Component)]
+struct DebuggerPanel;
+
+#[derive(Component)]
+struct DebuggerText;
+
+fn toggle_debugger(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    panels: Query<Entity, With<DebuggerPanel>>,
+) {
+    if let Ok(panel) = panels.single() {
+        commands.entity(panel).despawn();
+        return;
+    }
+    commands.spawn((
+        DebuggerPanel,
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            right: Val::Px(8.0),
+            padding: theme.panel_padding(),
+            ..Default::default()
+        },
+        theme.surface(Surface::Panel),
+        children![(DebuggerText, Text::default(), theme.text(TextRole::Small))],
+    ));
+}
+
+// Animated entities in a stable order, so the selection doesn't jump around
+fn sorted(entities: impl Iterator<Item = Entity>) -> Vec<Entity> {
+    let mut entities: Vec<Entity> = entities.collect();
+    entities.sort();
+    entities
+}
+
+fn debugger_controls(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut debugger: ResMut<AnimationDebugger>,
+    mut query: Query<(Entity, &mut AnimationConfig, &mut Sprite)>,
+) {
+    let entities = sorted(query.iter().map(|(entity, ..)| entity));
+    if entities.is_empty() {
+        return;
+    }
+    if keyboard_input.just_pressed(KeyCode::PageDown) {
+        debugger.selected += 1;
+    }
+    if keyboard_input.just_pressed(KeyCode::PageUp) {
+        debugger.selected = debugger
+            .selected
+            .checked_sub(1)
+            .unwrap_or(entities.len() - 1);
+    }
+    debugger.selected %= entities.len();
+
+    let Ok((_, mut config, mut sprite)) = query.get_mut(entities[debugger.selected]) else {
+        return;
+    };
+    let Some(index) = sprite.texture_atlas.as_ref().map(|atlas| atlas.index) else {
+        return;
+    };
+    if keyboard_input.just_pressed(KeyCode::BracketLeft) {
+        config.pause();
+        config.seek(index.saturating_sub(1), &mut sprite);
+    }
+    if keyboard_input.just_pressed(KeyCode::BracketRight) {
+        config.pause();
+        config.seek(index + 1, &mut sprite);
+    }
+    if keyboard_input.just_pressed(KeyCode::Minus) {
+        config.fps = config.fps.saturating_sub(1).max(1);
+    }
+    if keyboard_input.just_pressed(KeyCode::Equal) {
+        config.fps = config.fps.saturating_add(1);
+    }
+    if keyboard_input.just_pressed(KeyCode::KeyP) {
+        config.toggle_pause();
+    }
+}
+
+fn update_debugger_text(
+    debugger: Res<AnimationDebugger>,
+    query: Query<(Entity, Option<&Name>, &AnimationConfig, &Sprite)>,
+    mut text: Single<&mut Text, With<DebuggerText>>,
+) {
+    let mut lines = vec!["Animations (F3 to close)".to_owned()];
+    let entities = sorted(query.iter().map(|(entity, ..)| entity));
+    for (i, entity) in entities.into_iter().enumerate() {
+        let Ok((_, name, config, sprite)) = query.get(entity) else {
+            continue;
+        };
+        let index = sprite.texture_atlas.as_ref().map_or(0, |atlas| atlas.index);
+        let status = if !config.is_playing {
+            "stopped"
+        } else if config.is_paused {
+            "paused"
+        } else if config.is_holding {
+            "holding"
+        } else {
+            "playing"
+        };
+        lines.push(format!(
+            "{} {} | {:?} | frame {} ({}..={}) | {:.0}% | {} fps | {}",
+            if i == debugger.selected { ">" } else { " " },
+            name.map_or_else(|| entity.to_string(), |name| name.to_string()),
+            config.clip_name,
+            index,
+            config.first_sprite_index,
+            config.last_sprite_index,
+            config.frame_timer.fraction() * 100.0,
+            config.fps,
+            status,
+        ));
+    }
+    lines.push("PgUp/PgDn select  [ ] scrub  - = fps  P pause".to_owned());
+    text.0 = lines.join("\n");
+}
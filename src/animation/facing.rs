@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::AnimationConfig;
+
+// Which way an entity is looking, used to pick a row of a directional sprite sheet
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Reflect)]
+#[reflect(Component, Hash, PartialEq)]
+pub enum Facing {
+    #[default]
+    Right,
+    UpRight,
+    Up,
+    UpLeft,
+    Left,
+    DownLeft,
+    Down,
+    DownRight,
+}
+
+impl Facing {
+    // The closest of the eight directions, or `None` for a zero vector
+    pub fn from_direction(direction: Vec2) -> Option<Self> {
+        if direction == Vec2::ZERO {
+            return None;
+        }
+        let octant = (direction.to_angle() / std::f32::consts::FRAC_PI_4).round() as i32;
+        Some(match octant.rem_euclid(8) {
+            0 => Self::Right,
+            1 => Self::UpRight,
+            2 => Self::Up,
+            3 => Self::UpLeft,
+            4 => Self::Left,
+            5 => Self::DownLeft,
+            6 => Self::Down,
+            _ => Self::DownRight,
+        })
+    }
+
+    pub fn is_left(self) -> bool {
+        matches!(self, Self::UpLeft | Self::Left | Self::DownLeft)
+    }
+
+    // Whether the default (right-facing) row is mirrored to look this way, or `None` straight up
+    // or down, where it keeps looking whichever way it did before
+    fn mirrored(self) -> Option<bool> {
+        match self {
+            Self::Up | Self::Down => None,
+            other => Some(other.is_left()),
+        }
+    }
+
+    // Diagonals fall back to their horizontal component on four-direction sheets
+    fn horizontal(self) -> Self {
+        match self {
+            Self::UpRight | Self::DownRight => Self::Right,
+            Self::UpLeft | Self::DownLeft => Self::Left,
+            other => other,
+        }
+    }
+
+    pub fn to_vec2(self) -> Vec2 {
+        match self {
+            Self::Right => Vec2::X,
+            Self::UpRight => Vec2::new(1.0, 1.0).normalize(),
+            Self::Up => Vec2::Y,
+            Self::UpLeft => Vec2::new(-1.0, 1.0).normalize(),
+            Self::Left => Vec2::NEG_X,
+            Self::DownLeft => Vec2::new(-1.0, -1.0).normalize(),
+            Self::Down => Vec2::NEG_Y,
+            Self::DownRight => Vec2::new(1.0, -1.0).normalize(),
+        }
+    }
+}
+
+impl AnimationConfig {
+    // Atlas offset of the row for `facing`, and whether the default row has to be mirrored instead
+    // (`None` to leave the sprite as it is)
+    fn resolve_facing(&self, facing: Facing) -> (usize, Option<bool>) {
+        [facing, facing.horizontal()]
+            .iter()
+            .find_map(|candidate| self.facing_offsets.get(candidate))
+            .map(|&offset| (offset, Some(false)))
+            // No row for this facing, mirror the default (right-facing) row
+            .unwrap_or((0, facing.mirrored()))
+    }
+
+    // Moves the clip onto the row of `facing`, keeping the position within the clip
+    pub fn set_facing(&mut self, facing: Facing, sprite: &mut Sprite) {
+        let (offset, flip) = self.resolve_facing(facing);
+        self.facing = facing;
+        self.shift_row(offset, sprite.texture_atlas.as_mut());
+        if let Some(flip) = flip
+            && sprite.flip_x != flip
+        {
+            sprite.flip_x = flip;
+        }
+    }
+
+    pub(super) fn apply_facing_offsets(&mut self, offsets: &HashMap<Facing, usize>) {
+        self.facing_offsets.clone_from(offsets);
+        self.row_offset = 0;
+        let (offset, _) = self.resolve_facing(self.facing);
+        self.shift_row(offset, None);
+    }
+
+    fn shift_row(&mut self, offset: usize, atlas: Option<&mut TextureAtlas>) {
+        if offset == self.row_offset {
+            return;
+        }
+        // An atlas index that isn't on the current row (yet) ends up at the start of the new one
+        let shift = |index: usize| index.saturating_sub(self.row_offset) + offset;
+        self.first_sprite_index = shift(self.first_sprite_index);
+        self.last_sprite_index = shift(self.last_sprite_index);
+        if let Some(atlas) = atlas {
+            atlas.index = shift(atlas.index);
+        }
+        self.row_offset = offset;
+    }
+}
+
+pub(super) fn apply_facing(mut query: Query<(&Facing, &mut AnimationConfig, &mut Sprite)>) {
+    for (facing, mut config, mut sprite) in &mut query {
+        let (_, flip) = config.resolve_facing(*facing);
+        if config.facing != *facing || flip.is_some_and(|flip| sprite.flip_x != flip) {
+            config.set_facing(*facing, &mut sprite);
+        }
+    }
+}
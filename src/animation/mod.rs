@@ -0,0 +1,921 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::accessibility::Accessibility;
+use crate::movement::{CAT_SPEED, MovementConfig, Velocity};
+
+mod aseprite;
+mod clips;
+mod debugger;
+mod facing;
+mod registry;
+mod reload;
+mod sheet;
+mod squash;
+mod texture_packer;
+mod transition;
+
+pub use aseprite::AsepriteLoader;
+pub use clips::AnimationClips;
+pub use debugger::AnimationDebuggerPlugin;
+pub use facing::Facing;
+pub use registry::{AnimationRegistry, SpawnAnimatedExt};
+pub use reload::AnimationSheetHandle;
+pub use sheet::{AnimationSheet, AnimationSheetLoader};
+pub use squash::SquashStretch;
+pub use texture_packer::TexturePackerLoader;
+pub use transition::{AnimationTransitions, PendingClip, TransitionDef, TransitionRule};
+
+// Upper bound on frames a single update may advance an animation by
+const MAX_FRAMES_PER_UPDATE: usize = 256;
+
+// Moving at the regular cat speed walks, only boosted speeds (sprint, dash, ...) run. Used when
+// there is no `MovementConfig` to derive it from.
+const RUN_SPEED_THRESHOLD: f32 = CAT_SPEED * 1.25;
+
+// Animates every entity with an `AnimationConfig` and a `Sprite`, whatever else it is
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AnimationSpeed>()
+            .init_resource::<AnimationRegistry>()
+            .register_type::<AnimationSpeed>()
+            .register_type::<AnimationConfig>()
+            .register_type::<AnimationState>()
+            .register_type::<StateClips>()
+            .register_type::<Facing>()
+            .register_type::<SquashStretch>()
+            .add_event::<AnimationFrameEvent>()
+            .add_event::<AnimationFinished>()
+            .add_observer(on_play_animation)
+            .init_asset::<AnimationSheet>()
+            .init_asset_loader::<AnimationSheetLoader>()
+            .init_asset_loader::<AsepriteLoader>()
+            .init_asset_loader::<TexturePackerLoader>()
+            .configure_sets(
+                Update,
+                (AnimationSystems::SelectClip, AnimationSystems::Advance).chain(),
+            )
+            .add_systems(
+                Update,
+                (
+                    reload::reload_modified_sheets,
+                    update_animation_state,
+                    apply_animation_state,
+                    resume_state_clip,
+                    clips::play_queued_clips,
+                    clips::play_requested_clips,
+                )
+                    .chain()
+                    .in_set(AnimationSystems::SelectClip),
+            )
+            .add_systems(
+                Update,
+                (
+                    execute_animations,
+                    facing::apply_facing,
+                    sheet::apply_frame_anchors,
+                    squash::squash_and_stretch,
+                )
+                    .chain()
+                    .in_set(AnimationSystems::Advance),
+            );
+    }
+}
+
+// Scales the playback rate of every animation, on top of `Time<Virtual>`'s relative speed
+#[derive(Resource, Clone, Copy, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct AnimationSpeed(pub f32);
+
+impl Default for AnimationSpeed {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+// Gameplay that changes what an entity should be playing runs before `SelectClip`
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum AnimationSystems {
+    SelectClip,
+    Advance,
+}
+
+// Everything an entity needs to be picked up by `AnimationPlugin`, showing `clip` from `sheet`
+pub fn animated_sprite(sheet: &AnimationSheet, clip: &AnimationClip) -> (Sprite, AnimationConfig) {
+    let config = AnimationConfig::from(clip.clone());
+    let sprite = Sprite {
+        image: sheet.texture.clone(),
+        texture_atlas: Some(TextureAtlas {
+            layout: sheet.layout.clone(),
+            index: config.start_index(),
+        }),
+        ..Default::default()
+    };
+    (sprite, config)
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Reflect)]
+pub enum PlaybackMode {
+    // Play the clip a single time and stop on the first frame
+    #[default]
+    Once,
+    // Play the clip over and over until something else is played
+    Loop,
+    // Play the clip this many times in total, then stop
+    LoopN(u32),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Reflect)]
+pub enum PlaybackDirection {
+    // first -> last
+    #[default]
+    Forward,
+    // last -> first
+    Reverse,
+    // first -> last -> first, counting as a single loop
+    PingPong,
+    // last -> first -> last, counting as a single loop
+    PingPongReverse,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum BlockedPolicy {
+    // Play once the blocking clip has finished, replacing anything queued before
+    #[default]
+    Queue,
+    // Forget about the request
+    Drop,
+}
+
+// A named marker on an atlas index, see `AnimationFrameEvent`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Reflect)]
+pub struct FrameMarker {
+    pub frame: usize,
+    pub name: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct AnimationClip {
+    // Filled in from the key the clip is stored under in its sheet
+    #[serde(skip)]
+    pub name: String,
+    pub first: usize,
+    pub last: usize,
+    pub fps: u8,
+    // Seconds to show specific atlas indices for, overriding `fps`
+    #[serde(default)]
+    pub durations: HashMap<usize, f32>,
+    #[serde(default)]
+    pub mode: PlaybackMode,
+    #[serde(default)]
+    pub direction: PlaybackDirection,
+    // Atlas offset of the row to use for each facing, e.g. `{Left: 10, Up: 20}`. Facings without
+    // a row use the clip's own frames, mirrored when looking left.
+    #[serde(default)]
+    pub facings: HashMap<Facing, usize>,
+    // Seconds to linger on the last frame once the clip has finished
+    #[serde(default)]
+    pub hold: f32,
+    // Clips can only be interrupted by clips of at least the same priority
+    #[serde(default)]
+    pub priority: u8,
+    // What happens to this clip when it's requested while a higher priority one plays
+    #[serde(default)]
+    pub when_blocked: BlockedPolicy,
+    #[serde(default)]
+    pub events: Vec<FrameMarker>,
+}
+
+impl AnimationClip {
+    pub fn new(first: usize, last: usize, fps: u8) -> Self {
+        Self {
+            name: String::new(),
+            first,
+            last,
+            fps,
+            durations: HashMap::new(),
+            mode: PlaybackMode::Once,
+            direction: PlaybackDirection::Forward,
+            facings: HashMap::new(),
+            hold: 0.0,
+            priority: 0,
+            when_blocked: BlockedPolicy::Queue,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    pub fn with_hold(mut self, seconds: f32) -> Self {
+        self.hold = seconds;
+        self
+    }
+
+    pub fn with_duration(mut self, index: usize, seconds: f32) -> Self {
+        self.durations.insert(index, seconds);
+        self
+    }
+
+    pub fn with_mode(mut self, mode: PlaybackMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: PlaybackDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn with_priority(mut self, priority: u8, when_blocked: BlockedPolicy) -> Self {
+        self.priority = priority;
+        self.when_blocked = when_blocked;
+        self
+    }
+
+    pub fn with_facing_row(mut self, facing: Facing, offset: usize) -> Self {
+        self.facings.insert(facing, offset);
+        self
+    }
+
+    pub fn with_event(mut self, frame: usize, name: impl Into<String>) -> Self {
+        self.events.push(FrameMarker {
+            frame,
+            name: name.into(),
+        });
+        self
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct AnimationConfig {
+    pub clip_name: String,
+    pub first_sprite_index: usize,
+    pub last_sprite_index: usize,
+    pub fps: u8,
+    pub frame_durations: HashMap<usize, Duration>,
+    pub mode: PlaybackMode,
+    pub direction: PlaybackDirection,
+    pub facing: Facing,
+    pub facing_offsets: HashMap<Facing, usize>,
+    // Offset of the facing row currently applied to the sprite indices
+    pub row_offset: usize,
+    pub loops_completed: u32,
+    pub priority: u8,
+    pub hold_last_frame: Duration,
+    pub events: Vec<FrameMarker>,
+    pub frame_timer: Timer,
+    pub is_playing: bool,
+    pub is_holding: bool,
+    pub is_paused: bool,
+    // Per-entity playback rate, multiplied with the global `AnimationSpeed`
+    pub speed: f32,
+    // Whether a ping-pong clip is on its way back to the frame it started from
+    pub is_returning: bool,
+    #[reflect(ignore)]
+    pub pending: Option<PendingClip>,
+}
+
+impl AnimationConfig {
+    pub fn new(first: usize, last: usize, fps: u8) -> Self {
+        Self {
+            clip_name: String::new(),
+            first_sprite_index: first,
+            last_sprite_index: last,
+            fps,
+            frame_durations: HashMap::new(),
+            mode: PlaybackMode::Once,
+            direction: PlaybackDirection::Forward,
+            facing: Facing::default(),
+            facing_offsets: HashMap::new(),
+            row_offset: 0,
+            loops_completed: 0,
+            priority: 0,
+            hold_last_frame: Duration::ZERO,
+            events: Vec::new(),
+            frame_timer: Timer::new(Self::fps_duration(fps), TimerMode::Once),
+            is_playing: false,
+            is_holding: false,
+            is_paused: false,
+            speed: 1.0,
+            is_returning: false,
+            pending: None,
+        }
+    }
+
+    pub fn with_speed(mut self, speed: f32) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_hold(mut self, hold: Duration) -> Self {
+        self.hold_last_frame = hold;
+        self
+    }
+
+    pub fn with_mode(mut self, mode: PlaybackMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: PlaybackDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    fn fps_duration(fps: u8) -> Duration {
+        Duration::from_secs_f32(1.0 / (fps.max(1) as f32))
+    }
+
+    // Where the atlas index `index` is on the default facing row, which frame durations and
+    // markers refer to. `None` for an index before the current row, e.g. one left over from
+    // another clip.
+    pub fn row_frame(&self, index: usize) -> Option<usize> {
+        index.checked_sub(self.row_offset)
+    }
+
+    // How long the atlas index `index` stays on screen
+    pub fn frame_duration(&self, index: usize) -> Duration {
+        self.row_frame(index)
+            .and_then(|frame| self.frame_durations.get(&frame))
+            .copied()
+            .unwrap_or_else(|| Self::fps_duration(self.fps))
+    }
+
+    pub fn timer_for_frame(&self, index: usize) -> Timer {
+        Timer::new(self.frame_duration(index), TimerMode::Once)
+    }
+
+    pub fn set_clip(&mut self, clip: &AnimationClip) {
+        self.clip_name.clone_from(&clip.name);
+        self.first_sprite_index = clip.first;
+        self.last_sprite_index = clip.last;
+        self.fps = clip.fps;
+        self.frame_durations = Self::durations_from_clip(clip);
+        self.mode = clip.mode;
+        self.direction = clip.direction;
+        self.apply_facing_offsets(&clip.facings);
+        self.loops_completed = 0;
+        self.priority = clip.priority;
+        self.hold_last_frame = clip_seconds(clip, clip.hold.max(0.0)).unwrap_or_default();
+        self.events.clone_from(&clip.events);
+        self.frame_timer = self.timer_for_frame(self.start_index());
+        self.is_holding = false;
+        self.is_returning = false;
+        self.pending = None;
+    }
+
+    fn durations_from_clip(clip: &AnimationClip) -> HashMap<usize, Duration> {
+        clip.durations
+            .iter()
+            .filter_map(|(&index, &seconds)| Some((index, clip_seconds(clip, seconds.max(0.001))?)))
+            .collect()
+    }
+
+    // The atlas index playback starts from (and rests on once stopped)
+    pub fn start_index(&self) -> usize {
+        match self.direction {
+            PlaybackDirection::Forward | PlaybackDirection::PingPong => self.first_sprite_index,
+            PlaybackDirection::Reverse | PlaybackDirection::PingPongReverse => {
+                self.last_sprite_index
+            }
+        }
+    }
+
+    // Starts the current clip over from its first frame
+    pub fn restart(&mut self) {
+        // We create a new timer when the animation is triggered
+        self.frame_timer = self.timer_for_frame(self.start_index());
+        self.loops_completed = 0;
+        self.is_playing = true;
+        self.is_paused = false;
+        self.is_holding = false;
+        self.is_returning = false;
+    }
+
+    // Freezes playback on the current frame, keeping the time already spent on it
+    pub fn pause(&mut self) {
+        self.is_paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.is_paused = false;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.is_paused = !self.is_paused;
+    }
+
+    // Stops playback and puts the clip back on its starting frame
+    pub fn stop(&mut self, sprite: &mut Sprite) {
+        self.is_playing = false;
+        self.is_paused = false;
+        self.is_holding = false;
+        self.is_returning = false;
+        self.pending = None;
+        self.loops_completed = 0;
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            atlas.index = self.start_index();
+        }
+        self.frame_timer = self.timer_for_frame(self.start_index());
+    }
+
+    // Jumps to `index` (clamped to the clip) and shows it for its full duration
+    pub fn seek(&mut self, index: usize, sprite: &mut Sprite) {
+        let index = index.clamp(self.first_sprite_index, self.last_sprite_index);
+        self.is_holding = false;
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            atlas.index = index;
+        }
+        self.frame_timer = self.timer_for_frame(index);
+    }
+
+    // The frame following `current`, or `None` once a full pass through the clip is done
+    fn next_index(&mut self, current: usize) -> Option<usize> {
+        let (first, last) = (self.first_sprite_index, self.last_sprite_index);
+        match self.direction {
+            PlaybackDirection::Forward => (current < last).then(|| current + 1),
+            PlaybackDirection::Reverse => (current > first).then(|| current - 1),
+            PlaybackDirection::PingPong if !self.is_returning => {
+                if current < last {
+                    Some(current + 1)
+                } else if current > first {
+                    self.is_returning = true;
+                    Some(current - 1)
+                } else {
+                    None
+                }
+            }
+            PlaybackDirection::PingPong => (current > first).then(|| current - 1),
+            PlaybackDirection::PingPongReverse if !self.is_returning => {
+                if current > first {
+                    Some(current - 1)
+                } else if current < last {
+                    self.is_returning = true;
+                    Some(current + 1)
+                } else {
+                    None
+                }
+            }
+            PlaybackDirection::PingPongReverse => (current < last).then(|| current + 1),
+        }
+    }
+
+    // Called when the last frame has been shown, returns whether playback should wrap around
+    fn finish_loop(&mut self) -> bool {
+        self.loops_completed = self.loops_completed.saturating_add(1);
+        match self.mode {
+            PlaybackMode::Once => false,
+            PlaybackMode::Loop => true,
+            PlaybackMode::LoopN(count) => self.loops_completed < count,
+        }
+    }
+}
+
+// Clips come from asset files, where a duration can be anything: one that isn't a number of
+// seconds is left out (with a warning) instead of panicking
+fn clip_seconds(clip: &AnimationClip, seconds: f32) -> Option<Duration> {
+    Duration::try_from_secs_f32(seconds)
+        .inspect_err(|error| warn!("clip {:?}: invalid duration {seconds}: {error}", clip.name))
+        .ok()
+}
+
+impl From<AnimationClip> for AnimationConfig {
+    fn from(clip: AnimationClip) -> Self {
+        let mut config = Self::new(clip.first, clip.last, clip.fps);
+        config.set_clip(&clip);
+        config
+    }
+}
+
+// Sent (and triggered on the entity, for observers) whenever an atlas index
+// carrying a `FrameMarker` is reached
+#[derive(Event, Clone, Debug)]
+pub struct AnimationFrameEvent {
+    pub entity: Entity,
+    pub frame: usize,
+    pub name: String,
+}
+
+// Sent (and triggered on the entity) once a clip that doesn't loop forever has played out
+#[derive(Event, Clone, Debug)]
+pub struct AnimationFinished {
+    pub entity: Entity,
+    pub clip: String,
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub enum AnimationState {
+    #[default]
+    Idle,
+    Walk,
+    Run,
+}
+
+impl AnimationState {
+    pub fn from_speed(speed: f32, run_threshold: f32) -> Self {
+        if speed <= f32::EPSILON {
+            Self::Idle
+        } else if speed < run_threshold {
+            Self::Walk
+        } else {
+            Self::Run
+        }
+    }
+}
+
+// Name of the `AnimationClips` entry played for each `AnimationState`
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct StateClips {
+    pub idle: String,
+    pub walk: String,
+    pub run: String,
+}
+
+impl Default for StateClips {
+    fn default() -> Self {
+        Self {
+            idle: "idle".into(),
+            walk: "walk".into(),
+            run: "run".into(),
+        }
+    }
+}
+
+impl StateClips {
+    pub fn get(&self, state: AnimationState) -> &str {
+        match state {
+            AnimationState::Idle => &self.idle,
+            AnimationState::Walk => &self.walk,
+            AnimationState::Run => &self.run,
+        }
+    }
+}
+
+// Starts a clip on the targeted entity: `commands.trigger_targets(PlayAnimation::new("uia"), cat)`
+#[derive(Event, Clone, Debug)]
+pub struct PlayAnimation {
+    pub clip: String,
+}
+
+impl PlayAnimation {
+    pub fn new(clip: impl Into<String>) -> Self {
+        Self { clip: clip.into() }
+    }
+}
+
+fn on_play_animation(trigger: Trigger<PlayAnimation>, mut query: Query<&mut AnimationClips>) {
+    if let Ok(mut clips) = query.get_mut(trigger.target()) {
+        clips.play(trigger.event().clip.clone());
+    }
+}
+
+// Plays the clip called `name` on every entity with `S`
+pub fn play_clip<S: Component>(name: &'static str) -> impl Fn(Commands, Query<Entity, With<S>>) {
+    move |mut commands, query| {
+        for entity in &query {
+            commands.trigger_targets(PlayAnimation::new(name), entity);
+        }
+    }
+}
+
+fn update_animation_state(
+    movement_config: Option<Res<MovementConfig>>,
+    mut query: Query<(&Velocity, &mut AnimationState)>,
+) {
+    let run_threshold =
+        movement_config.map_or(RUN_SPEED_THRESHOLD, |config| config.run_threshold());
+    for (velocity, mut state) in &mut query {
+        state.set_if_neq(AnimationState::from_speed(
+            velocity.0.length(),
+            run_threshold,
+        ));
+    }
+}
+
+fn apply_animation_state(
+    mut query: Query<(&AnimationState, &StateClips, &mut AnimationClips), Changed<AnimationState>>,
+) {
+    for (state, state_clips, mut clips) in &mut query {
+        clips.play(state_clips.get(*state));
+    }
+}
+
+// Once a one-off clip (like the UIA spin) is over, go back to whatever the state calls for
+fn resume_state_clip(
+    mut finished: EventReader<AnimationFinished>,
+    mut query: Query<(&AnimationState, &StateClips, &mut AnimationClips)>,
+) {
+    for event in finished.read() {
+        let Ok((state, state_clips, mut clips)) = query.get_mut(event.entity) else {
+            continue;
+        };
+        let clip = state_clips.get(*state);
+        // A queued clip takes precedence, see `clips::play_queued_clips`
+        if clip != event.clip && !clips.has_queued() {
+            clips.play(clip);
+        }
+    }
+}
+
+// What moving past the current frame led to
+enum FrameStep {
+    Advanced,
+    Holding,
+    Switched,
+    Finished,
+}
+
+fn step_frame(config: &mut AnimationConfig, atlas: &mut TextureAtlas) -> FrameStep {
+    if let Some(pending) = config.pending.take() {
+        // ...and another clip was waiting for this frame to end, then we switch to it.
+        config.switch_to(&pending.clip, pending.play, atlas);
+        return FrameStep::Switched;
+    }
+
+    if config.is_holding {
+        // ...and we were lingering on the last frame, then we move back to the start and stop.
+        atlas.index = config.start_index();
+        config.is_playing = false;
+        config.is_holding = false;
+        FrameStep::Finished
+    } else if let Some(next) = config.next_index(atlas.index) {
+        // ...and it is NOT the last frame, then we move to the next frame...
+        atlas.index = next;
+        // ...and reset the frame timer to start counting all over again
+        config.frame_timer = config.timer_for_frame(next);
+        FrameStep::Advanced
+    } else if config.finish_loop() {
+        // ...and it IS the last frame but the clip loops, then we start over. A ping-pong clip is
+        // already back on its start frame, so it goes on with the one after instead of showing
+        // that frame twice.
+        config.is_returning = false;
+        let start = config.start_index();
+        atlas.index = match config.direction {
+            PlaybackDirection::PingPong | PlaybackDirection::PingPongReverse => {
+                config.next_index(start).unwrap_or(start)
+            }
+            PlaybackDirection::Forward | PlaybackDirection::Reverse => start,
+        };
+        config.frame_timer = config.timer_for_frame(atlas.index);
+        FrameStep::Advanced
+    } else if config.hold_last_frame.is_zero() {
+        // ...and it IS the last frame, then we move back to the start and stop.
+        config.is_returning = false;
+        atlas.index = config.start_index();
+        config.is_playing = false;
+        FrameStep::Finished
+    } else {
+        // ...and it IS the last frame, then we keep showing it for the hold duration.
+        config.is_returning = false;
+        config.frame_timer = Timer::new(config.hold_last_frame, TimerMode::Once);
+        config.is_holding = true;
+        FrameStep::Holding
+    }
+}
+
+pub fn execute_animations(
+    mut commands: Commands,
+    // Virtual time, so pausing or slowing down the game does the same to animations
+    time: Res<Time<Virtual>>,
+    speed: Res<AnimationSpeed>,
+    accessibility: Option<Res<Accessibility>>,
+    mut frame_events: EventWriter<AnimationFrameEvent>,
+    mut finished_events: EventWriter<AnimationFinished>,
+    mut query: Query<(Entity, &mut AnimationConfig, &mut Sprite)>,
+) {
+    for (entity, mut config, mut sprite) in &mut query {
+        // We track how long the current sprite has been displayed for
+        if !config.is_playing || config.is_paused {
+            continue;
+        }
+        let Some(atlas) = &mut sprite.texture_atlas else {
+            continue;
+        };
+        let clip_speed = accessibility.as_deref().map_or(1.0, |accessibility| {
+            accessibility.clip_speed(&config.clip_name)
+        });
+        let rate = (speed.0 * config.speed * clip_speed).max(0.0);
+        let mut elapsed = time.delta().mul_f32(rate);
+
+        // A slow update can span several frames, so keep stepping until the elapsed time is used up
+        for _ in 0..MAX_FRAMES_PER_UPDATE {
+            let remaining = config.frame_timer.remaining();
+            if elapsed < remaining {
+                config.frame_timer.tick(elapsed);
+                break;
+            }
+            // If it has been displayed for the user-defined amount of time (fps or frame
+            // duration)...
+            elapsed -= remaining;
+
+            match step_frame(&mut config, atlas) {
+                FrameStep::Advanced => {
+                    let frame = config.row_frame(atlas.index);
+                    for marker in config.events.iter().filter(|m| Some(m.frame) == frame) {
+                        let event = AnimationFrameEvent {
+                            entity,
+                            frame: marker.frame,
+                            name: marker.name.clone(),
+                        };
+                        commands.trigger_targets(event.clone(), entity);
+                        frame_events.write(event);
+                    }
+                }
+                FrameStep::Finished => {
+                    let finished = AnimationFinished {
+                        entity,
+                        clip: config.clip_name.clone(),
+                    };
+                    commands.trigger_targets(finished.clone(), entity);
+                    finished_events.write(finished);
+                }
+                FrameStep::Holding | FrameStep::Switched => {}
+            }
+
+            if !config.is_playing {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playing(clip: AnimationClip) -> (AnimationConfig, TextureAtlas) {
+        let mut config = AnimationConfig::from(clip);
+        config.restart();
+        let atlas = TextureAtlas {
+            index: config.start_index(),
+            ..Default::default()
+        };
+        (config, atlas)
+    }
+
+    // Atlas indices shown by stepping `steps` times, stopping early once the clip finishes
+    fn frames(config: &mut AnimationConfig, atlas: &mut TextureAtlas, steps: usize) -> Vec<usize> {
+        let mut shown = Vec::new();
+        for _ in 0..steps {
+            let step = step_frame(config, atlas);
+            shown.push(atlas.index);
+            if matches!(step, FrameStep::Finished) {
+                break;
+            }
+        }
+        shown
+    }
+
+    #[test]
+    fn once_plays_through_and_stops_on_the_start() {
+        let (mut config, mut atlas) = playing(AnimationClip::new(3, 5, 10));
+        assert_eq!(frames(&mut config, &mut atlas, 10), [4, 5, 3]);
+        assert!(!config.is_playing);
+    }
+
+    #[test]
+    fn loop_wraps_around_counting_loops() {
+        let clip = AnimationClip::new(0, 2, 10).with_mode(PlaybackMode::Loop);
+        let (mut config, mut atlas) = playing(clip);
+        assert_eq!(frames(&mut config, &mut atlas, 7), [1, 2, 0, 1, 2, 0, 1]);
+        assert_eq!(config.loops_completed, 2);
+        assert!(config.is_playing);
+    }
+
+    #[test]
+    fn loop_n_stops_after_the_last_loop() {
+        let clip = AnimationClip::new(0, 1, 10).with_mode(PlaybackMode::LoopN(2));
+        let (mut config, mut atlas) = playing(clip);
+        assert_eq!(frames(&mut config, &mut atlas, 10), [1, 0, 1, 0]);
+        assert_eq!(config.loops_completed, 2);
+    }
+
+    #[test]
+    fn reverse_plays_from_the_last_frame() {
+        let clip = AnimationClip::new(0, 2, 10).with_direction(PlaybackDirection::Reverse);
+        let (mut config, mut atlas) = playing(clip);
+        assert_eq!(atlas.index, 2);
+        assert_eq!(frames(&mut config, &mut atlas, 10), [1, 0, 2]);
+    }
+
+    #[test]
+    fn ping_pong_goes_there_and_back() {
+        let clip = AnimationClip::new(0, 2, 10).with_direction(PlaybackDirection::PingPong);
+        let (mut config, mut atlas) = playing(clip);
+        assert_eq!(frames(&mut config, &mut atlas, 10), [1, 2, 1, 0, 0]);
+    }
+
+    #[test]
+    fn looping_ping_pong_doesnt_repeat_the_start_frame() {
+        let clip = AnimationClip::new(0, 2, 10)
+            .with_direction(PlaybackDirection::PingPong)
+            .with_mode(PlaybackMode::Loop);
+        let (mut config, mut atlas) = playing(clip);
+        assert_eq!(
+            frames(&mut config, &mut atlas, 9),
+            [1, 2, 1, 0, 1, 2, 1, 0, 1]
+        );
+        assert_eq!(config.loops_completed, 2);
+    }
+
+    #[test]
+    fn looping_ping_pong_reverse_doesnt_repeat_the_last_frame() {
+        let clip = AnimationClip::new(0, 2, 10)
+            .with_direction(PlaybackDirection::PingPongReverse)
+            .with_mode(PlaybackMode::Loop);
+        let (mut config, mut atlas) = playing(clip);
+        assert_eq!(frames(&mut config, &mut atlas, 6), [1, 0, 1, 2, 1, 0]);
+    }
+
+    #[test]
+    fn looping_a_single_frame_ping_pong_stays_on_it() {
+        let clip = AnimationClip::new(4, 4, 10)
+            .with_direction(PlaybackDirection::PingPong)
+            .with_mode(PlaybackMode::Loop);
+        let (mut config, mut atlas) = playing(clip);
+        assert_eq!(frames(&mut config, &mut atlas, 3), [4, 4, 4]);
+    }
+
+    #[test]
+    fn ping_pong_reverse_starts_from_the_last_frame() {
+        let clip = AnimationClip::new(0, 2, 10).with_direction(PlaybackDirection::PingPongReverse);
+        let (mut config, mut atlas) = playing(clip);
+        assert_eq!(atlas.index, 2);
+        assert_eq!(frames(&mut config, &mut atlas, 10), [1, 0, 1, 2, 2]);
+    }
+
+    #[test]
+    fn hold_lingers_on_the_last_frame() {
+        let (mut config, mut atlas) = playing(AnimationClip::new(0, 1, 10).with_hold(0.5));
+        assert!(matches!(
+            step_frame(&mut config, &mut atlas),
+            FrameStep::Advanced
+        ));
+        assert!(matches!(
+            step_frame(&mut config, &mut atlas),
+            FrameStep::Holding
+        ));
+        assert_eq!(atlas.index, 1);
+        assert_eq!(config.frame_timer.duration(), Duration::from_secs_f32(0.5));
+        assert!(matches!(
+            step_frame(&mut config, &mut atlas),
+            FrameStep::Finished
+        ));
+        assert_eq!(atlas.index, 0);
+    }
+
+    #[test]
+    fn frame_durations_override_the_frame_rate() {
+        let (config, _) = playing(AnimationClip::new(0, 2, 10).with_duration(1, 0.25));
+        assert_eq!(config.frame_duration(0), Duration::from_secs_f32(0.1));
+        assert_eq!(config.frame_duration(1), Duration::from_secs_f32(0.25));
+    }
+
+    #[test]
+    fn restart_transition_switches_right_away() {
+        let (mut config, mut atlas) = playing(AnimationClip::new(0, 3, 10));
+        step_frame(&mut config, &mut atlas);
+        let next = AnimationClip::new(10, 13, 10).with_name("next");
+        config.transition_to(&next, TransitionRule::Restart, true, &mut atlas);
+        assert_eq!(config.clip_name, "next");
+        assert_eq!(atlas.index, 10);
+        assert!(config.is_playing);
+    }
+
+    #[test]
+    fn finish_frame_transition_waits_for_the_frame() {
+        let (mut config, mut atlas) = playing(AnimationClip::new(0, 3, 10));
+        step_frame(&mut config, &mut atlas);
+        let next = AnimationClip::new(10, 13, 10).with_name("next");
+        config.transition_to(&next, TransitionRule::FinishFrame, true, &mut atlas);
+        assert_eq!(atlas.index, 1);
+        assert!(config.pending.is_some());
+        assert!(matches!(
+            step_frame(&mut config, &mut atlas),
+            FrameStep::Switched
+        ));
+        assert_eq!(config.clip_name, "next");
+        assert_eq!(atlas.index, 10);
+    }
+
+    #[test]
+    fn proportional_transition_keeps_the_position() {
+        let (mut config, mut atlas) = playing(AnimationClip::new(0, 3, 10));
+        step_frame(&mut config, &mut atlas);
+        step_frame(&mut config, &mut atlas);
+        let next = AnimationClip::new(10, 13, 10).with_name("next");
+        config.transition_to(&next, TransitionRule::Proportional, true, &mut atlas);
+        assert_eq!(atlas.index, 12);
+    }
+}
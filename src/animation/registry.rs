@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use super::{
+    AnimationClips, AnimationSheet, AnimationSheetHandle, AnimationTransitions, animated_sprite,
+};
+
+// Every animation sheet the game knows about, by name, so spawners don't each load their own
+#[derive(Resource, Default)]
+pub struct AnimationRegistry {
+    sheets: HashMap<String, Handle<AnimationSheet>>,
+}
+
+impl AnimationRegistry {
+    pub fn load(&mut self, asset_server: &AssetServer, name: impl Into<String>, path: &str) {
+        self.insert(name, asset_server.load(path.to_owned()));
+    }
+
+    pub fn insert(&mut self, name: impl Into<String>, sheet: Handle<AnimationSheet>) {
+        self.sheets.insert(name.into(), sheet);
+    }
+
+    pub fn handle(&self, name: &str) -> Option<&Handle<AnimationSheet>> {
+        self.sheets.get(name)
+    }
+
+    pub fn handles(&self) -> impl Iterator<Item = &Handle<AnimationSheet>> {
+        self.sheets.values()
+    }
+
+    pub fn get<'a>(
+        &self,
+        sheets: &'a Assets<AnimationSheet>,
+        name: &str,
+    ) -> Option<&'a AnimationSheet> {
+        sheets.get(self.handle(name)?)
+    }
+
+    pub fn is_loaded(&self, sheets: &Assets<AnimationSheet>, name: &str) -> bool {
+        self.get(sheets, name).is_some()
+    }
+
+    // Sprite, playback state and clip library for an entity showing `clip` from sheet `name`
+    pub fn bundle(
+        &self,
+        sheets: &Assets<AnimationSheet>,
+        name: &str,
+        clip: &str,
+    ) -> Option<impl Bundle + use<>> {
+        let handle = self.handle(name)?;
+        let sheet = sheets.get(handle)?;
+        Some((
+            animated_sprite(sheet, &sheet.clip(clip)?),
+            AnimationClips::from_sheet(sheet),
+            AnimationTransitions::from_sheet(sheet),
+            AnimationSheetHandle(handle.clone()),
+        ))
+    }
+}
+
+pub trait SpawnAnimatedExt {
+    // Spawns an entity showing `clip` from the registered sheet `name`. The sheet has to be loaded
+    // by the time commands are applied, otherwise the entity is left without animation.
+    fn spawn_animated(&mut self, name: &str, clip: &str) -> EntityCommands<'_>;
+}
+
+impl SpawnAnimatedExt for Commands<'_, '_> {
+    fn spawn_animated(&mut self, name: &str, clip: &str) -> EntityCommands<'_> {
+        let (name, clip) = (name.to_owned(), clip.to_owned());
+        let mut entity = self.spawn_empty();
+        entity.queue(move |mut entity: EntityWorldMut| {
+            let bundle = entity.world_scope(|world| {
+                world.resource::<AnimationRegistry>().bundle(
+                    world.resource::<Assets<AnimationSheet>>(),
+                    &name,
+                    &clip,
+                )
+            });
+            match bundle {
+                Some(bundle) => {
+                    entity.insert(bundle);
+                }
+                None => warn!("animation sheet {name:?} isn't loaded or has no clip {clip:?}"),
+            }
+        });
+        entity
+    }
+}
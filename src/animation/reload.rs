@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+
+use super::{AnimationClips, AnimationConfig, AnimationSheet, AnimationTransitions};
+
+// The sheet an entity's animation came from, so it can be rebuilt when the sheet changes on disk
+#[derive(Component, Clone, Debug)]
+pub struct AnimationSheetHandle(pub Handle<AnimationSheet>);
+
+// Everything about an animated entity that comes from its sheet
+type Reloadable = (
+    Entity,
+    &'static AnimationSheetHandle,
+    &'static mut AnimationConfig,
+    &'static mut AnimationClips,
+    Option<&'static mut AnimationTransitions>,
+    &'static mut Sprite,
+);
+
+// Needs the `dev` feature (bevy's `file_watcher`) for changes on disk to be picked up
+pub(super) fn reload_modified_sheets(
+    mut events: EventReader<AssetEvent<AnimationSheet>>,
+    sheets: Res<Assets<AnimationSheet>>,
+    mut query: Query<Reloadable>,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        let Some(sheet) = sheets.get(*id) else {
+            continue;
+        };
+        for (entity, handle, mut config, mut clips, transitions, mut sprite) in &mut query {
+            if handle.0.id() != *id {
+                continue;
+            }
+            sprite.image = sheet.texture.clone();
+            clips.replace_from_sheet(sheet);
+            if let Some(mut transitions) = transitions {
+                *transitions = AnimationTransitions::from_sheet(sheet);
+            }
+
+            let Some(atlas) = &mut sprite.texture_atlas else {
+                continue;
+            };
+            atlas.layout = sheet.layout.clone();
+            // Pick up new frame ranges and timings without restarting the clip
+            if let Some(clip) = sheet.clip(&config.clip_name) {
+                let is_playing = config.is_playing;
+                config.set_clip(&clip);
+                config.is_playing = is_playing;
+                atlas.index = atlas
+                    .index
+                    .clamp(config.first_sprite_index, config.last_sprite_index);
+            }
+            info!("reloaded animation sheet of {entity}");
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::AnimationClip;
+use super::reload::AnimationSheetHandle;
+use super::transition::{TransitionDef, TransitionRule};
+
+// A sprite sheet together with the clips that can be played from it
+#[derive(Asset, TypePath, Debug)]
+pub struct AnimationSheet {
+    pub texture: Handle<Image>,
+    pub layout: Handle<TextureAtlasLayout>,
+    pub clips: HashMap<String, AnimationClip>,
+    // Atlas index of each named frame, for atlases that name their frames
+    pub frame_names: HashMap<String, usize>,
+    // Sprite anchor of each frame that had its transparent border trimmed off when packed, so it's
+    // drawn where it was in the untrimmed frame. Keyed by atlas index, frames not in here are
+    // drawn centered.
+    pub frame_anchors: HashMap<usize, Vec2>,
+    pub default_transition: TransitionRule,
+    pub transitions: Vec<TransitionDef>,
+}
+
+impl AnimationSheet {
+    pub fn clip(&self, name: &str) -> Option<AnimationClip> {
+        self.clips.get(name).cloned()
+    }
+
+    // Size of the first cell of the atlas, which is the size of every cell for grid sheets
+    pub fn cell_size(&self, layouts: &Assets<TextureAtlasLayout>) -> Option<Vec2> {
+        let layout = layouts.get(&self.layout)?;
+        Some(layout.textures.first()?.size().as_vec2())
+    }
+
+    pub fn frame_index(&self, name: &str) -> Option<usize> {
+        self.frame_names.get(name).copied()
+    }
+
+    // A clip running from one named frame to another, e.g. "jump_01.png" to "jump_06.png"
+    pub fn clip_between(&self, first: &str, last: &str, fps: u8) -> Option<AnimationClip> {
+        Some(AnimationClip::new(
+            self.frame_index(first)?,
+            self.frame_index(last)?,
+            fps,
+        ))
+    }
+}
+
+// Keeps trimmed frames in place as the atlas index changes
+pub(super) fn apply_frame_anchors(
+    sheets: Res<Assets<AnimationSheet>>,
+    mut query: Query<(&AnimationSheetHandle, &mut Sprite)>,
+) {
+    for (handle, mut sprite) in &mut query {
+        let Some(sheet) = sheets.get(&handle.0) else {
+            continue;
+        };
+        let Some(atlas) = &sprite.texture_atlas else {
+            continue;
+        };
+        if sheet.frame_anchors.is_empty() {
+            continue;
+        }
+        let anchor = match sheet.frame_anchors.get(&atlas.index) {
+            // Mirroring flips the image but not the anchor
+            Some(&anchor) => Anchor::Custom(Vec2::new(
+                if sprite.flip_x { -anchor.x } else { anchor.x },
+                if sprite.flip_y { -anchor.y } else { anchor.y },
+            )),
+            None => Anchor::Center,
+        };
+        if sprite.anchor != anchor {
+            sprite.anchor = anchor;
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AnimationSheetDef {
+    texture: String,
+    grid: GridDef,
+    clips: HashMap<String, AnimationClip>,
+    #[serde(default)]
+    default_transition: TransitionRule,
+    #[serde(default)]
+    transitions: Vec<TransitionDef>,
+}
+
+#[derive(Deserialize)]
+struct GridDef {
+    tile_size: (u32, u32),
+    columns: u32,
+    rows: u32,
+    #[serde(default)]
+    padding: Option<(u32, u32)>,
+    #[serde(default)]
+    offset: Option<(u32, u32)>,
+}
+
+impl GridDef {
+    fn to_layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(
+            self.tile_size.into(),
+            self.columns,
+            self.rows,
+            self.padding.map(UVec2::from),
+            self.offset.map(UVec2::from),
+        )
+    }
+}
+
+#[derive(Default)]
+pub struct AnimationSheetLoader;
+
+#[derive(Debug, Error)]
+pub enum AnimationSheetLoaderError {
+    #[error("could not read animation sheet: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse animation sheet: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("clip {name:?} runs from frame {first} to {last}, but the sheet has {frames} frames")]
+    ClipOutOfRange {
+        name: String,
+        first: usize,
+        last: usize,
+        frames: usize,
+    },
+}
+
+// A clip past the end of the grid would only show up as wrong or empty frames once it's played
+fn check_clip_range(clip: &AnimationClip, frames: usize) -> Result<(), AnimationSheetLoaderError> {
+    if clip.first <= clip.last && clip.last < frames {
+        return Ok(());
+    }
+    Err(AnimationSheetLoaderError::ClipOutOfRange {
+        name: clip.name.clone(),
+        first: clip.first,
+        last: clip.last,
+        frames,
+    })
+}
+
+impl AssetLoader for AnimationSheetLoader {
+    type Asset = AnimationSheet;
+    type Settings = ();
+    type Error = AnimationSheetLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let mut def: AnimationSheetDef = ron::de::from_bytes(&bytes)?;
+        let frames = (def.grid.columns * def.grid.rows) as usize;
+        for (name, clip) in &mut def.clips {
+            clip.name.clone_from(name);
+            check_clip_range(clip, frames)?;
+        }
+
+        let texture = load_context.load(def.texture);
+        let layout = load_context.add_labeled_asset("layout".into(), def.grid.to_layout());
+        Ok(AnimationSheet {
+            texture,
+            layout,
+            clips: def.clips,
+            frame_names: HashMap::new(),
+            frame_anchors: HashMap::new(),
+            default_transition: def.default_transition,
+            transitions: def.transitions,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip(first: usize, last: usize) -> AnimationClip {
+        AnimationClip::new(first, last, 10).with_name("walk")
+    }
+
+    #[test]
+    fn clips_within_the_grid_load() {
+        assert!(check_clip_range(&clip(0, 5), 6).is_ok());
+        assert!(check_clip_range(&clip(3, 3), 6).is_ok());
+    }
+
+    #[test]
+    fn clips_past_the_last_frame_fail() {
+        assert!(matches!(
+            check_clip_range(&clip(2, 6), 6),
+            Err(AnimationSheetLoaderError::ClipOutOfRange { frames: 6, .. })
+        ));
+    }
+
+    #[test]
+    fn reversed_clips_fail() {
+        assert!(matches!(
+            check_clip_range(&clip(4, 2), 6),
+            Err(AnimationSheetLoaderError::ClipOutOfRange { .. })
+        ));
+    }
+}
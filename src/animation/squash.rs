@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+use crate::accessibility::{Accessibility, reduced_motion};
+use crate::movement::Velocity;
+
+// Procedural squash when an entity stops and stretch when it starts moving, applied to its
+// `Transform` scale on top of whatever the sprite animation shows
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct SquashStretch {
+    // How much to stretch along the direction of movement when starting (0.15 = 15%)
+    pub stretch: f32,
+    // How much to squash vertically when stopping
+    pub squash: f32,
+    // Seconds to spring back to the normal shape
+    pub recovery: f32,
+    kick: Vec2,
+    elapsed: f32,
+    applied: Vec2,
+    was_moving: bool,
+}
+
+impl Default for SquashStretch {
+    fn default() -> Self {
+        Self {
+            stretch: 0.15,
+            squash: 0.2,
+            recovery: 0.15,
+            kick: Vec2::ONE,
+            elapsed: 0.0,
+            applied: Vec2::ONE,
+            was_moving: false,
+        }
+    }
+}
+
+impl SquashStretch {
+    // Deforms the scale by `amount` (1.0 = unchanged) and lets it spring back from there
+    pub fn kick(&mut self, amount: Vec2) {
+        self.kick = amount;
+        self.elapsed = 0.0;
+    }
+
+    pub fn stretch_along(&mut self, direction: Vec2) {
+        let amount = if direction.x.abs() >= direction.y.abs() {
+            Vec2::new(1.0 + self.stretch, 1.0 - self.stretch)
+        } else {
+            Vec2::new(1.0 - self.stretch, 1.0 + self.stretch)
+        };
+        self.kick(amount);
+    }
+
+    // Pressed together along `direction`, like when pushing off something that way
+    pub fn squash_along(&mut self, direction: Vec2) {
+        let amount = if direction.x.abs() >= direction.y.abs() {
+            Vec2::new(1.0 - self.squash, 1.0 + self.squash)
+        } else {
+            Vec2::new(1.0 + self.squash, 1.0 - self.squash)
+        };
+        self.kick(amount);
+    }
+
+    pub fn squash(&mut self) {
+        self.kick(Vec2::new(1.0 + self.squash, 1.0 - self.squash));
+    }
+
+    fn current(&self) -> Vec2 {
+        let t = (self.elapsed / self.recovery.max(f32::EPSILON)).clamp(0.0, 1.0);
+        // Ease out so most of the recovery happens right away
+        let eased = 1.0 - (1.0 - t).powi(3);
+        self.kick.lerp(Vec2::ONE, eased)
+    }
+}
+
+pub(super) fn squash_and_stretch(
+    time: Res<Time>,
+    accessibility: Option<Res<Accessibility>>,
+    mut query: Query<(&Velocity, &mut SquashStretch, &mut Transform)>,
+) {
+    // With reduced motion nothing new is kicked off, what's already going springs back as usual
+    let reduced_motion = reduced_motion(accessibility);
+    for (velocity, mut squash, mut transform) in &mut query {
+        let is_moving = velocity.0 != Vec2::ZERO;
+        if !reduced_motion {
+            if is_moving && !squash.was_moving {
+                squash.stretch_along(velocity.0);
+            } else if !is_moving && squash.was_moving {
+                squash.squash();
+            }
+        }
+        squash.was_moving = is_moving;
+        squash.elapsed += time.delta_secs();
+
+        // Undo last frame's deformation before applying this frame's, so other scale changes
+        // survive
+        let factor = squash.current();
+        if factor != squash.applied {
+            let scale = transform.scale.truncate() / squash.applied * factor;
+            transform.scale = scale.extend(transform.scale.z);
+            squash.applied = factor;
+        }
+    }
+}
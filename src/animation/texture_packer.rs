@@ -0,0 +1,255 @@
+use std::collections::HashMap;
+
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::{AnimationClip, AnimationSheet, PlaybackMode, TransitionRule};
+
+// Frame rate given to clips guessed from frame names, TexturePacker doesn't store timing
+const DEFAULT_FPS: u8 = 12;
+
+// Reads TexturePacker's JSON (hash or array) export of a non-uniform atlas into an
+// `AnimationSheet`. Frames named like `walk_01.png`, `walk_02.png` become a "walk" clip. Trimmed
+// frames are drawn where they were before trimming, but rotated frames and the .plist format
+// aren't supported and fail to load.
+#[derive(Default)]
+pub struct TexturePackerLoader;
+
+#[derive(Debug, Error)]
+pub enum TexturePackerLoaderError {
+    #[error("could not read texture packer atlas: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse texture packer atlas: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("{0} is rotated in the texture packer atlas, export it with rotation turned off")]
+    Rotated(String),
+    #[error("texture packer .plist atlases aren't supported, export them as JSON")]
+    Plist,
+}
+
+#[derive(Deserialize)]
+struct PackedAtlas {
+    frames: PackedFrames,
+    meta: PackedMeta,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PackedFrames {
+    Array(Vec<PackedFrame>),
+    Hash(serde_json::Map<String, serde_json::Value>),
+}
+
+#[derive(Deserialize)]
+struct PackedFrame {
+    // Only present in the array format, the hash format uses it as the key
+    #[serde(default)]
+    filename: String,
+    frame: PackedRect,
+    #[serde(default)]
+    rotated: bool,
+    // Whether the transparent border was cut off, leaving `sprite_source_size` of the original
+    // `source_size`
+    #[serde(default)]
+    trimmed: bool,
+    #[serde(default, rename = "spriteSourceSize")]
+    sprite_source_size: Option<PackedRect>,
+    #[serde(default, rename = "sourceSize")]
+    source_size: Option<PackedSize>,
+}
+
+impl PackedFrame {
+    // Where the center of the untrimmed frame is on the trimmed one, as a sprite anchor (y up)
+    fn trim_anchor(&self) -> Option<Vec2> {
+        let (Some(trimmed), Some(source)) = (&self.sprite_source_size, &self.source_size) else {
+            return None;
+        };
+        if !self.trimmed || trimmed.w == 0 || trimmed.h == 0 {
+            return None;
+        }
+        let size = Vec2::new(trimmed.w as f32, trimmed.h as f32);
+        let center = Vec2::new(trimmed.x as f32, trimmed.y as f32) + size / 2.0;
+        let source_center = Vec2::new(source.w as f32, source.h as f32) / 2.0;
+        let offset = (source_center - center) / size;
+        (offset != Vec2::ZERO).then_some(Vec2::new(offset.x, -offset.y))
+    }
+}
+
+#[derive(Deserialize)]
+struct PackedRect {
+    x: u32,
+    y: u32,
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct PackedSize {
+    w: u32,
+    h: u32,
+}
+
+#[derive(Deserialize)]
+struct PackedMeta {
+    image: String,
+    size: PackedSize,
+}
+
+// "walk_01.png" -> "walk"
+fn clip_name(frame_name: &str) -> &str {
+    let stem = frame_name
+        .rsplit_once('.')
+        .map_or(frame_name, |(stem, _)| stem);
+    stem.trim_end_matches(|c: char| c.is_ascii_digit())
+        .trim_end_matches(['_', '-', ' '])
+}
+
+// Groups consecutive frames sharing a name prefix into looping clips
+fn guess_clips(names: &[String]) -> HashMap<String, AnimationClip> {
+    let mut clips: HashMap<String, AnimationClip> = HashMap::new();
+    for (index, name) in names.iter().enumerate() {
+        let name = clip_name(name);
+        if name.is_empty() {
+            continue;
+        }
+        match clips.get_mut(name) {
+            Some(clip) if clip.last + 1 == index => clip.last = index,
+            Some(_) => {}
+            None => {
+                let clip = AnimationClip::new(index, index, DEFAULT_FPS)
+                    .with_name(name)
+                    .with_mode(PlaybackMode::Loop);
+                clips.insert(name.to_owned(), clip);
+            }
+        }
+    }
+    clips
+}
+
+impl AssetLoader for TexturePackerLoader {
+    type Asset = AnimationSheet;
+    type Settings = ();
+    type Error = TexturePackerLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        if load_context
+            .path()
+            .extension()
+            .is_some_and(|extension| extension == "plist")
+        {
+            return Err(TexturePackerLoaderError::Plist);
+        }
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let atlas: PackedAtlas = serde_json::from_slice(&bytes)?;
+
+        let frames = match atlas.frames {
+            PackedFrames::Array(frames) => frames,
+            PackedFrames::Hash(map) => map
+                .into_iter()
+                .map(|(name, value)| {
+                    serde_json::from_value(value).map(|frame: PackedFrame| PackedFrame {
+                        filename: name,
+                        ..frame
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+        };
+
+        let mut layout =
+            TextureAtlasLayout::new_empty(UVec2::new(atlas.meta.size.w, atlas.meta.size.h));
+        let mut names = Vec::with_capacity(frames.len());
+        let mut anchors = HashMap::new();
+        for packed in frames {
+            // Stored turned by 90 degrees, which sprites can't undo
+            if packed.rotated {
+                return Err(TexturePackerLoaderError::Rotated(packed.filename));
+            }
+            let frame = &packed.frame;
+            let index = layout.add_texture(URect::new(
+                frame.x,
+                frame.y,
+                frame.x + frame.w,
+                frame.y + frame.h,
+            ));
+            if let Some(anchor) = packed.trim_anchor() {
+                anchors.insert(index, anchor);
+            }
+            names.push(packed.filename);
+        }
+
+        let clips = guess_clips(&names);
+        let image_path = load_context
+            .path()
+            .parent()
+            .map(|dir| dir.join(&atlas.meta.image))
+            .unwrap_or_else(|| atlas.meta.image.clone().into());
+        let texture = load_context.load(image_path);
+        let layout = load_context.add_labeled_asset("layout".into(), layout);
+        Ok(AnimationSheet {
+            texture,
+            layout,
+            clips,
+            frame_names: names
+                .into_iter()
+                .enumerate()
+                .map(|(index, name)| (name, index))
+                .collect(),
+            frame_anchors: anchors,
+            default_transition: TransitionRule::default(),
+            transitions: Vec::new(),
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas.json", "plist"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| (*name).to_owned()).collect()
+    }
+
+    #[test]
+    fn clip_name_strips_the_extension_and_frame_number() {
+        assert_eq!(clip_name("walk_01.png"), "walk");
+        assert_eq!(clip_name("idle-3"), "idle");
+        assert_eq!(clip_name("jump 10.png"), "jump");
+        assert_eq!(clip_name("sit.png"), "sit");
+        assert_eq!(clip_name("07.png"), "");
+    }
+
+    #[test]
+    fn guess_clips_groups_consecutive_frames() {
+        let clips = guess_clips(&names(&[
+            "idle_01.png",
+            "idle_02.png",
+            "walk_01.png",
+            "walk_02.png",
+            "walk_03.png",
+            "01.png",
+        ]));
+        assert_eq!(clips.len(), 2);
+        assert_eq!((clips["idle"].first, clips["idle"].last), (0, 1));
+        assert_eq!((clips["walk"].first, clips["walk"].last), (2, 4));
+        assert_eq!(clips["walk"].name, "walk");
+        assert_eq!(clips["walk"].mode, PlaybackMode::Loop);
+    }
+
+    #[test]
+    fn guess_clips_keeps_the_first_run_of_a_name() {
+        let clips = guess_clips(&names(&["run_1", "run_2", "hit_1", "run_3"]));
+        assert_eq!((clips["run"].first, clips["run"].last), (0, 1));
+    }
+}
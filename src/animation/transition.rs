@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::{AnimationClip, AnimationConfig, AnimationSheet};
+
+// How to get from the clip currently playing to the next one
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum TransitionRule {
+    // Jump to the first frame of the new clip right away
+    #[default]
+    Restart,
+    // Let the frame on screen run out its time before switching
+    FinishFrame,
+    // Continue the new clip at the same relative position the old one was at
+    Proportional,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct TransitionDef {
+    pub from: String,
+    pub to: String,
+    pub rule: TransitionRule,
+}
+
+// Per source/target clip transition rules, falling back to `default`
+#[derive(Component, Clone, Debug, Default)]
+pub struct AnimationTransitions {
+    pub default: TransitionRule,
+    pub rules: HashMap<(String, String), TransitionRule>,
+}
+
+impl AnimationTransitions {
+    pub fn from_sheet(sheet: &AnimationSheet) -> Self {
+        Self {
+            default: sheet.default_transition,
+            rules: sheet
+                .transitions
+                .iter()
+                .map(|def| ((def.from.clone(), def.to.clone()), def.rule))
+                .collect(),
+        }
+    }
+
+    pub fn rule(&self, from: &str, to: &str) -> TransitionRule {
+        self.rules
+            .get(&(from.to_owned(), to.to_owned()))
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+// A clip waiting for the current frame to finish
+#[derive(Clone, Debug)]
+pub struct PendingClip {
+    pub clip: AnimationClip,
+    pub play: bool,
+}
+
+impl AnimationConfig {
+    // Switches to `clip` following `rule`, leaving it playing or stopped depending on `play`
+    pub fn transition_to(
+        &mut self,
+        clip: &AnimationClip,
+        rule: TransitionRule,
+        play: bool,
+        atlas: &mut TextureAtlas,
+    ) {
+        match rule {
+            TransitionRule::FinishFrame if self.is_playing && !self.is_holding => {
+                self.pending = Some(PendingClip {
+                    clip: clip.clone(),
+                    play,
+                });
+            }
+            TransitionRule::Proportional => {
+                let progress = self.progress(atlas.index);
+                self.set_clip(clip);
+                atlas.index = self.index_at(progress);
+                self.is_playing = play;
+            }
+            _ => self.switch_to(clip, play, atlas),
+        }
+    }
+
+    pub(super) fn switch_to(&mut self, clip: &AnimationClip, play: bool, atlas: &mut TextureAtlas) {
+        self.set_clip(clip);
+        atlas.index = self.start_index();
+        if play {
+            self.restart();
+        } else {
+            self.is_playing = false;
+        }
+    }
+
+    // How far into a single pass `index` is, from 0 up to (but excluding) 1
+    fn progress(&self, index: usize) -> f32 {
+        let len = self.len() as f32;
+        let offset = match self.start_index() {
+            start if start == self.first_sprite_index => index.saturating_sub(start),
+            start => start.saturating_sub(index),
+        };
+        (offset as f32 / len).clamp(0.0, 1.0)
+    }
+
+    fn index_at(&self, progress: f32) -> usize {
+        let offset = ((progress * self.len() as f32) as usize).min(self.len() - 1);
+        match self.start_index() {
+            start if start == self.first_sprite_index => start + offset,
+            start => start - offset,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.last_sprite_index
+            .saturating_sub(self.first_sprite_index)
+            + 1
+    }
+}
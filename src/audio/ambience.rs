@@ -0,0 +1,366 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{AudioBus, Mixer, SoundPool};
+use crate::config;
+use crate::loading::LoadingAssets;
+use crate::state::{GameState, InGame};
+
+const AMBIENCE_FILE: &str = "ambience.ron";
+
+// Seconds between birdsong, and what a one-shot with a broken interval falls back to
+const DEFAULT_INTERVAL: (f32, f32) = (2.0, 7.0);
+
+// Background sounds of the place the cats are in: looping beds like wind or room tone, and
+// one-shots like birdsong dropped in at random times and places. Every scene in
+// config/ambience.ron has its own mix, and `AmbienceScene` says which one is playing.
+pub(super) struct AmbiencePlugin;
+
+impl Plugin for AmbiencePlugin {
+    fn build(&self, app: &mut App) {
+        let mut ambience = config::load_or_default::<AmbienceConfig>(AMBIENCE_FILE);
+        ambience.sanitize();
+        app.insert_resource(ambience)
+            .init_resource::<AmbienceScene>()
+            .add_systems(Startup, load_ambience)
+            .add_systems(OnEnter(InGame), start_ambience)
+            .add_systems(
+                Update,
+                (
+                    start_ambience.run_if(
+                        resource_changed::<AmbienceScene>
+                            .and(not(resource_added::<AmbienceScene>))
+                            .and(in_state(InGame)),
+                    ),
+                    play_one_shots.run_if(in_state(GameState::Playing)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+// Name of the scene in `AmbienceConfig` to play in game
+#[derive(Resource, Clone, Debug, PartialEq, Eq)]
+pub struct AmbienceScene(pub String);
+
+impl Default for AmbienceScene {
+    fn default() -> Self {
+        Self("meadow".to_owned())
+    }
+}
+
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AmbienceConfig {
+    pub scenes: HashMap<String, AmbienceMix>,
+}
+
+impl Default for AmbienceConfig {
+    fn default() -> Self {
+        let wind = AmbienceBed {
+            sound: "ambience/wind.wav".to_owned(),
+            volume: 0.35,
+        };
+        let room_tone = AmbienceBed {
+            sound: "ambience/room-tone.wav".to_owned(),
+            volume: 0.25,
+        };
+        let birds = AmbienceOneShot {
+            sounds: vec![
+                "ambience/bird-1.wav".to_owned(),
+                "ambience/bird-2.wav".to_owned(),
+                "ambience/bird-3.wav".to_owned(),
+            ],
+            interval: DEFAULT_INTERVAL,
+            volume: 0.5,
+            pitch_variation: 0.1,
+        };
+        Self {
+            scenes: HashMap::from([
+                (
+                    "meadow".to_owned(),
+                    AmbienceMix {
+                        beds: vec![wind],
+                        one_shots: vec![birds],
+                    },
+                ),
+                (
+                    "indoors".to_owned(),
+                    AmbienceMix {
+                        beds: vec![room_tone],
+                        one_shots: Vec::new(),
+                    },
+                ),
+            ]),
+        }
+    }
+}
+
+impl AmbienceConfig {
+    // The file is edited by hand, and timers and random ranges panic on negative or NaN seconds,
+    // so anything that can't be played is replaced with a warning
+    fn sanitize(&mut self) {
+        for (name, mix) in &mut self.scenes {
+            for bed in &mut mix.beds {
+                bed.volume = volume(name, bed.volume);
+            }
+            for one_shot in &mut mix.one_shots {
+                let interval = one_shot.interval;
+                let valid = |seconds: f32| seconds.is_finite() && seconds >= 0.0;
+                if !(valid(interval.0) && valid(interval.1) && interval.1 > 0.0) {
+                    let fallback = DEFAULT_INTERVAL;
+                    warn!(
+                        "{AMBIENCE_FILE}: {name}: invalid interval {interval:?}, using {fallback:?}"
+                    );
+                    one_shot.interval = fallback;
+                }
+                one_shot.volume = volume(name, one_shot.volume);
+                // Playback speeds are 1 ± the variation, which has to stay above 0
+                let variation = one_shot.pitch_variation;
+                if !(variation.is_finite() && (0.0..1.0).contains(&variation)) {
+                    warn!("{AMBIENCE_FILE}: {name}: invalid pitch variation {variation}, using 0");
+                    one_shot.pitch_variation = 0.0;
+                }
+            }
+        }
+    }
+}
+
+fn volume(scene: &str, volume: f32) -> f32 {
+    if volume.is_finite() && volume >= 0.0 {
+        return volume;
+    }
+    warn!("{AMBIENCE_FILE}: {scene}: invalid volume {volume}, muting it");
+    0.0
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AmbienceMix {
+    pub beds: Vec<AmbienceBed>,
+    pub one_shots: Vec<AmbienceOneShot>,
+}
+
+// A sound looping for as long as the scene plays
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AmbienceBed {
+    pub sound: String,
+    pub volume: f32,
+}
+
+// Sounds played one at a time, somewhere on screen, every so often
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AmbienceOneShot {
+    pub sounds: Vec<String>,
+    // Shortest and longest seconds between two of them
+    pub interval: (f32, f32),
+    pub volume: f32,
+    pub pitch_variation: f32,
+}
+
+impl AmbienceOneShot {
+    fn next_delay(&self) -> f32 {
+        let (shortest, longest) = self.interval;
+        rand::thread_rng().gen_range(shortest..=longest.max(shortest))
+    }
+}
+
+// Every sound any scene uses, by path, loaded behind the loading screen
+#[derive(Resource, Default)]
+struct AmbienceSounds(HashMap<String, Handle<AudioSource>>);
+
+// Drops in one of a scene's one-shots whenever its timer runs out. Virtual time, so birds keep
+// quiet while the game is paused.
+#[derive(Component)]
+struct OneShotSchedule {
+    pool: SoundPool,
+    one_shot: AmbienceOneShot,
+    timer: Timer,
+}
+
+// Everything of the scene that's playing, so it can be swapped for another
+#[derive(Component)]
+struct AmbienceSound;
+
+fn load_ambience(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    ambience: Res<AmbienceConfig>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let mut sounds = HashMap::new();
+    let paths = ambience.scenes.values().flat_map(|mix| {
+        mix.beds
+            .iter()
+            .map(|bed| &bed.sound)
+            .chain(mix.one_shots.iter().flat_map(|one_shot| &one_shot.sounds))
+    });
+    for path in paths {
+        sounds.entry(path.clone()).or_insert_with(|| {
+            let sound: Handle<AudioSource> = asset_server.load(path.clone());
+            loading.track(sound.clone());
+            sound
+        });
+    }
+    commands.insert_resource(AmbienceSounds(sounds));
+}
+
+fn start_ambience(
+    mut commands: Commands,
+    ambience: Res<AmbienceConfig>,
+    scene: Res<AmbienceScene>,
+    sounds: Res<AmbienceSounds>,
+    mixer: Mixer,
+    playing: Query<Entity, With<AmbienceSound>>,
+) {
+    for entity in &playing {
+        commands.entity(entity).despawn();
+    }
+    let Some(mix) = ambience.scenes.get(&scene.0) else {
+        warn!("{AMBIENCE_FILE}: no scene called {:?}", scene.0);
+        return;
+    };
+    for bed in &mix.beds {
+        let Some(sound) = sounds.0.get(&bed.sound) else {
+            continue;
+        };
+        commands.spawn((
+            AmbienceSound,
+            StateScoped(InGame),
+            Name::new(format!("Ambience {}", bed.sound)),
+            mixer.play_with_gain(
+                AudioBus::Effects,
+                sound.clone(),
+                PlaybackSettings::LOOP,
+                bed.volume,
+            ),
+        ));
+    }
+    for one_shot in &mix.one_shots {
+        let pool = SoundPool::new(
+            one_shot
+                .sounds
+                .iter()
+                .filter_map(|path| sounds.0.get(path).cloned()),
+        )
+        .with_gain(one_shot.volume)
+        .with_pitch_variation(one_shot.pitch_variation);
+        commands.spawn((
+            AmbienceSound,
+            StateScoped(InGame),
+            Name::new("Ambience one-shots"),
+            OneShotSchedule {
+                pool,
+                timer: Timer::from_seconds(one_shot.next_delay(), TimerMode::Once),
+                one_shot: one_shot.clone(),
+            },
+        ));
+    }
+}
+
+fn play_one_shots(
+    mut commands: Commands,
+    time: Res<Time>,
+    mixer: Mixer,
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut schedules: Query<&mut OneShotSchedule>,
+) {
+    let mut rng = rand::thread_rng();
+    for mut schedule in &mut schedules {
+        if !schedule.timer.tick(time.delta()).finished() || schedule.pool.samples().is_empty() {
+            continue;
+        }
+        let delay = schedule.one_shot.next_delay();
+        schedule.timer = Timer::from_seconds(delay, TimerMode::Once);
+        // Anywhere across the window, in its upper half where birds would be
+        let half_size = window.size() / 2.0;
+        let position = Vec2::new(
+            rng.gen_range(-half_size.x..=half_size.x),
+            rng.gen_range(0.0..=half_size.y),
+        );
+        commands.spawn((
+            AmbienceSound,
+            StateScoped(InGame),
+            Name::new("Ambience one-shot"),
+            mixer.play_at_position(AudioBus::Effects, &schedule.pool, position),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn one_shot(interval: (f32, f32), volume: f32, pitch_variation: f32) -> AmbienceOneShot {
+        AmbienceOneShot {
+            sounds: Vec::new(),
+            interval,
+            volume,
+            pitch_variation,
+        }
+    }
+
+    // `one_shot` and a bed at `volume` as the only scene, sanitized
+    fn sanitized(one_shot: AmbienceOneShot, volume: f32) -> AmbienceMix {
+        let bed = AmbienceBed {
+            sound: String::new(),
+            volume,
+        };
+        let mut config = AmbienceConfig {
+            scenes: HashMap::from([(
+                "meadow".to_owned(),
+                AmbienceMix {
+                    beds: vec![bed],
+                    one_shots: vec![one_shot],
+                },
+            )]),
+        };
+        config.sanitize();
+        config.scenes.remove("meadow").unwrap()
+    }
+
+    #[test]
+    fn sanitize_keeps_a_playable_mix() {
+        let mix = sanitized(one_shot((0.0, 3.0), 0.5, 0.2), 0.3);
+        assert_eq!(mix.beds[0].volume, 0.3);
+        let one_shot = &mix.one_shots[0];
+        assert_eq!(one_shot.interval, (0.0, 3.0));
+        assert_eq!(one_shot.volume, 0.5);
+        assert_eq!(one_shot.pitch_variation, 0.2);
+    }
+
+    #[test]
+    fn sanitize_replaces_intervals_timers_cant_run_on() {
+        for interval in [
+            (-1.0, 3.0),
+            (1.0, -3.0),
+            (f32::NAN, 3.0),
+            (1.0, f32::INFINITY),
+            (0.0, 0.0),
+        ] {
+            let mix = sanitized(one_shot(interval, 0.5, 0.1), 0.3);
+            assert_eq!(mix.one_shots[0].interval, DEFAULT_INTERVAL, "{interval:?}");
+        }
+    }
+
+    #[test]
+    fn sanitize_mutes_volumes_that_are_not_a_gain() {
+        for volume in [-0.5, f32::NAN, f32::INFINITY] {
+            let mix = sanitized(one_shot(DEFAULT_INTERVAL, volume, 0.1), volume);
+            assert_eq!(mix.beds[0].volume, 0.0, "{volume}");
+            assert_eq!(mix.one_shots[0].volume, 0.0, "{volume}");
+        }
+    }
+
+    #[test]
+    fn sanitize_drops_pitch_variations_that_are_not_a_range() {
+        for variation in [-0.1, 1.0, f32::NAN] {
+            let mix = sanitized(one_shot(DEFAULT_INTERVAL, 0.5, variation), 0.3);
+            assert_eq!(mix.one_shots[0].pitch_variation, 0.0, "{variation}");
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use bevy::audio::Volume;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use super::{AudioSettings, SPATIAL_SCALE, SoundPool};
+
+// Which volume a sound plays at. Everything that makes a noise is started through `Mixer`, which
+// tags it with its bus, so turning a bus up or down reaches sounds that are already playing.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum AudioBus {
+    Music,
+    Effects,
+    Ui,
+}
+
+// A sound's own gain on top of its bus, e.g. one stem of the music fading in and out
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct SoundGain(pub f32);
+
+// Gains on top of the player's volumes that the game itself changes, e.g. to duck the music while
+// something important plays. 1 leaves a bus as the settings have it.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct BusGains {
+    pub music: f32,
+    pub effects: f32,
+    pub ui: f32,
+}
+
+impl Default for BusGains {
+    fn default() -> Self {
+        Self {
+            music: 1.0,
+            effects: 1.0,
+            ui: 1.0,
+        }
+    }
+}
+
+impl BusGains {
+    pub fn get(&self, bus: AudioBus) -> f32 {
+        match bus {
+            AudioBus::Music => self.music,
+            AudioBus::Effects => self.effects,
+            AudioBus::Ui => self.ui,
+        }
+    }
+
+    pub fn get_mut(&mut self, bus: AudioBus) -> &mut f32 {
+        match bus {
+            AudioBus::Music => &mut self.music,
+            AudioBus::Effects => &mut self.effects,
+            AudioBus::Ui => &mut self.ui,
+        }
+    }
+}
+
+// Starts sounds on a bus: `commands.spawn((Name::new("Meow"), mixer.play(AudioBus::Effects, meow,
+// PlaybackSettings::DESPAWN)))`
+#[derive(SystemParam)]
+pub struct Mixer<'w> {
+    settings: Res<'w, AudioSettings>,
+    gains: Res<'w, BusGains>,
+}
+
+impl Mixer<'_> {
+    pub fn volume(&self, bus: AudioBus) -> Volume {
+        self.volume_with_gain(bus, 1.0)
+    }
+
+    pub fn volume_with_gain(&self, bus: AudioBus, gain: f32) -> Volume {
+        bus_volume(&self.settings, &self.gains, bus, gain)
+    }
+
+    pub fn play(
+        &self,
+        bus: AudioBus,
+        sound: Handle<AudioSource>,
+        playback: PlaybackSettings,
+    ) -> impl Bundle + use<> {
+        (
+            bus,
+            AudioPlayer::new(sound),
+            playback.with_volume(self.volume(bus)),
+        )
+    }
+
+    // A sound that keeps its own `SoundGain` under the bus volume
+    pub fn play_with_gain(
+        &self,
+        bus: AudioBus,
+        sound: Handle<AudioSource>,
+        playback: PlaybackSettings,
+        gain: f32,
+    ) -> impl Bundle + use<> {
+        (
+            bus,
+            SoundGain(gain),
+            AudioPlayer::new(sound),
+            playback.with_volume(self.volume_with_gain(bus, gain)),
+        )
+    }
+
+    // A random take from `pool`, see `SoundPool`
+    pub fn play_from(
+        &self,
+        bus: AudioBus,
+        pool: &SoundPool,
+        playback: PlaybackSettings,
+    ) -> impl Bundle + use<> {
+        let (sample, speed, gain) = pool.pick();
+        self.play_with_gain(bus, sample, playback.with_speed(speed), gain)
+    }
+
+    // A one-off take from `pool`, panned and attenuated by where `emitter` is relative to the
+    // camera. It rides along as a child, so a walking cat's steps follow it.
+    pub fn play_at(&self, bus: AudioBus, pool: &SoundPool, emitter: Entity) -> impl Bundle + use<> {
+        (
+            self.play_from(bus, pool, spatial_one_shot()),
+            Transform::default(),
+            ChildOf(emitter),
+        )
+    }
+
+    // Like `play_at`, for a sound that comes from a spot in the world rather than an entity
+    pub fn play_at_position(
+        &self,
+        bus: AudioBus,
+        pool: &SoundPool,
+        position: Vec2,
+    ) -> impl Bundle + use<> {
+        (
+            self.play_from(bus, pool, spatial_one_shot()),
+            Transform::from_translation(position.extend(0.0)),
+        )
+    }
+}
+
+fn spatial_one_shot() -> PlaybackSettings {
+    PlaybackSettings::DESPAWN
+        .with_spatial(true)
+        .with_spatial_scale(SPATIAL_SCALE)
+}
+
+fn bus_volume(settings: &AudioSettings, gains: &BusGains, bus: AudioBus, gain: f32) -> Volume {
+    if settings.muted {
+        Volume::SILENT
+    } else {
+        Volume::Linear(settings.master * settings.level(bus) * gains.get(bus) * gain)
+    }
+}
+
+// Sinks only exist once a sound has started, until then the volume it was spawned with applies
+pub(super) fn apply_bus_volumes(
+    settings: Res<AudioSettings>,
+    gains: Res<BusGains>,
+    mut sinks: Query<(&AudioBus, Option<&SoundGain>, &mut AudioSink)>,
+    mut spatial_sinks: Query<(&AudioBus, Option<&SoundGain>, &mut SpatialAudioSink)>,
+) {
+    let gain = |sound_gain: Option<&SoundGain>| sound_gain.map_or(1.0, |gain| gain.0);
+    for (bus, sound_gain, mut sink) in &mut sinks {
+        sink.set_volume(bus_volume(&settings, &gains, *bus, gain(sound_gain)));
+    }
+    for (bus, sound_gain, mut sink) in &mut spatial_sinks {
+        sink.set_volume(bus_volume(&settings, &gains, *bus, gain(sound_gain)));
+    }
+}
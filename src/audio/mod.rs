@@ -0,0 +1,175 @@
+use bevy::audio::SpatialScale;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::animation::{AnimationFrameEvent, PlayAnimation};
+use crate::health::DamageTaken;
+use crate::input::{ActionState, CatAction};
+use crate::localization::Localization;
+use crate::movement::HitBoundary;
+use crate::notifications::Notify;
+use crate::settings::Settings;
+
+mod ambience;
+mod mixer;
+mod music;
+mod pool;
+mod sound;
+
+pub use ambience::{AmbienceBed, AmbienceConfig, AmbienceMix, AmbienceOneShot, AmbienceScene};
+pub use mixer::{AudioBus, BusGains, Mixer, SoundGain};
+pub use music::{LayerMix, MusicDirector, MusicLayer, MusicMood, MusicTrack};
+pub use pool::SoundPool;
+pub use sound::{AudioRegistry, PlaySound, SoundId, SoundPosition};
+
+// World units to audio units: sounds half a screen away are about as loud as they get quieter
+const SPATIAL_SCALE: SpatialScale = SpatialScale::new_2d(1.0 / 640.0);
+// World units between the listener's ears, wide enough for a clear pan across the screen
+const EAR_GAP: f32 = 400.0;
+// Name of the frame markers in the walk and run clips that land a paw
+const FOOTSTEP_MARKER: &str = "footstep";
+
+// Background music, ambience and sound effects: a meow whenever a cat starts its UIA spin, soft
+// pads on the walk cycle frames where a paw touches the ground, a bump at the window edges and on
+// every hit, and a click for pressed buttons. Everything else asks for sounds with a `PlaySound`
+// event. Effects come from where their cat is, as heard by a listener on the camera. Every sound
+// plays on one of the `AudioBus`es, whose volumes come from the `AudioSettings` in the settings
+// file and apply to whatever is already playing.
+pub struct AudioPlugin;
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AudioSettings>()
+            .init_resource::<BusGains>()
+            .register_type::<AudioSettings>()
+            .register_type::<BusGains>()
+            .register_type::<AudioBus>()
+            .register_type::<SoundGain>()
+            .register_type::<SoundId>()
+            .add_event::<PlaySound>()
+            .add_plugins((music::MusicPlugin, ambience::AmbiencePlugin))
+            .add_systems(Startup, sound::load_sounds)
+            .add_systems(
+                Update,
+                (
+                    toggle_mute,
+                    sync_audio_settings.run_if(resource_changed::<Settings>),
+                    mixer::apply_bus_volumes
+                        .run_if(resource_changed::<AudioSettings>.or(resource_changed::<BusGains>)),
+                )
+                    .chain(),
+            )
+            .add_systems(Update, (add_listener, play_footsteps, click_buttons))
+            .add_systems(PostUpdate, sound::play_sounds)
+            .add_observer(meow_on_uia)
+            .add_observer(bump_on_boundary)
+            .add_observer(bump_on_damage);
+    }
+}
+
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct AudioSettings {
+    // Volumes from 0 to 1, the ones for each bus are scaled by the master volume
+    pub master: f32,
+    pub music: f32,
+    pub effects: f32,
+    pub ui: f32,
+    // Silences everything without touching the volumes
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 1.0,
+            music: 0.8,
+            effects: 0.8,
+            ui: 0.6,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    // The bus's own volume, before the master volume and muting
+    pub fn level(&self, bus: AudioBus) -> f32 {
+        match bus {
+            AudioBus::Music => self.music,
+            AudioBus::Effects => self.effects,
+            AudioBus::Ui => self.ui,
+        }
+    }
+}
+
+fn add_listener(mut commands: Commands, cameras: Query<Entity, Added<Camera2d>>) {
+    for camera in &cameras {
+        commands
+            .entity(camera)
+            .insert(SpatialListener::new(EAR_GAP));
+    }
+}
+
+// Goes through the settings like the Mute row does, so the choice is saved and every bus follows
+fn toggle_mute(
+    actions: Res<ActionState>,
+    mut settings: ResMut<Settings>,
+    localization: Res<Localization>,
+    mut notify: EventWriter<Notify>,
+) {
+    if !actions.just_pressed(CatAction::ToggleMute) {
+        return;
+    }
+    settings.audio.muted = !settings.audio.muted;
+    let key = if settings.audio.muted {
+        "toast-muted"
+    } else {
+        "toast-unmuted"
+    };
+    notify.write(Notify::new(localization.get(key)));
+}
+
+fn sync_audio_settings(settings: Res<Settings>, mut audio: ResMut<AudioSettings>) {
+    audio.set_if_neq(settings.audio);
+}
+
+fn meow_on_uia(trigger: Trigger<PlayAnimation>, mut sounds: EventWriter<PlaySound>) {
+    if trigger.event().clip == "uia" {
+        sounds.write(PlaySound::new(SoundId::Meow).on(trigger.target()));
+    }
+}
+
+fn bump_on_boundary(trigger: Trigger<HitBoundary>, mut sounds: EventWriter<PlaySound>) {
+    sounds.write(PlaySound::new(SoundId::Bump).on(trigger.target()));
+}
+
+fn bump_on_damage(trigger: Trigger<DamageTaken>, mut sounds: EventWriter<PlaySound>) {
+    sounds.write(PlaySound::new(SoundId::Bump).on(trigger.target()));
+}
+
+// Driven by the clips' frame markers rather than a timer, so steps stay on the paws whatever the
+// animation speed
+fn play_footsteps(
+    mut frame_events: EventReader<AnimationFrameEvent>,
+    mut sounds: EventWriter<PlaySound>,
+) {
+    for event in frame_events
+        .read()
+        .filter(|event| event.name == FOOTSTEP_MARKER)
+    {
+        sounds.write(PlaySound::new(SoundId::Footstep).on(event.entity));
+    }
+}
+
+fn click_buttons(
+    buttons: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+    mut sounds: EventWriter<PlaySound>,
+) {
+    if buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        sounds.write(PlaySound::new(SoundId::Click));
+    }
+}
@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{AudioBus, Mixer, SoundGain};
+use crate::cat::Cat;
+use crate::health::Health;
+use crate::loading::LoadingAssets;
+use crate::settings::Settings;
+use crate::state::{GameState, InGame};
+
+// Below this fraction of its health a cat is in danger, and the music says so
+const DANGER_HEALTH: f32 = 0.3;
+
+// Background music from the main menu on: the director's menu track in the menus and the one
+// picked in the settings in game, crossfading whenever that changes. Each track is split into
+// stems that always play together, and the `MusicDirector` fades them in and out with what's going
+// on: calm in the menus, everything in play, tense when a cat is low on health.
+pub(super) struct MusicPlugin;
+
+impl Plugin for MusicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MusicDirector>()
+            .register_type::<MusicTrack>()
+            .register_type::<MusicDirector>()
+            .add_systems(Startup, load_stems)
+            .add_systems(
+                Update,
+                (
+                    choose_track.run_if(music_allowed),
+                    choose_mood,
+                    fade_tracks,
+                    fade_layers,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+pub enum MusicTrack {
+    #[default]
+    Meadow,
+    Nap,
+}
+
+impl MusicTrack {
+    pub const ALL: [MusicTrack; 2] = [MusicTrack::Meadow, MusicTrack::Nap];
+
+    // Localization key of the track's name
+    pub fn label(self) -> &'static str {
+        match self {
+            MusicTrack::Meadow => "music-meadow",
+            MusicTrack::Nap => "music-nap",
+        }
+    }
+
+    // Where the track's stems are, one file per `MusicLayer`
+    pub fn folder(self) -> &'static str {
+        match self {
+            MusicTrack::Meadow => "music/meadow",
+            MusicTrack::Nap => "music/nap",
+        }
+    }
+
+    pub fn path(self, layer: MusicLayer) -> String {
+        format!("{}/{}", self.folder(), layer.file())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum MusicLayer {
+    // Bass and chords, always there
+    Base,
+    Percussion,
+    Melody,
+}
+
+impl MusicLayer {
+    pub const ALL: [MusicLayer; 3] = [MusicLayer::Base, MusicLayer::Percussion, MusicLayer::Melody];
+
+    fn file(self) -> &'static str {
+        match self {
+            MusicLayer::Base => "base.wav",
+            MusicLayer::Percussion => "percussion.wav",
+            MusicLayer::Melody => "melody.wav",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+pub enum MusicMood {
+    #[default]
+    Menu,
+    Play,
+    Danger,
+}
+
+// How loud each stem plays in a mood, from 0 to 1
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct LayerMix {
+    pub base: f32,
+    pub percussion: f32,
+    pub melody: f32,
+}
+
+impl LayerMix {
+    pub fn get(&self, layer: MusicLayer) -> f32 {
+        match layer {
+            MusicLayer::Base => self.base,
+            MusicLayer::Percussion => self.percussion,
+            MusicLayer::Melody => self.melody,
+        }
+    }
+}
+
+// Picks the track and mood from the game state and fades the music towards them
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct MusicDirector {
+    // Played outside of games, which use the track from the settings
+    pub menu_track: MusicTrack,
+    // Seconds one track takes to fade into the next
+    pub crossfade_seconds: f32,
+    pub mood: MusicMood,
+    // Seconds a stem takes to fade all the way in or out
+    pub fade_seconds: f32,
+    pub menu: LayerMix,
+    pub play: LayerMix,
+    pub danger: LayerMix,
+}
+
+impl Default for MusicDirector {
+    fn default() -> Self {
+        Self {
+            menu_track: MusicTrack::Nap,
+            crossfade_seconds: 2.0,
+            mood: MusicMood::Menu,
+            fade_seconds: 1.5,
+            menu: LayerMix {
+                base: 1.0,
+                percussion: 0.0,
+                melody: 0.6,
+            },
+            play: LayerMix {
+                base: 1.0,
+                percussion: 0.7,
+                melody: 1.0,
+            },
+            danger: LayerMix {
+                base: 1.0,
+                percussion: 1.0,
+                melody: 0.2,
+            },
+        }
+    }
+}
+
+impl MusicDirector {
+    pub fn mix(&self) -> LayerMix {
+        match self.mood {
+            MusicMood::Menu => self.menu,
+            MusicMood::Play => self.play,
+            MusicMood::Danger => self.danger,
+        }
+    }
+}
+
+// A track that's playing, with its stems as children. It fades in when it starts and, once
+// another track takes over, fades out and goes away.
+#[derive(Component)]
+struct Music {
+    track: MusicTrack,
+    // From 0 to 1, multiplying all of its stems
+    fade: f32,
+    fading_out: bool,
+}
+
+#[derive(Component)]
+struct Stem {
+    layer: MusicLayer,
+    // From 0 to 1, eased towards the director's mix
+    level: f32,
+}
+
+// Every stem of every track, loaded before the menu shows up so switching tracks never waits and
+// a track's stems all start on the same frame, in step
+#[derive(Resource)]
+struct MusicStems(HashMap<(MusicTrack, MusicLayer), Handle<AudioSource>>);
+
+fn load_stems(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let mut stems = HashMap::new();
+    for track in MusicTrack::ALL {
+        for layer in MusicLayer::ALL {
+            let stem: Handle<AudioSource> = asset_server.load(track.path(layer));
+            loading.track(stem.clone());
+            stems.insert((track, layer), stem);
+        }
+    }
+    commands.insert_resource(MusicStems(stems));
+}
+
+// Nothing plays before the menu
+fn music_allowed(state: Res<State<GameState>>) -> bool {
+    !matches!(state.get(), GameState::Splash | GameState::Loading)
+}
+
+fn choose_track(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    director: Res<MusicDirector>,
+    in_game: Option<Res<State<InGame>>>,
+    stems: Res<MusicStems>,
+    mixer: Mixer,
+    mut music: Query<(Entity, &mut Music)>,
+) {
+    let track = if in_game.is_some() {
+        settings.music_track
+    } else {
+        director.menu_track
+    };
+    let mut playing = false;
+    for (_, mut music) in &mut music {
+        let wanted = music.track == track;
+        // A track that's on its way out comes back from where it is
+        if music.fading_out == wanted {
+            music.fading_out = !wanted;
+        }
+        playing |= wanted;
+    }
+    if playing {
+        return;
+    }
+
+    let mix = director.mix();
+    let entity = commands
+        .spawn((
+            Music {
+                track,
+                fade: 0.0,
+                fading_out: false,
+            },
+            Name::new(format!("Music {track:?}")),
+        ))
+        .id();
+    for layer in MusicLayer::ALL {
+        let Some(stem) = stems.0.get(&(track, layer)) else {
+            continue;
+        };
+        let level = mix.get(layer);
+        commands.spawn((
+            Stem { layer, level },
+            Name::new(format!("Music {layer:?}")),
+            ChildOf(entity),
+            // Tracks end where they begin, so looping them is seamless
+            mixer.play_with_gain(AudioBus::Music, stem.clone(), PlaybackSettings::LOOP, 0.0),
+        ));
+    }
+}
+
+fn choose_mood(
+    state: Res<State<GameState>>,
+    cats: Query<&Health, With<Cat>>,
+    mut director: ResMut<MusicDirector>,
+) {
+    let mood = match state.get() {
+        GameState::Playing if cats.iter().any(|health| health.fraction() < DANGER_HEALTH) => {
+            MusicMood::Danger
+        }
+        GameState::Playing => MusicMood::Play,
+        _ => MusicMood::Menu,
+    };
+    if director.mood != mood {
+        director.mood = mood;
+    }
+}
+
+// Real time, so the music still settles while the game is paused
+fn fade_tracks(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    director: Res<MusicDirector>,
+    mut music: Query<(Entity, &mut Music)>,
+) {
+    let step = time.delta_secs() / director.crossfade_seconds.max(f32::EPSILON);
+    for (entity, mut music) in &mut music {
+        let target = if music.fading_out { 0.0 } else { 1.0 };
+        if music.fade != target {
+            music.fade += (target - music.fade).clamp(-step, step);
+        } else if music.fading_out {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn fade_layers(
+    time: Res<Time<Real>>,
+    director: Res<MusicDirector>,
+    mixer: Mixer,
+    music: Query<&Music>,
+    mut stems: Query<(&mut Stem, &ChildOf, &mut SoundGain, Option<&mut AudioSink>)>,
+) {
+    let mix = director.mix();
+    let step = time.delta_secs() / director.fade_seconds.max(f32::EPSILON);
+    for (mut stem, child_of, mut gain, sink) in &mut stems {
+        let target = mix.get(stem.layer);
+        if stem.level != target {
+            stem.level += (target - stem.level).clamp(-step, step);
+        }
+        let fade = music.get(child_of.parent()).map_or(0.0, |music| music.fade);
+        let new_gain = stem.level * fade;
+        if gain.0 == new_gain {
+            continue;
+        }
+        gain.0 = new_gain;
+        // Sinks only exist once a stem has started, until then it's spawned at its gain
+        if let Some(mut sink) = sink {
+            sink.set_volume(mixer.volume_with_gain(AudioBus::Music, gain.0));
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+use rand::Rng;
+use rand::seq::SliceRandom;
+
+// Interchangeable takes of one sound, e.g. a few different meows. Every time it plays, one of them
+// is picked at random and nudged a little in pitch and loudness, so repeats don't sound canned.
+#[derive(Clone, Debug)]
+pub struct SoundPool {
+    samples: Vec<Handle<AudioSource>>,
+    // Volume of the pool as a whole, from 0 to 1
+    gain: f32,
+    // Largest change in playback speed either way, which shifts the pitch with it
+    pitch_variation: f32,
+    // Largest drop in volume, as a fraction of the full volume
+    volume_variation: f32,
+}
+
+impl SoundPool {
+    pub fn new(samples: impl IntoIterator<Item = Handle<AudioSource>>) -> Self {
+        Self {
+            samples: samples.into_iter().collect(),
+            gain: 1.0,
+            pitch_variation: 0.0,
+            volume_variation: 0.0,
+        }
+    }
+
+    pub fn with_gain(mut self, gain: f32) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    pub fn with_pitch_variation(mut self, variation: f32) -> Self {
+        self.pitch_variation = variation;
+        self
+    }
+
+    pub fn with_volume_variation(mut self, variation: f32) -> Self {
+        self.volume_variation = variation;
+        self
+    }
+
+    pub fn samples(&self) -> &[Handle<AudioSource>] {
+        &self.samples
+    }
+
+    // A sample with the speed and gain to play it at
+    pub fn pick(&self) -> (Handle<AudioSource>, f32, f32) {
+        let mut rng = rand::thread_rng();
+        let sample = self.samples.choose(&mut rng).cloned().unwrap_or_default();
+        let speed = 1.0 + rng.gen_range(-self.pitch_variation..=self.pitch_variation);
+        let gain = self.gain * (1.0 - rng.gen_range(0.0..=self.volume_variation));
+        (sample, speed, gain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pick_without_variation_plays_as_is() {
+        let pool = SoundPool::new([Handle::default()]).with_gain(0.5);
+        let (_, speed, gain) = pool.pick();
+        assert_eq!(speed, 1.0);
+        assert_eq!(gain, 0.5);
+    }
+
+    #[test]
+    fn pick_stays_within_the_variation() {
+        let pool = SoundPool::new([Handle::default(), Handle::default()])
+            .with_gain(0.8)
+            .with_pitch_variation(0.1)
+            .with_volume_variation(0.25);
+        for _ in 0..100 {
+            let (_, speed, gain) = pool.pick();
+            assert!((0.9..=1.1).contains(&speed), "speed {speed}");
+            assert!((0.6..=0.8).contains(&gain), "gain {gain}");
+        }
+    }
+
+    #[test]
+    fn pick_from_an_empty_pool_gives_a_default_handle() {
+        let (sample, ..) = SoundPool::new([]).pick();
+        assert_eq!(sample, Handle::default());
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use super::{AudioBus, Mixer, SoundPool};
+use crate::loading::LoadingAssets;
+
+// The one-off sounds the game knows about, played by sending a `PlaySound`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum SoundId {
+    Meow,
+    Footstep,
+    Bump,
+    Click,
+    Pickup,
+    // Counting down the last seconds of a timed game
+    Tick,
+}
+
+impl SoundId {
+    pub const ALL: [SoundId; 6] = [
+        SoundId::Meow,
+        SoundId::Footstep,
+        SoundId::Bump,
+        SoundId::Click,
+        SoundId::Pickup,
+        SoundId::Tick,
+    ];
+
+    fn paths(self) -> &'static [&'static str] {
+        match self {
+            SoundId::Meow => &["sounds/meow.wav", "sounds/meow-2.wav", "sounds/meow-3.wav"],
+            SoundId::Footstep => &[
+                "sounds/footstep.wav",
+                "sounds/footstep-2.wav",
+                "sounds/footstep-3.wav",
+            ],
+            SoundId::Bump => &["sounds/bump.wav"],
+            SoundId::Click => &["sounds/click.wav"],
+            SoundId::Pickup => &["sounds/pickup.wav"],
+            SoundId::Tick => &["sounds/tick.wav"],
+        }
+    }
+
+    fn pool(self, samples: Vec<Handle<AudioSource>>) -> SoundPool {
+        let pool = SoundPool::new(samples);
+        match self {
+            SoundId::Meow => pool.with_pitch_variation(0.08).with_volume_variation(0.1),
+            // Steps come often, so they vary more to keep from sounding like a metronome
+            SoundId::Footstep => pool.with_pitch_variation(0.12).with_volume_variation(0.3),
+            SoundId::Bump => pool.with_pitch_variation(0.1).with_volume_variation(0.2),
+            SoundId::Pickup => pool.with_pitch_variation(0.05),
+            SoundId::Click | SoundId::Tick => pool,
+        }
+    }
+
+    // Where the sound plays unless the `PlaySound` says otherwise
+    pub fn bus(self) -> AudioBus {
+        match self {
+            SoundId::Click | SoundId::Tick => AudioBus::Ui,
+            _ => AudioBus::Effects,
+        }
+    }
+}
+
+// Where a sound comes from
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum SoundPosition {
+    // The same in both ears, like the UI
+    #[default]
+    Everywhere,
+    // A spot in the world
+    At(Vec2),
+    // An entity, following it while it plays
+    On(Entity),
+}
+
+// Asks for a sound without touching any audio API: `sounds.write(PlaySound::new(SoundId::Meow)
+// .on(cat))`. The audio plugin picks it up, finds the samples in the `AudioRegistry` and plays
+// them on the bus.
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub struct PlaySound {
+    pub id: SoundId,
+    pub position: SoundPosition,
+    pub bus: AudioBus,
+}
+
+impl PlaySound {
+    pub fn new(id: SoundId) -> Self {
+        Self {
+            id,
+            position: SoundPosition::Everywhere,
+            bus: id.bus(),
+        }
+    }
+
+    pub fn at(mut self, position: Vec2) -> Self {
+        self.position = SoundPosition::At(position);
+        self
+    }
+
+    pub fn on(mut self, entity: Entity) -> Self {
+        self.position = SoundPosition::On(entity);
+        self
+    }
+
+    pub fn on_bus(mut self, bus: AudioBus) -> Self {
+        self.bus = bus;
+        self
+    }
+}
+
+// The samples of every `SoundId`, loaded up front and held by the loading screen so the first
+// meow doesn't wait on the disk
+#[derive(Resource)]
+pub struct AudioRegistry {
+    pools: HashMap<SoundId, SoundPool>,
+}
+
+impl AudioRegistry {
+    pub fn get(&self, id: SoundId) -> Option<&SoundPool> {
+        self.pools.get(&id)
+    }
+}
+
+pub(super) fn load_sounds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let mut pools = HashMap::new();
+    for id in SoundId::ALL {
+        let samples: Vec<Handle<AudioSource>> = id
+            .paths()
+            .iter()
+            .map(|&path| asset_server.load(path))
+            .collect();
+        for sample in &samples {
+            loading.track(sample.clone());
+        }
+        pools.insert(id, id.pool(samples));
+    }
+    commands.insert_resource(AudioRegistry { pools });
+}
+
+pub(super) fn play_sounds(
+    mut commands: Commands,
+    mut requests: EventReader<PlaySound>,
+    registry: Res<AudioRegistry>,
+    mixer: Mixer,
+    emitters: Query<(), With<GlobalTransform>>,
+) {
+    for request in requests.read() {
+        let Some(pool) = registry.get(request.id) else {
+            continue;
+        };
+        let name = Name::new(format!("{:?}", request.id));
+        match request.position {
+            SoundPosition::Everywhere => {
+                commands.spawn((
+                    name,
+                    mixer.play_from(request.bus, pool, PlaybackSettings::DESPAWN),
+                ));
+            }
+            SoundPosition::At(position) => {
+                commands.spawn((name, mixer.play_at_position(request.bus, pool, position)));
+            }
+            // Gone before it could make a sound
+            SoundPosition::On(entity) if !emitters.contains(entity) => {}
+            SoundPosition::On(entity) => {
+                commands.spawn((name, mixer.play_at(request.bus, pool, entity)));
+            }
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use bevy::prelude::*;
+
+use crate::animation::{
+    AnimationRegistry, AnimationSheet, AnimationState, AnimationSystems, Facing, SpawnAnimatedExt,
+    SquashStretch, StateClips, play_clip,
+};
+use crate::collision::Collider;
+use crate::console::{AddConsoleCommandExt, ConsoleOutput, parse_arg};
+use crate::health::{Died, Health};
+use crate::input::{ActionState, CatAction};
+use crate::level::Level;
+use crate::minimap::MinimapIcon;
+use crate::movement::{
+    BoundaryBehavior, Cooldown, Dash, InterpolatedTranslation, Jumper, MovementSettings,
+    MovementSystems, Sprint, Stamina, Velocity, is_platformer,
+};
+use crate::projectile::YarnThrower;
+use crate::state::{GameState, InGame};
+
+const CAT_SCALE: f32 = 0.5;
+const CAT_HEALTH: f32 = 100.0;
+// Horizontal distance between the cats when they're spawned
+const CAT_SPACING: f32 = 160.0;
+// Tint of the cats that aren't being controlled
+const IDLE_CAT_TINT: Color = Color::srgb(0.65, 0.65, 0.7);
+
+pub struct CatPlugin;
+
+impl Plugin for CatPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Cat>()
+            .register_type::<ControlledBy>()
+            .add_systems(Startup, load_cat_sheet)
+            .add_observer(game_over_on_death)
+            .add_console_command(
+                "spawn",
+                "spawn cat [count]: add cats to the game",
+                spawn_cat_command,
+            )
+            .add_systems(
+                Update,
+                spawn_cats.run_if(in_state(GameState::Playing).and(not(any_with_component::<Cat>))),
+            )
+            .add_systems(
+                Update,
+                (
+                    switch_cat.run_if(in_state(GameState::Playing).and(switch_cat_just_pressed)),
+                    tint_cats,
+                )
+                    .chain()
+                    .before(MovementSystems::Input),
+            )
+            .add_systems(
+                Update,
+                // Space jumps in platformer mode instead
+                play_clip::<ControlledBy>("uia")
+                    .run_if(
+                        in_state(GameState::Playing)
+                            .and(uia_just_pressed)
+                            .and(not(is_platformer)),
+                    )
+                    .before(AnimationSystems::SelectClip),
+            );
+    }
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Cat;
+
+// Marks the cat the player's input currently goes to
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct ControlledBy;
+
+fn uia_just_pressed(actions: Res<ActionState>) -> bool {
+    actions.just_pressed(CatAction::Uia)
+}
+
+fn switch_cat_just_pressed(actions: Res<ActionState>) -> bool {
+    actions.just_pressed(CatAction::SwitchCat)
+}
+
+fn load_cat_sheet(mut registry: ResMut<AnimationRegistry>, asset_server: Res<AssetServer>) {
+    registry.load(&asset_server, "cat", "cat.anim.ron");
+}
+
+// As many as the level says, more can be added with the `spawn cat` command
+fn spawn_cats(
+    mut commands: Commands,
+    level: Res<Level>,
+    registry: Res<AnimationRegistry>,
+    sheets: Res<Assets<AnimationSheet>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+) {
+    // Wait until the sheet (and with it the atlas layout) has been loaded
+    let Some(cell_size) = registry
+        .get(&sheets, "cat")
+        .and_then(|sheet| sheet.cell_size(&layouts))
+    else {
+        return;
+    };
+    let count = level.definition.cats.max(1);
+    for index in 0..count {
+        // Side by side, centered on the window
+        let x = (index as f32 - (count - 1) as f32 / 2.0) * CAT_SPACING;
+        let cat = spawn_cat(&mut commands, cell_size, index + 1, Vec2::new(x, 0.0));
+        if index == 0 {
+            commands.entity(cat).insert(ControlledBy);
+        }
+    }
+}
+
+fn spawn_cat(commands: &mut Commands, cell_size: Vec2, number: usize, position: Vec2) -> Entity {
+    let state_clips = StateClips::default();
+    let mut cat = commands.spawn_animated("cat", &state_clips.idle);
+    cat.insert((
+        (
+            Cat,
+            // Every new game starts with fresh cats
+            StateScoped(InGame),
+            Name::new(format!("Cat {number}")),
+            Transform::from_translation(position.extend(0.0)).with_scale(Vec3::splat(CAT_SCALE)),
+            AnimationState::default(),
+            Facing::default(),
+            SquashStretch::default(),
+            state_clips,
+        ),
+        (
+            Velocity::default(),
+            Collider::from_size(cell_size * CAT_SCALE),
+            BoundaryBehavior::Clamp,
+            MovementSettings::default(),
+            Dash::default(),
+            Cooldown::default(),
+            Sprint::default(),
+            Stamina::default(),
+            Jumper::default(),
+            YarnThrower::default(),
+            InterpolatedTranslation::default(),
+            Health::new(CAT_HEALTH),
+            MinimapIcon::new(Color::WHITE),
+        ),
+    ));
+    cat.id()
+}
+
+// spawn cat [count]: adds cats in a row above the middle of the window
+fn spawn_cat_command(
+    In(args): In<Vec<String>>,
+    mut commands: Commands,
+    registry: Res<AnimationRegistry>,
+    sheets: Res<Assets<AnimationSheet>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    in_game: Option<Res<State<InGame>>>,
+    cats: Query<(), With<Cat>>,
+) -> ConsoleOutput {
+    if args.first().map(String::as_str) != Some("cat") {
+        return Err("only cats can be spawned".to_owned());
+    }
+    let count: usize = if args.len() > 1 {
+        parse_arg(&args, 1, "count")?
+    } else {
+        1
+    };
+    if in_game.is_none() {
+        return Err("start a game first".to_owned());
+    }
+    let cell_size = registry
+        .get(&sheets, "cat")
+        .and_then(|sheet| sheet.cell_size(&layouts))
+        .ok_or_else(|| "the cat sheet isn't loaded".to_owned())?;
+    let existing = cats.iter().count();
+    for index in 0..count {
+        let x = (index as f32 - (count - 1) as f32 / 2.0) * CAT_SPACING;
+        spawn_cat(
+            &mut commands,
+            cell_size,
+            existing + index + 1,
+            Vec2::new(x, CAT_SPACING),
+        );
+    }
+    Ok(format!("spawned {count} cat(s)"))
+}
+
+// Losing any of the cats ends the game
+fn game_over_on_death(
+    trigger: Trigger<Died>,
+    cats: Query<(), With<Cat>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if cats.contains(trigger.target()) {
+        next_state.set(GameState::GameOver);
+    }
+}
+
+// Hands control to the next cat, in spawn order
+fn switch_cat(mut commands: Commands, cats: Query<(Entity, Has<ControlledBy>), With<Cat>>) {
+    let mut order: Vec<(Entity, bool)> = cats.iter().collect();
+    if order.len() < 2 {
+        return;
+    }
+    order.sort_by_key(|(entity, _)| *entity);
+    let current = order
+        .iter()
+        .position(|(_, controlled)| *controlled)
+        .unwrap_or(order.len() - 1);
+    let next = order[(current + 1) % order.len()].0;
+
+    for (entity, controlled) in order {
+        if entity == next {
+            commands.entity(entity).insert(ControlledBy);
+        } else if controlled {
+            commands.entity(entity).remove::<ControlledBy>();
+        }
+    }
+}
+
+// Greys out the cats that aren't listening to input
+fn tint_cats(mut cats: Query<(&mut Sprite, Has<ControlledBy>), With<Cat>>) {
+    for (mut sprite, controlled) in &mut cats {
+        let color = if controlled {
+            Color::WHITE
+        } else {
+            IDLE_CAT_TINT
+        };
+        if sprite.color != color {
+            sprite.color = color;
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::accessibility::{Accessibility, reduced_motion};
+use crate::audio::{PlaySound, SoundId};
+use crate::cat::Cat;
+use crate::collision::{Aabb, Collider, Collision, Sensor};
+use crate::level::LevelScoped;
+use crate::minimap::MinimapIcon;
+use crate::movement::MovementSystems;
+use crate::score::AddScoreExt;
+use crate::spawner::{Prototype, Spawn, SpawnedBy};
+use crate::state::{GameState, InGame};
+
+pub const FISH_SIZE: Vec2 = Vec2::new(28.0, 14.0);
+const FISH_COLOR: Color = Color::srgb(1.0, 0.55, 0.2);
+const FISH_POINTS: u32 = 1;
+// Between the floor surfaces and the cats
+const FISH_Z: f32 = -1.0;
+// How far from the window edges fish are kept, so they're never half off screen
+const SPAWN_MARGIN: f32 = 24.0;
+// Tries at finding a spot for a pickup that isn't inside an obstacle
+const SPAWN_ATTEMPTS: usize = 16;
+
+// Fish turning up around the room, brought in by a `Spawner`, for the cats to eat. Touching one
+// is worth points, with a little pop and a sound.
+pub struct CollectiblePlugin;
+
+impl Plugin for CollectiblePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Collectible>()
+            .add_systems(
+                FixedUpdate,
+                collect_collectibles
+                    .after(MovementSystems::Collide)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (spawn_fish, pop_collected).run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+// Something a cat picks up by touching it
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+#[require(Sensor)]
+pub struct Collectible {
+    pub points: u32,
+}
+
+// A collectible that's been picked up, growing and fading before it goes away
+#[derive(Component)]
+struct Collected {
+    timer: Timer,
+}
+
+fn fish(position: Vec2, points: u32) -> impl Bundle {
+    (
+        Collectible { points },
+        StateScoped(InGame),
+        LevelScoped,
+        Name::new("Fish"),
+        Sprite::from_color(FISH_COLOR, FISH_SIZE),
+        Transform::from_translation(position.extend(FISH_Z)),
+        Collider::from_size(FISH_SIZE),
+        MinimapIcon::new(FISH_COLOR),
+        children![(
+            Sprite::from_color(FISH_COLOR, Vec2::splat(FISH_SIZE.y)),
+            // A diamond at the back end makes the tail
+            Transform::from_xyz(-FISH_SIZE.x / 2.0, 0.0, 0.0)
+                .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+        )],
+    )
+}
+
+fn spawn_fish(mut commands: Commands, mut spawns: EventReader<Spawn>) {
+    for spawn in spawns.read() {
+        if spawn.prototype == Prototype::Fish {
+            commands.spawn((fish(spawn.position, FISH_POINTS), SpawnedBy(spawn.spawner)));
+        }
+    }
+}
+
+// Somewhere for a pickup of `size` to turn up: anywhere the cats can reach, the same bounds
+// movement keeps them in, and out of the `obstacles` unless the room is too full to find a spot
+pub fn random_position(window: &Window, size: Vec2, obstacles: &[Aabb]) -> Vec2 {
+    let half_size = (window.size() / 2.0 - size / 2.0 - SPAWN_MARGIN).max(Vec2::ZERO);
+    let mut rng = rand::thread_rng();
+    let mut position = Vec2::ZERO;
+    for _ in 0..SPAWN_ATTEMPTS {
+        position = Vec2::new(
+            rng.gen_range(-half_size.x..=half_size.x),
+            rng.gen_range(-half_size.y..=half_size.y),
+        );
+        let spot = Aabb {
+            center: position,
+            half_extents: size / 2.0,
+        };
+        if !obstacles
+            .iter()
+            .any(|obstacle| spot.penetration(obstacle).is_some())
+        {
+            break;
+        }
+    }
+    position
+}
+
+fn collect_collectibles(
+    mut commands: Commands,
+    mut collisions: EventReader<Collision>,
+    mut sounds: EventWriter<PlaySound>,
+    cats: Query<(), With<Cat>>,
+    collectibles: Query<&Collectible>,
+) {
+    // Two cats can reach the same fish in one step, only the first gets it
+    let mut collected = HashSet::new();
+    for collision in collisions.read() {
+        if !cats.contains(collision.entity) {
+            continue;
+        }
+        let Ok(collectible) = collectibles.get(collision.other) else {
+            continue;
+        };
+        if !collected.insert(collision.other) {
+            continue;
+        }
+        commands.add_score(collectible.points);
+        sounds.write(PlaySound::new(SoundId::Pickup).on(collision.other));
+        commands
+            .entity(collision.other)
+            .remove::<(Collectible, Collider, Sensor, MinimapIcon, SpawnedBy)>()
+            .insert(Collected {
+                timer: Timer::from_seconds(0.25, TimerMode::Once),
+            });
+    }
+}
+
+fn pop_collected(
+    mut commands: Commands,
+    time: Res<Time>,
+    accessibility: Option<Res<Accessibility>>,
+    mut collected: Query<(Entity, &mut Collected, &mut Transform, &mut Sprite)>,
+    mut tails: Query<(&ChildOf, &mut Sprite), Without<Collected>>,
+) {
+    let reduced_motion = reduced_motion(accessibility);
+    for (entity, mut pop, mut transform, mut sprite) in &mut collected {
+        pop.timer.tick(time.delta());
+        if pop.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let t = pop.timer.fraction();
+        if !reduced_motion {
+            transform.scale = Vec3::splat(1.0 + t * 0.6);
+        }
+        let alpha = 1.0 - t;
+        sprite.color.set_alpha(alpha);
+        for (child_of, mut tail) in &mut tails {
+            if child_of.parent() == entity {
+                tail.color.set_alpha(alpha);
+            }
+        }
+    }
+}
@@ -0,0 +1,209 @@
+use bevy::prelude::*;
+
+use crate::movement::{MovementSystems, Velocity};
+
+// Axis-aligned box collisions between anything with a `Collider`. Entities with a `Velocity`
+// are pushed out of the solid ones they run into, and every overlap is reported as a `Collision`.
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Collision>()
+            .register_type::<Collider>()
+            .register_type::<Sensor>()
+            .add_systems(
+                FixedUpdate,
+                resolve_collisions.in_set(MovementSystems::Collide),
+            );
+    }
+}
+
+// A box around the entity's translation, in world units
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Collider {
+    pub half_extents: Vec2,
+}
+
+impl Collider {
+    pub fn new(half_extents: Vec2) -> Self {
+        Self { half_extents }
+    }
+
+    pub fn from_size(size: Vec2) -> Self {
+        Self::new(size / 2.0)
+    }
+}
+
+// Colliders with this only report overlaps (pickups, triggers) and never block anything
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Sensor;
+
+// Sent (and triggered on both entities) for every pair of overlapping colliders, every frame
+// they overlap. `normal` points from `other` towards `entity`.
+#[derive(Event, Clone, Debug)]
+pub struct Collision {
+    pub entity: Entity,
+    pub other: Entity,
+    pub normal: Vec2,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Aabb {
+    pub center: Vec2,
+    pub half_extents: Vec2,
+}
+
+impl Aabb {
+    pub fn new(center: Vec2, collider: &Collider) -> Self {
+        Self {
+            center,
+            half_extents: collider.half_extents,
+        }
+    }
+
+    // The shortest move that takes `self` out of `other`, if they overlap
+    pub fn penetration(&self, other: &Aabb) -> Option<Vec2> {
+        self.penetration_after(other, Vec2::ZERO)
+    }
+
+    // The move that takes `self` out of `other` after it moved by `motion`, back out along the
+    // axis it ran into `other` on. Pressing into a row of boxes while sliding along it then
+    // always pushes out of the row, instead of catching on the seams between them.
+    pub fn penetration_after(&self, other: &Aabb, motion: Vec2) -> Option<Vec2> {
+        let delta = self.center - other.center;
+        let overlap = self.half_extents + other.half_extents - delta.abs();
+        if overlap.x <= 0.0 || overlap.y <= 0.0 {
+            return None;
+        }
+        // How far into the move each axis started to overlap, from 0 to 1. An axis that didn't
+        // move, or already overlapped before, wasn't the one that was run into.
+        let entry = |overlap: f32, motion: f32| {
+            if motion == 0.0 || overlap > motion.abs() {
+                f32::NEG_INFINITY
+            } else {
+                1.0 - overlap / motion.abs()
+            }
+        };
+        let (entry_x, entry_y) = (entry(overlap.x, motion.x), entry(overlap.y, motion.y));
+        let along_x = if entry_x == entry_y {
+            overlap.x < overlap.y
+        } else {
+            entry_x > entry_y
+        };
+        Some(if along_x {
+            Vec2::new(overlap.x.copysign(delta.x), 0.0)
+        } else {
+            Vec2::new(0.0, overlap.y.copysign(delta.y))
+        })
+    }
+}
+
+type MoverItem<'a> = (
+    Entity,
+    &'a mut Transform,
+    &'a mut Velocity,
+    &'a Collider,
+    Has<Sensor>,
+);
+
+pub fn resolve_collisions(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut collisions: EventWriter<Collision>,
+    mut movers: Query<MoverItem>,
+    obstacles: Query<(Entity, &Transform, &Collider, Has<Sensor>), Without<Velocity>>,
+) {
+    let mut report = |entity: Entity, other: Entity, normal: Vec2| {
+        let collision = Collision {
+            entity,
+            other,
+            normal,
+        };
+        commands.trigger_targets(collision.clone(), [entity, other]);
+        collisions.write(collision);
+    };
+
+    for (entity, mut transform, mut velocity, collider, is_sensor) in &mut movers {
+        // Roughly where this step's move came from
+        let motion = velocity.0 * time.delta_secs();
+        for (other, other_transform, other_collider, other_is_sensor) in &obstacles {
+            let aabb = Aabb::new(transform.translation.truncate(), collider);
+            let other_aabb = Aabb::new(other_transform.translation.truncate(), other_collider);
+            let Some(push) = aabb.penetration_after(&other_aabb, motion) else {
+                continue;
+            };
+            let normal = push.normalize();
+            report(entity, other, normal);
+
+            if !is_sensor && !other_is_sensor {
+                transform.translation += push.extend(0.0);
+                // Stop moving into the obstacle, sliding along it is fine
+                let into = velocity.0.dot(normal);
+                if into < 0.0 {
+                    velocity.0 -= normal * into;
+                }
+            }
+        }
+    }
+
+    // Moving entities only report overlaps among themselves
+    for [a, b] in movers.iter_combinations() {
+        let a_aabb = Aabb::new(a.1.translation.truncate(), a.3);
+        let b_aabb = Aabb::new(b.1.translation.truncate(), b.3);
+        if let Some(push) = a_aabb.penetration(&b_aabb) {
+            report(a.0, b.0, push.normalize());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aabb(x: f32, y: f32, half_width: f32, half_height: f32) -> Aabb {
+        Aabb {
+            center: Vec2::new(x, y),
+            half_extents: Vec2::new(half_width, half_height),
+        }
+    }
+
+    #[test]
+    fn penetration_is_none_apart_or_touching() {
+        let other = aabb(0.0, 0.0, 10.0, 10.0);
+        assert_eq!(aabb(30.0, 0.0, 10.0, 10.0).penetration(&other), None);
+        assert_eq!(aabb(20.0, 0.0, 10.0, 10.0).penetration(&other), None);
+        assert_eq!(aabb(0.0, -20.0, 10.0, 10.0).penetration(&other), None);
+    }
+
+    #[test]
+    fn penetration_pushes_out_along_the_shallower_axis() {
+        let other = aabb(0.0, 0.0, 10.0, 10.0);
+        assert_eq!(
+            aabb(15.0, 2.0, 10.0, 10.0).penetration(&other),
+            Some(Vec2::new(5.0, 0.0))
+        );
+        assert_eq!(
+            aabb(-15.0, 2.0, 10.0, 10.0).penetration(&other),
+            Some(Vec2::new(-5.0, 0.0))
+        );
+        assert_eq!(
+            aabb(1.0, -18.0, 10.0, 10.0).penetration(&other),
+            Some(Vec2::new(0.0, -2.0))
+        );
+    }
+
+    #[test]
+    fn penetration_after_backs_out_along_the_axis_it_ran_in_on() {
+        let other = aabb(0.0, 0.0, 10.0, 10.0);
+        // Moving right into the box's side while sliding down past its top corner: shallower on
+        // y, but it was x that ran into it
+        let mover = aabb(-15.0, 17.0, 10.0, 10.0);
+        assert_eq!(mover.penetration(&other), Some(Vec2::new(0.0, 3.0)));
+        assert_eq!(
+            mover.penetration_after(&other, Vec2::new(6.0, -1.0)),
+            Some(Vec2::new(-5.0, 0.0))
+        );
+    }
+}
@@ -0,0 +1,61 @@
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+// Folder (relative to the working directory) user-editable settings are kept in
+const CONFIG_DIR: &str = "config";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("could not access config file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse config file: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not write config file: {0}")]
+    Write(#[from] ron::Error),
+}
+
+pub fn config_path(file: &str) -> PathBuf {
+    PathBuf::from(CONFIG_DIR).join(file)
+}
+
+pub fn load<T: DeserializeOwned>(file: &str) -> Result<T, ConfigError> {
+    let contents = fs::read_to_string(config_path(file))?;
+    Ok(ron::from_str(&contents)?)
+}
+
+pub fn save<T: Serialize>(file: &str, value: &T) -> Result<(), ConfigError> {
+    let path = config_path(file);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let contents = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())?;
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+// Loads `file`, falling back to (and writing out, so there's something to edit) the defaults
+pub fn load_or_default<T: DeserializeOwned + Serialize + Default>(file: &str) -> T {
+    match load(file) {
+        Ok(value) => value,
+        Err(ConfigError::Io(error)) if error.kind() == std::io::ErrorKind::NotFound => {
+            let value = T::default();
+            save_or_warn(file, &value);
+            value
+        }
+        Err(error) => {
+            warn!("{file}: {error}, using defaults");
+            T::default()
+        }
+    }
+}
+
+pub fn save_or_warn<T: Serialize>(file: &str, value: &T) {
+    if let Err(error) = save(file, value) {
+        warn!("{file}: {error}");
+    }
+}
@@ -0,0 +1,250 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use bevy::ecs::system::SystemId;
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+
+use crate::input::ActionSystems;
+use crate::state::GameState;
+use crate::theme::{Surface, TextRole, Theme};
+
+// Lines kept in the scrollback
+const MAX_LOG_LINES: usize = 200;
+// Lines of scrollback shown above the prompt
+const VISIBLE_LOG_LINES: usize = 12;
+
+// Drop-down console toggled with the key left of 1 (` or ~). Typing a line runs the command
+// registered under its first word with the rest as arguments. Plugins add their own commands with
+// `add_console_command`.
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Console>()
+            .init_resource::<ConsoleCommands>()
+            // Gameplay doesn't see the keys typed into the console
+            .configure_sets(
+                PreUpdate,
+                ActionSystems::Collect.run_if(not(console_is_open)),
+            )
+            .add_systems(
+                Update,
+                (
+                    toggle_console.run_if(input_just_pressed(KeyCode::Backquote)),
+                    (type_into_console, run_submitted, update_console_text)
+                        .chain()
+                        .run_if(console_is_open),
+                )
+                    .chain(),
+            )
+            .add_console_command("help", "list the commands", help)
+            .add_console_command("clear", "empty the scrollback", clear)
+            .add_console_command(
+                "state",
+                "state <menu|credits|playing|paused|gameover>: switch screens",
+                set_state,
+            );
+    }
+}
+
+// What a command prints, or why it failed
+pub type ConsoleOutput = Result<String, String>;
+
+#[derive(Resource, Default, Debug)]
+pub struct Console {
+    pub open: bool,
+    pub input: String,
+    log: Vec<String>,
+    // The last line run, for arrow up
+    previous: Option<String>,
+    submitted: Option<String>,
+}
+
+impl Console {
+    pub fn print(&mut self, line: impl Into<String>) {
+        self.log.push(line.into());
+        if self.log.len() > MAX_LOG_LINES {
+            self.log.drain(..self.log.len() - MAX_LOG_LINES);
+        }
+    }
+}
+
+pub fn console_is_open(console: Res<Console>) -> bool {
+    console.open
+}
+
+struct ConsoleCommand {
+    help: &'static str,
+    system: SystemId<In<Vec<String>>, ConsoleOutput>,
+}
+
+// Every command the console knows, by name
+#[derive(Resource, Default)]
+pub struct ConsoleCommands(BTreeMap<String, ConsoleCommand>);
+
+pub trait AddConsoleCommandExt {
+    // Registers `system` to run with the words after `name` whenever a line starts with `name`
+    fn add_console_command<M>(
+        &mut self,
+        name: &str,
+        help: &'static str,
+        system: impl IntoSystem<In<Vec<String>>, ConsoleOutput, M> + 'static,
+    ) -> &mut Self;
+}
+
+impl AddConsoleCommandExt for App {
+    fn add_console_command<M>(
+        &mut self,
+        name: &str,
+        help: &'static str,
+        system: impl IntoSystem<In<Vec<String>>, ConsoleOutput, M> + 'static,
+    ) -> &mut Self {
+        let world = self.world_mut();
+        let system = world.register_system(system);
+        world
+            .get_resource_or_init::<ConsoleCommands>()
+            .0
+            .insert(name.to_owned(), ConsoleCommand { help, system });
+        self
+    }
+}
+
+// Argument `index` parsed as a `T`, with an error naming what was expected
+pub fn parse_arg<T: FromStr>(args: &[String], index: usize, name: &str) -> Result<T, String> {
+    let arg = args.get(index).ok_or_else(|| format!("missing {name}"))?;
+    arg.parse()
+        .map_err(|_| format!("{arg:?} isn't a valid {name}"))
+}
+
+#[derive(Component)]
+struct ConsolePanel;
+
+#[derive(Component)]
+struct ConsoleText;
+
+fn toggle_console(
+    mut commands: Commands,
+    mut console: ResMut<Console>,
+    theme: Res<Theme>,
+    panels: Query<Entity, With<ConsolePanel>>,
+) {
+    console.open = !console.open;
+    console.input.clear();
+    if let Ok(panel) = panels.single() {
+        commands.entity(panel).despawn();
+        return;
+    }
+    commands.spawn((
+        ConsolePanel,
+        Name::new("Console"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            padding: theme.panel_padding(),
+            ..Default::default()
+        },
+        theme.surface(Surface::Panel),
+        GlobalZIndex(10),
+        children![(ConsoleText, Text::default(), theme.text(TextRole::Small))],
+    ));
+}
+
+fn type_into_console(mut console: ResMut<Console>, mut keys: EventReader<KeyboardInput>) {
+    for key in keys.read() {
+        if !key.state.is_pressed() {
+            continue;
+        }
+        match &key.logical_key {
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                if !line.trim().is_empty() {
+                    console.submitted = Some(line);
+                }
+            }
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::ArrowUp => {
+                if let Some(previous) = console.previous.clone() {
+                    console.input = previous;
+                }
+            }
+            Key::Space => console.input.push(' '),
+            // The toggle key itself isn't typed
+            Key::Character(text) if key.key_code != KeyCode::Backquote => {
+                console.input.push_str(text);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn run_submitted(world: &mut World) {
+    let Some(line) = world.resource_mut::<Console>().submitted.take() else {
+        return;
+    };
+    let mut words = line.split_whitespace().map(str::to_owned);
+    let name = words.next().unwrap_or_default();
+    let args: Vec<String> = words.collect();
+
+    let system = world
+        .resource::<ConsoleCommands>()
+        .0
+        .get(&name)
+        .map(|command| command.system);
+    let output = match system {
+        Some(system) => world
+            .run_system_with(system, args)
+            .unwrap_or_else(|error| Err(error.to_string())),
+        None => Err(format!("unknown command {name:?}, try help")),
+    };
+
+    let mut console = world.resource_mut::<Console>();
+    console.print(format!("> {line}"));
+    match output {
+        Ok(text) if text.is_empty() => {}
+        Ok(text) => console.print(text),
+        Err(error) => console.print(format!("error: {error}")),
+    }
+    console.previous = Some(line);
+}
+
+fn update_console_text(console: Res<Console>, mut text: Single<&mut Text, With<ConsoleText>>) {
+    let start = console.log.len().saturating_sub(VISIBLE_LOG_LINES);
+    let mut lines = console.log[start..].to_vec();
+    lines.push(format!("> {}_", console.input));
+    text.0 = lines.join("\n");
+}
+
+fn help(_: In<Vec<String>>, commands: Res<ConsoleCommands>) -> ConsoleOutput {
+    let lines: Vec<String> = commands
+        .0
+        .iter()
+        .map(|(name, command)| format!("{name}: {}", command.help))
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+fn clear(_: In<Vec<String>>, mut console: ResMut<Console>) -> ConsoleOutput {
+    console.log.clear();
+    Ok(String::new())
+}
+
+fn set_state(
+    In(args): In<Vec<String>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) -> ConsoleOutput {
+    let state = match args.first().map(|arg| arg.to_lowercase()).as_deref() {
+        Some("menu") => GameState::Menu,
+        Some("credits") => GameState::Credits,
+        Some("playing") | Some("play") => GameState::Playing,
+        Some("paused") | Some("pause") => GameState::Paused,
+        Some("gameover") => GameState::GameOver,
+        _ => return Err("expected menu, credits, playing, paused or gameover".to_owned()),
+    };
+    next_state.set(state);
+    Ok(format!("switching to {state:?}"))
+}
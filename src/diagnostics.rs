@@ -0,0 +1,97 @@
+use bevy::diagnostic::{
+    DiagnosticPath, DiagnosticsStore, EntityCountDiagnosticsPlugin, FrameTimeDiagnosticsPlugin,
+};
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+
+use crate::animation::AnimationConfig;
+use crate::cat::ControlledBy;
+use crate::theme::{Surface, TextRole, Theme};
+
+// F2 overlay (F3 is the animation debugger) with FPS, frame time, entity count and the frame the
+// controlled cat's animation is on
+pub struct DiagnosticsOverlayPlugin;
+
+impl Plugin for DiagnosticsOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            FrameTimeDiagnosticsPlugin::default(),
+            EntityCountDiagnosticsPlugin,
+        ))
+        .add_systems(
+            Update,
+            (
+                toggle_overlay.run_if(input_just_pressed(KeyCode::F2)),
+                update_overlay_text.run_if(any_with_component::<DiagnosticsOverlay>),
+            ),
+        );
+    }
+}
+
+#[derive(Component)]
+struct DiagnosticsOverlay;
+
+#[derive(Component)]
+struct DiagnosticsText;
+
+fn toggle_overlay(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    overlays: Query<Entity, With<DiagnosticsOverlay>>,
+) {
+    if let Ok(overlay) = overlays.single() {
+        commands.entity(overlay).despawn();
+        return;
+    }
+    commands.spawn((
+        DiagnosticsOverlay,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.0),
+            left: Val::Px(8.0),
+            padding: theme.panel_padding(),
+            ..Default::default()
+        },
+        theme.surface(Surface::Panel),
+        GlobalZIndex(2),
+        children![(
+            DiagnosticsText,
+            Text::default(),
+            theme.text(TextRole::Small)
+        )],
+    ));
+}
+
+fn update_overlay_text(
+    diagnostics: Res<DiagnosticsStore>,
+    cat: Query<(Option<&Name>, &AnimationConfig, &Sprite), With<ControlledBy>>,
+    mut text: Single<&mut Text, With<DiagnosticsText>>,
+) {
+    let smoothed = |path: &DiagnosticPath| {
+        diagnostics
+            .get(path)
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or_default()
+    };
+    let mut lines = vec![
+        format!("{:.0} fps", smoothed(&FrameTimeDiagnosticsPlugin::FPS)),
+        format!(
+            "{:.2} ms/frame",
+            smoothed(&FrameTimeDiagnosticsPlugin::FRAME_TIME)
+        ),
+        format!(
+            "{:.0} entities",
+            smoothed(&EntityCountDiagnosticsPlugin::ENTITY_COUNT)
+        ),
+    ];
+    if let Ok((name, config, sprite)) = cat.single() {
+        let index = sprite.texture_atlas.as_ref().map_or(0, |atlas| atlas.index);
+        lines.push(format!(
+            "{}: {:?} frame {}",
+            name.map_or("Cat", Name::as_str),
+            config.clip_name,
+            index
+        ));
+    }
+    text.0 = lines.join("\n");
+}
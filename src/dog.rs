@@ -0,0 +1,240 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::animation::{
+    AnimationRegistry, AnimationSheet, AnimationState, Facing, SpawnAnimatedExt, StateClips,
+};
+use crate::cat::Cat;
+use crate::collision::Collider;
+use crate::health::Damage;
+use crate::level::{Level, LevelScoped};
+use crate::minimap::MinimapIcon;
+use crate::movement::{BoundaryBehavior, InterpolatedTranslation, MovementSystems, Velocity};
+use crate::spawner::{Prototype, Spawn, SpawnedBy};
+use crate::state::{GameState, InGame};
+
+const DOG_SCALE: f32 = 1.5;
+// Roughly the sprite as drawn, for keeping dogs from being spawned inside obstacles
+pub const DOG_SIZE: Vec2 = Vec2::splat(64.0 * DOG_SCALE);
+const DOG_COLOR: Color = Color::srgb(0.8, 0.35, 0.2);
+const DOG_DAMAGE: f32 = 10.0;
+// How hard a bitten cat is shoved away, in pixels per second
+const DOG_KNOCKBACK: f32 = 450.0;
+// How far from home a wandering dog strays before it turns back
+const WANDER_RADIUS: f32 = 120.0;
+// A dog that gave up a chase is home again this close to it
+const HOME_RADIUS: f32 = 16.0;
+
+// Dogs roaming around wherever their `Spawner` brought them in, as fast as the `Level` says. One
+// that spots a cat runs after it and bites, until the cat gets out of sight or the dog too far
+// from home, then it trots back.
+pub struct DogPlugin;
+
+impl Plugin for DogPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Dog>()
+            .register_type::<DogBehavior>()
+            .add_systems(Startup, load_dog_sheet)
+            .add_systems(Update, spawn_dogs.run_if(in_state(GameState::Playing)))
+            .add_systems(FixedUpdate, steer_dogs.in_set(MovementSystems::Move));
+    }
+}
+
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Dog {
+    // Cats closer than this are chased
+    pub sight_radius: f32,
+    // A chase ends once the dog is this far from home
+    pub leash_distance: f32,
+    // Speeds in pixels per second, and how quickly they're picked up
+    pub wander_speed: f32,
+    pub chase_speed: f32,
+    pub acceleration: f32,
+}
+
+impl Default for Dog {
+    fn default() -> Self {
+        Self {
+            sight_radius: 220.0,
+            leash_distance: 420.0,
+            wander_speed: 70.0,
+            chase_speed: 210.0,
+            acceleration: 900.0,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub enum DogBehavior {
+    // Ambling around home
+    #[default]
+    Wander,
+    Chase(Entity),
+    // Heading home, ignoring cats until it's there
+    GiveUp,
+}
+
+// Where a dog roams and the way it's currently ambling
+#[derive(Component)]
+struct Wander {
+    home: Vec2,
+    heading: Vec2,
+    // Until the next change of heading
+    timer: Timer,
+}
+
+fn load_dog_sheet(mut registry: ResMut<AnimationRegistry>, asset_server: Res<AssetServer>) {
+    registry.load(&asset_server, "dog", "dog.anim.ron");
+}
+
+fn spawn_dogs(
+    mut commands: Commands,
+    mut spawns: EventReader<Spawn>,
+    registry: Res<AnimationRegistry>,
+    sheets: Res<Assets<AnimationSheet>>,
+    layouts: Res<Assets<TextureAtlasLayout>>,
+    level: Res<Level>,
+    dogs: Query<(), With<Dog>>,
+) {
+    let Some(cell_size) = registry
+        .get(&sheets, "dog")
+        .and_then(|sheet| sheet.cell_size(&layouts))
+    else {
+        return;
+    };
+    let base = Dog::default();
+    let dog = Dog {
+        wander_speed: base.wander_speed * level.definition.dog_speed,
+        chase_speed: base.chase_speed * level.definition.dog_speed,
+        ..base
+    };
+    let mut number = dogs.iter().count();
+    for spawn in spawns.read() {
+        if spawn.prototype != Prototype::Dog {
+            continue;
+        }
+        number += 1;
+        let entity = spawn_dog(
+            &mut commands,
+            cell_size,
+            dog.clone(),
+            number,
+            spawn.position,
+        );
+        commands.entity(entity).insert(SpawnedBy(spawn.spawner));
+    }
+}
+
+fn spawn_dog(
+    commands: &mut Commands,
+    cell_size: Vec2,
+    dog: Dog,
+    number: usize,
+    position: Vec2,
+) -> Entity {
+    let state_clips = StateClips::default();
+    let mut entity = commands.spawn_animated("dog", &state_clips.idle);
+    entity.insert((
+        dog,
+        DogBehavior::default(),
+        Wander {
+            home: position,
+            heading: Vec2::ZERO,
+            timer: Timer::from_seconds(1.0, TimerMode::Once),
+        },
+        // A bite
+        Damage::new(DOG_DAMAGE).with_knockback(DOG_KNOCKBACK),
+        StateScoped(InGame),
+        LevelScoped,
+        Name::new(format!("Dog {number}")),
+        Transform::from_translation(position.extend(0.0)).with_scale(Vec3::splat(DOG_SCALE)),
+        AnimationState::default(),
+        Facing::default(),
+        state_clips,
+    ));
+    entity.insert((
+        Velocity::default(),
+        Collider::from_size(cell_size * DOG_SCALE * Vec2::new(0.8, 0.5)),
+        BoundaryBehavior::Clamp,
+        InterpolatedTranslation::default(),
+        MinimapIcon::new(DOG_COLOR),
+    ));
+    entity.id()
+}
+
+fn steer_dogs(
+    time: Res<Time>,
+    mut dogs: Query<(
+        &Dog,
+        &mut DogBehavior,
+        &mut Wander,
+        &Transform,
+        &mut Velocity,
+        &mut Facing,
+    )>,
+    cats: Query<(Entity, &Transform), With<Cat>>,
+) {
+    let mut rng = rand::thread_rng();
+    for (dog, mut behavior, mut wander, transform, mut velocity, mut facing) in &mut dogs {
+        let position = transform.translation.truncate();
+        let to_home = wander.home - position;
+        let cat_position = |cat: Entity| {
+            cats.get(cat)
+                .ok()
+                .map(|(_, cat)| cat.translation.truncate())
+        };
+
+        let next = match *behavior {
+            DogBehavior::Wander => cats
+                .iter()
+                .map(|(cat, cat_transform)| {
+                    (cat, cat_transform.translation.truncate().distance(position))
+                })
+                .filter(|(_, distance)| *distance < dog.sight_radius)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map_or(DogBehavior::Wander, |(cat, _)| DogBehavior::Chase(cat)),
+            DogBehavior::Chase(cat) => match cat_position(cat) {
+                Some(cat_position)
+                    if cat_position.distance(position) < dog.sight_radius
+                        && to_home.length() < dog.leash_distance =>
+                {
+                    DogBehavior::Chase(cat)
+                }
+                _ => DogBehavior::GiveUp,
+            },
+            DogBehavior::GiveUp if to_home.length() < HOME_RADIUS => DogBehavior::Wander,
+            DogBehavior::GiveUp => DogBehavior::GiveUp,
+        };
+        behavior.set_if_neq(next);
+
+        let target = match *behavior {
+            DogBehavior::Wander => {
+                if wander.timer.tick(time.delta()).finished() {
+                    // Now and then just stand there, otherwise amble off, back home if it's far
+                    wander.heading = if to_home.length() > WANDER_RADIUS {
+                        to_home.normalize()
+                    } else if rng.gen_bool(0.3) {
+                        Vec2::ZERO
+                    } else {
+                        Vec2::from_angle(rng.gen_range(0.0..std::f32::consts::TAU))
+                    };
+                    wander.timer = Timer::from_seconds(rng.gen_range(1.0..3.0), TimerMode::Once);
+                }
+                wander.heading * dog.wander_speed
+            }
+            DogBehavior::Chase(cat) => {
+                let cat_position = cat_position(cat).unwrap_or(position);
+                (cat_position - position).normalize_or_zero() * dog.chase_speed
+            }
+            DogBehavior::GiveUp => to_home.normalize_or_zero() * dog.wander_speed,
+        };
+        velocity.0 = velocity
+            .0
+            .move_towards(target, dog.acceleration * time.delta_secs());
+        if let Some(new_facing) = Facing::from_direction(target) {
+            facing.set_if_neq(new_facing);
+        }
+    }
+}
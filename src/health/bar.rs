@@ -0,0 +1,115 @@
+use bevy::prelude::*;
+use bevy::sprite::Anchor;
+use bevy::transform::TransformSystem;
+
+use super::Health;
+use crate::collision::Collider;
+use crate::movement::interpolate_translation;
+use crate::theme::Theme;
+
+const BAR_SIZE: Vec2 = Vec2::new(64.0, 6.0);
+// Gap between the top of the entity's collider and the bar
+const BAR_MARGIN: f32 = 12.0;
+// Above the sprites
+const BAR_Z: f32 = 10.0;
+// How quickly the fill catches up with a change in health, per second
+const FILL_RATE: f32 = 8.0;
+
+// Floating bar over every entity with `Health`, easing towards the new value whenever it changes
+pub(super) struct HealthBarPlugin;
+
+impl Plugin for HealthBarPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(spawn_health_bar).add_systems(
+            PostUpdate,
+            (follow_targets, animate_fill)
+                .after(interpolate_translation)
+                .before(TransformSystem::TransformPropagate),
+        );
+    }
+}
+
+// Kept as its own entity rather than a child, so the target's scale and squash don't bend it
+#[derive(Component)]
+struct HealthBar {
+    target: Entity,
+    // The fraction the fill currently shows
+    shown: f32,
+}
+
+#[derive(Component)]
+struct HealthFill;
+
+fn spawn_health_bar(
+    trigger: Trigger<OnAdd, Health>,
+    mut commands: Commands,
+    theme: Res<Theme>,
+    healths: Query<&Health>,
+) {
+    let target = trigger.target();
+    let shown = healths.get(target).map_or(1.0, Health::fraction);
+    commands.spawn((
+        HealthBar { target, shown },
+        Name::new("Health bar"),
+        Sprite::from_color(theme.track, BAR_SIZE),
+        Transform::default(),
+        // Hidden until it has been moved over its target
+        Visibility::Hidden,
+        children![(
+            HealthFill,
+            Sprite {
+                color: theme.meter,
+                custom_size: Some(Vec2::new(BAR_SIZE.x * shown, BAR_SIZE.y)),
+                anchor: Anchor::CenterLeft,
+                ..Default::default()
+            },
+            Transform::from_xyz(-BAR_SIZE.x / 2.0, 0.0, 0.1),
+        )],
+    ));
+}
+
+type TargetFilter = (With<Health>, Without<HealthBar>);
+
+fn follow_targets(
+    mut commands: Commands,
+    mut bars: Query<(Entity, &HealthBar, &mut Transform, &mut Visibility)>,
+    targets: Query<(&Transform, Option<&Collider>), TargetFilter>,
+) {
+    for (entity, bar, mut transform, mut visibility) in &mut bars {
+        let Ok((target, collider)) = targets.get(bar.target) else {
+            // The target died or lost its health
+            commands.entity(entity).despawn();
+            continue;
+        };
+        let height = collider.map_or(0.0, |collider| collider.half_extents.y);
+        transform.translation =
+            target.translation.truncate().extend(BAR_Z) + Vec3::Y * (height + BAR_MARGIN);
+        *visibility = Visibility::Inherited;
+    }
+}
+
+fn animate_fill(
+    time: Res<Time>,
+    mut bars: Query<(&mut HealthBar, &Children)>,
+    healths: Query<&Health>,
+    mut fills: Query<&mut Sprite, With<HealthFill>>,
+) {
+    for (mut bar, children) in &mut bars {
+        let Ok(health) = healths.get(bar.target) else {
+            continue;
+        };
+        let target = health.fraction();
+        if bar.shown == target {
+            continue;
+        }
+        bar.shown += (target - bar.shown) * (FILL_RATE * time.delta_secs()).min(1.0);
+        if (bar.shown - target).abs() < 0.001 {
+            bar.shown = target;
+        }
+        for &child in children {
+            if let Ok(mut fill) = fills.get_mut(child) {
+                fill.custom_size = Some(Vec2::new(BAR_SIZE.x * bar.shown, BAR_SIZE.y));
+            }
+        }
+    }
+}
@@ -0,0 +1,402 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::accessibility::{Accessibility, reduced_motion};
+use crate::animation::AnimationSystems;
+use crate::collision::Collision;
+use crate::movement::{Impulse, MovementSystems};
+use crate::state::GameState;
+
+mod bar;
+
+// Seconds a hit entity can't be hurt again
+const INVULNERABLE_SECONDS: f32 = 1.0;
+// Seconds the sprite of a hit entity stays tinted
+const HIT_FLASH_SECONDS: f32 = 0.15;
+const HIT_FLASH_COLOR: Color = Color::srgb(1.0, 0.25, 0.25);
+// How often an invulnerable sprite blinks, per second
+const BLINK_RATE: f32 = 10.0;
+
+// Health, and losing it to whatever `Damage` it runs into. A hit flashes the sprite and leaves a
+// moment of invulnerability, during which the sprite blinks. Running out of health sends `Died`,
+// what happens then is up to whoever owns the entity.
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Health>()
+            .register_type::<Damage>()
+            .register_type::<Invulnerable>()
+            .add_event::<DamageTaken>()
+            .add_event::<Died>()
+            .add_plugins(bar::HealthBarPlugin)
+            .add_systems(
+                FixedUpdate,
+                (tick_invulnerability, apply_contact_damage)
+                    .chain()
+                    .after(MovementSystems::Collide)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            // After the cats' tint, which would otherwise paint over the flash
+            .add_systems(
+                Update,
+                (flash_hits, blink_invulnerable).after(AnimationSystems::Advance),
+            );
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.max > 0.0 {
+            (self.current / self.max).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+}
+
+// Hurts anything with `Health` it touches, e.g. a dog's bite
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Damage {
+    pub amount: f32,
+    // Speed the victim is shoved away with, in pixels per second
+    pub knockback: f32,
+}
+
+impl Damage {
+    pub fn new(amount: f32) -> Self {
+        Self {
+            amount,
+            knockback: 0.0,
+        }
+    }
+
+    pub fn with_knockback(mut self, knockback: f32) -> Self {
+        self.knockback = knockback;
+        self
+    }
+}
+
+// Invulnerability frames: nothing hurts the entity until the timer runs out
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Invulnerable {
+    pub timer: Timer,
+    // Whether the sprite blinks meanwhile, as it does after a hit
+    pub blink: bool,
+}
+
+impl Invulnerable {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+            blink: true,
+        }
+    }
+
+    pub fn without_blink(mut self) -> Self {
+        self.blink = false;
+        self
+    }
+
+    pub fn extend(&mut self, seconds: f32) {
+        let Ok(extra) = Duration::try_from_secs_f32(seconds) else {
+            warn!("can't extend invulnerability by {seconds} seconds");
+            return;
+        };
+        let duration = self.timer.duration() + extra;
+        self.timer.set_duration(duration);
+    }
+}
+
+// Sent (and triggered on the entity) whenever it loses health
+#[derive(Event, Clone, Debug)]
+pub struct DamageTaken {
+    pub entity: Entity,
+    pub amount: f32,
+    // What dealt the damage
+    pub source: Entity,
+}
+
+// Sent (and triggered on the entity) when its health runs out. The entity is left alone.
+#[derive(Event, Clone, Debug)]
+pub struct Died {
+    pub entity: Entity,
+}
+
+// Tints the sprite for a moment, coming back to the color it had when it was hit
+#[derive(Component)]
+struct HitFlash {
+    timer: Timer,
+    color: Color,
+}
+
+fn tick_invulnerability(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Invulnerable, Option<&mut Visibility>)>,
+) {
+    for (entity, mut invulnerable, visibility) in &mut query {
+        if invulnerable.timer.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<Invulnerable>();
+            // Don't leave it halfway through a blink
+            if let Some(mut visibility) = visibility {
+                *visibility = Visibility::Inherited;
+            }
+        }
+    }
+}
+
+// What a hit changes about whoever takes it
+type Victim = (
+    &'static mut Health,
+    Option<&'static mut Impulse>,
+    Option<&'static Sprite>,
+);
+
+fn apply_contact_damage(
+    mut commands: Commands,
+    mut collisions: EventReader<Collision>,
+    mut damage_taken: EventWriter<DamageTaken>,
+    mut deaths: EventWriter<Died>,
+    dealers: Query<&Damage>,
+    mut victims: Query<Victim, Without<Invulnerable>>,
+) {
+    // Overlaps are reported every step, but a hit only counts once until the i-frames are over
+    let mut hit = HashSet::new();
+    for collision in collisions.read() {
+        // `normal` points from `other` towards `entity`, i.e. away from `other`
+        for (victim, source, away) in [
+            (collision.entity, collision.other, collision.normal),
+            (collision.other, collision.entity, -collision.normal),
+        ] {
+            let Ok(damage) = dealers.get(source) else {
+                continue;
+            };
+            let Ok((mut health, impulse, sprite)) = victims.get_mut(victim) else {
+                continue;
+            };
+            if health.is_dead() || !hit.insert(victim) {
+                continue;
+            }
+            health.current = (health.current - damage.amount).max(0.0);
+
+            let event = DamageTaken {
+                entity: victim,
+                amount: damage.amount,
+                source,
+            };
+            commands.trigger_targets(event.clone(), victim);
+            damage_taken.write(event);
+            if health.is_dead() {
+                let event = Died { entity: victim };
+                commands.trigger_targets(event.clone(), victim);
+                deaths.write(event);
+                continue;
+            }
+
+            let mut victim = commands.entity(victim);
+            victim.insert(Invulnerable::new(INVULNERABLE_SECONDS));
+            if let Some(sprite) = sprite {
+                victim.insert(HitFlash {
+                    timer: Timer::from_seconds(HIT_FLASH_SECONDS, TimerMode::Once),
+                    color: sprite.color,
+                });
+            }
+            let shove = away * damage.knockback;
+            if shove != Vec2::ZERO {
+                match impulse {
+                    Some(mut impulse) => impulse.add(shove),
+                    None => {
+                        victim.insert(Impulse::new(shove));
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn flash_hits(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut HitFlash, &mut Sprite)>,
+) {
+    for (entity, mut flash, mut sprite) in &mut query {
+        flash.timer.tick(time.delta());
+        if flash.timer.finished() {
+            sprite.color = flash.color;
+            commands.entity(entity).remove::<HitFlash>();
+            continue;
+        }
+        sprite.color = flash
+            .color
+            .mix(&HIT_FLASH_COLOR, 1.0 - flash.timer.fraction());
+    }
+}
+
+// Blinking is flashing too, so it's left out with reduced motion
+fn blink_invulnerable(
+    accessibility: Option<Res<Accessibility>>,
+    mut query: Query<(&Invulnerable, &mut Visibility)>,
+) {
+    if reduced_motion(accessibility) {
+        return;
+    }
+    for (invulnerable, mut visibility) in &mut query {
+        let shown = !invulnerable.blink
+            || ((invulnerable.timer.elapsed_secs() * BLINK_RATE * 2.0) as u32).is_multiple_of(2);
+        visibility.set_if_neq(if shown {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.init_resource::<Events<Collision>>();
+        world.init_resource::<Events<DamageTaken>>();
+        world.init_resource::<Events<Died>>();
+        world
+    }
+
+    // Reports the overlap the way `resolve_collisions` does and lets the damage system see it
+    fn touch(world: &mut World, entity: Entity, other: Entity) {
+        world.send_event(Collision {
+            entity,
+            other,
+            normal: Vec2::X,
+        });
+        world.run_system_once(apply_contact_damage).unwrap();
+    }
+
+    fn health(world: &World, entity: Entity) -> f32 {
+        world.get::<Health>(entity).unwrap().current
+    }
+
+    #[test]
+    fn damage_takes_health_and_leaves_i_frames() {
+        let mut world = world();
+        let cat = world.spawn(Health::new(3.0)).id();
+        let dog = world.spawn(Damage::new(1.0)).id();
+        touch(&mut world, cat, dog);
+        assert_eq!(health(&world, cat), 2.0);
+        assert!(world.get::<Invulnerable>(cat).is_some());
+        assert_eq!(world.resource::<Events<DamageTaken>>().len(), 1);
+    }
+
+    #[test]
+    fn damage_works_whichever_way_round_the_collision_is() {
+        let mut world = world();
+        let cat = world.spawn(Health::new(3.0)).id();
+        let dog = world.spawn(Damage::new(1.0)).id();
+        touch(&mut world, dog, cat);
+        assert_eq!(health(&world, cat), 2.0);
+    }
+
+    #[test]
+    fn i_frames_ignore_further_hits() {
+        let mut world = world();
+        let cat = world.spawn(Health::new(3.0)).id();
+        let dog = world.spawn(Damage::new(1.0)).id();
+        touch(&mut world, cat, dog);
+        touch(&mut world, cat, dog);
+        assert_eq!(health(&world, cat), 2.0);
+    }
+
+    #[test]
+    fn a_hit_counts_once_per_step() {
+        let mut world = world();
+        let cat = world.spawn(Health::new(3.0)).id();
+        let dog = world.spawn(Damage::new(1.0)).id();
+        let other_dog = world.spawn(Damage::new(1.0)).id();
+        world.send_event(Collision {
+            entity: cat,
+            other: dog,
+            normal: Vec2::X,
+        });
+        touch(&mut world, cat, other_dog);
+        assert_eq!(health(&world, cat), 2.0);
+    }
+
+    #[test]
+    fn i_frames_run_out() {
+        let mut world = world();
+        let cat = world.spawn((Health::new(3.0), Invulnerable::new(1.0))).id();
+        world.init_resource::<Time>();
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(500));
+        world.run_system_once(tick_invulnerability).unwrap();
+        assert!(world.get::<Invulnerable>(cat).is_some());
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(500));
+        world.run_system_once(tick_invulnerability).unwrap();
+        assert!(world.get::<Invulnerable>(cat).is_none());
+    }
+
+    #[test]
+    fn knockback_shoves_the_victim_away() {
+        let mut world = world();
+        let cat = world.spawn(Health::new(3.0)).id();
+        let dog = world.spawn(Damage::new(1.0).with_knockback(200.0)).id();
+        touch(&mut world, cat, dog);
+        assert_eq!(
+            world.get::<Impulse>(cat).unwrap().velocity,
+            Vec2::new(200.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn running_out_of_health_sends_died_instead_of_i_frames() {
+        let mut world = world();
+        let cat = world.spawn(Health::new(1.0)).id();
+        let dog = world.spawn(Damage::new(2.0)).id();
+        touch(&mut world, cat, dog);
+        assert_eq!(health(&world, cat), 0.0);
+        assert!(world.get::<Health>(cat).unwrap().is_dead());
+        assert!(world.get::<Invulnerable>(cat).is_none());
+        assert_eq!(world.resource::<Events<Died>>().len(), 1);
+    }
+
+    #[test]
+    fn extend_adds_to_the_invulnerability_left() {
+        let mut invulnerable = Invulnerable::new(1.0);
+        invulnerable.extend(2.0);
+        assert_eq!(invulnerable.timer.duration(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn extend_ignores_seconds_that_are_not_a_duration() {
+        let mut invulnerable = Invulnerable::new(1.0);
+        invulnerable.extend(-2.0);
+        invulnerable.extend(f32::NAN);
+        assert_eq!(invulnerable.timer.duration(), Duration::from_secs(1));
+    }
+}
@@ -0,0 +1,247 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::mode::GameMode;
+use crate::score::Score;
+use crate::settings::Settings;
+use crate::state::GameState;
+
+const HIGH_SCORES_FILE: &str = "highscores.ron";
+// How many scores each mode keeps
+pub const MAX_HIGH_SCORES: usize = 10;
+
+// The best scores of each game mode, kept in config/highscores.ron under the player name from
+// the settings. A finished game's score goes in as soon as the game is over.
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(config::load_or_default::<HighScores>(HIGH_SCORES_FILE))
+            .register_type::<HighScores>()
+            .add_systems(OnEnter(GameState::GameOver), record_high_score)
+            .add_systems(OnExit(GameState::GameOver), forget_new_high_score);
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Reflect)]
+pub struct HighScore {
+    pub name: String,
+    pub score: u32,
+    // The day it was set, as YYYY-MM-DD
+    pub date: String,
+    pub mode: GameMode,
+}
+
+// Best first. Each mode has its own top ten, so a timed game's fish aren't measured against a
+// whole run through the levels.
+#[derive(Resource, Clone, Debug, Default, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct HighScores(pub Vec<HighScore>);
+
+impl HighScores {
+    pub fn top(&self, mode: GameMode) -> impl Iterator<Item = &HighScore> {
+        self.0
+            .iter()
+            .filter(move |high_score| high_score.mode == mode)
+    }
+
+    // Adds `high_score` if it's good enough for its mode's table, returning its place there (0 is
+    // the best). A tie goes below the scores that were there first.
+    pub fn insert(&mut self, high_score: HighScore) -> Option<usize> {
+        let rank = self
+            .top(high_score.mode)
+            .take_while(|other| other.score >= high_score.score)
+            .count();
+        if high_score.score == 0 || rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+        let mode = high_score.mode;
+        let index = self
+            .0
+            .iter()
+            .position(|other| other.mode == mode && other.score < high_score.score)
+            .unwrap_or(self.0.len());
+        self.0.insert(index, high_score);
+        // Drop whatever was pushed out of the table
+        let mut kept = 0;
+        self.0.retain(|other| {
+            if other.mode != mode {
+                return true;
+            }
+            kept += 1;
+            kept <= MAX_HIGH_SCORES
+        });
+        Some(rank)
+    }
+}
+
+// The place the game that just ended took in its mode's table, while the game over screen is up
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct NewHighScore {
+    pub rank: usize,
+}
+
+impl NewHighScore {
+    // Better than every score before it
+    pub fn is_record(&self) -> bool {
+        self.rank == 0
+    }
+}
+
+pub fn record_high_score(
+    mut commands: Commands,
+    mut high_scores: ResMut<HighScores>,
+    score: Res<Score>,
+    mode: Res<GameMode>,
+    settings: Res<Settings>,
+) {
+    let high_score = HighScore {
+        name: settings.player_name.clone(),
+        score: score.0,
+        date: today(),
+        mode: *mode,
+    };
+    let Some(rank) = high_scores.insert(high_score) else {
+        return;
+    };
+    commands.insert_resource(NewHighScore { rank });
+    config::save_or_warn(HIGH_SCORES_FILE, &*high_scores);
+}
+
+fn forget_new_high_score(mut commands: Commands) {
+    commands.remove_resource::<NewHighScore>();
+}
+
+// Today's date in UTC, as YYYY-MM-DD
+fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+// Days since 1970-01-01 to a year, month and day of the Gregorian calendar, after Howard
+// Hinnant's `civil_from_days`
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn high_score(name: &str, score: u32, mode: GameMode) -> HighScore {
+        HighScore {
+            name: name.to_owned(),
+            score,
+            date: "2026-01-01".to_owned(),
+            mode,
+        }
+    }
+
+    fn scores(high_scores: &HighScores, mode: GameMode) -> Vec<u32> {
+        high_scores
+            .top(mode)
+            .map(|high_score| high_score.score)
+            .collect()
+    }
+
+    #[test]
+    fn insert_keeps_the_best_first() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(
+            high_scores.insert(high_score("a", 5, GameMode::Levels)),
+            Some(0)
+        );
+        assert_eq!(
+            high_scores.insert(high_score("b", 9, GameMode::Levels)),
+            Some(0)
+        );
+        assert_eq!(
+            high_scores.insert(high_score("c", 7, GameMode::Levels)),
+            Some(1)
+        );
+        assert_eq!(scores(&high_scores, GameMode::Levels), [9, 7, 5]);
+    }
+
+    #[test]
+    fn insert_puts_a_tie_below() {
+        let mut high_scores = HighScores::default();
+        high_scores.insert(high_score("first", 5, GameMode::Levels));
+        assert_eq!(
+            high_scores.insert(high_score("second", 5, GameMode::Levels)),
+            Some(1)
+        );
+        let names: Vec<&str> = high_scores
+            .top(GameMode::Levels)
+            .map(|high_score| high_score.name.as_str())
+            .collect();
+        assert_eq!(names, ["first", "second"]);
+    }
+
+    #[test]
+    fn insert_skips_a_zero_score() {
+        let mut high_scores = HighScores::default();
+        assert_eq!(
+            high_scores.insert(high_score("a", 0, GameMode::Levels)),
+            None
+        );
+        assert!(high_scores.0.is_empty());
+    }
+
+    #[test]
+    fn insert_keeps_a_full_table_per_mode() {
+        let mut high_scores = HighScores::default();
+        for score in 1..=MAX_HIGH_SCORES as u32 {
+            high_scores.insert(high_score("a", score, GameMode::Levels));
+        }
+        assert_eq!(
+            high_scores.insert(high_score("b", 3, GameMode::Countdown)),
+            Some(0)
+        );
+        // Not better than anything in a full table
+        assert_eq!(
+            high_scores.insert(high_score("c", 1, GameMode::Levels)),
+            None
+        );
+        // Pushes the worst one out
+        assert_eq!(
+            high_scores.insert(high_score("d", 20, GameMode::Levels)),
+            Some(0)
+        );
+        let levels = scores(&high_scores, GameMode::Levels);
+        assert_eq!(levels.len(), MAX_HIGH_SCORES);
+        assert_eq!(levels.first(), Some(&20));
+        assert_eq!(levels.last(), Some(&2));
+        assert_eq!(scores(&high_scores, GameMode::Countdown), [3]);
+    }
+
+    #[test]
+    fn civil_from_days_matches_the_calendar() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(19_723), (2024, 1, 1));
+        assert_eq!(civil_from_days(20_742), (2026, 10, 16));
+        assert_eq!(civil_from_days(-25_508), (1900, 3, 1));
+    }
+}
@@ -0,0 +1,115 @@
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+
+use crate::input::{CatAction, Keybindings};
+use crate::localization::Localization;
+use crate::state::InGame;
+use crate::theme::{Surface, TextRole, Theme};
+
+// Corner panel listing the current keyboard bindings during a game, rebuilt whenever they're
+// remapped. F4 hides and shows it.
+pub struct ControlHintsPlugin;
+
+impl Plugin for ControlHintsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ControlHints>()
+            .add_systems(OnEnter(InGame), spawn_hints)
+            .add_systems(
+                Update,
+                (
+                    toggle_hints.run_if(input_just_pressed(KeyCode::F4)),
+                    update_hints_text.run_if(
+                        resource_changed::<Keybindings>
+                            .or(resource_changed::<Localization>)
+                            .or(any_match_filter::<Added<HintsText>>),
+                    ),
+                )
+                    .run_if(in_state(InGame)),
+            );
+    }
+}
+
+#[derive(Resource, Debug)]
+pub struct ControlHints {
+    pub visible: bool,
+}
+
+impl Default for ControlHints {
+    fn default() -> Self {
+        Self { visible: true }
+    }
+}
+
+#[derive(Component)]
+struct HintsPanel;
+
+#[derive(Component)]
+struct HintsText;
+
+fn spawn_hints(mut commands: Commands, theme: Res<Theme>, hints: Res<ControlHints>) {
+    commands.spawn((
+        HintsPanel,
+        StateScoped(InGame),
+        Name::new("Control hints"),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(8.0),
+            right: Val::Px(8.0),
+            padding: theme.panel_padding(),
+            ..Default::default()
+        },
+        theme.surface(Surface::Panel),
+        visibility(hints.visible),
+        children![(HintsText, Text::default(), theme.text(TextRole::Small))],
+    ));
+}
+
+fn toggle_hints(
+    mut hints: ResMut<ControlHints>,
+    mut panel: Single<&mut Visibility, With<HintsPanel>>,
+) {
+    hints.visible = !hints.visible;
+    **panel = visibility(hints.visible);
+}
+
+fn visibility(visible: bool) -> Visibility {
+    if visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    }
+}
+
+fn update_hints_text(
+    keybindings: Res<Keybindings>,
+    localization: Res<Localization>,
+    mut text: Single<&mut Text, With<HintsText>>,
+) {
+    let key = |action| key_name(keybindings.key(action));
+    let mut lines = vec![format!(
+        "{}{}{}{} {}",
+        key(CatAction::MoveUp),
+        key(CatAction::MoveLeft),
+        key(CatAction::MoveDown),
+        key(CatAction::MoveRight),
+        localization.get("hints-move")
+    )];
+    for action in CatAction::ALL.into_iter().skip(4) {
+        lines.push(format!(
+            "{} {}",
+            key(action),
+            localization.get(action.label())
+        ));
+    }
+    lines.push(format!("F4 {}", localization.get("hints-hide")));
+    text.0 = lines.join("\n");
+}
+
+// "KeyW" as "W", "ShiftLeft" stays as it is
+fn key_name(key: KeyCode) -> String {
+    let name = format!("{key:?}");
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_owned()
+}
@@ -0,0 +1,277 @@
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+
+use crate::cat::ControlledBy;
+use crate::localization::LocalizedText;
+use crate::mode::{Countdown, GameMode};
+use crate::movement::Stamina;
+use crate::power_up::{PowerUpKind, PowerUps};
+use crate::score::ScoreChanged;
+use crate::state::{GameState, InGame};
+use crate::theme::{Surface, TextRole, Theme};
+use crate::tween::Tween;
+
+pub const HUD_HEIGHT: f32 = 36.0;
+const STAMINA_BAR_WIDTH: f32 = 160.0;
+const POWER_UP_ICON_SIZE: f32 = 14.0;
+// The countdown's last seconds
+const WARNING_COLOR: Color = Color::srgb(1.0, 0.3, 0.25);
+
+// Bar along the top of the screen during a game, showing the score, the controlled cat's
+// power-ups with the seconds they have left, the time played (or left, in a timed game) and the
+// controlled cat's stamina.
+// Each value is only redrawn when what it shows changes, and the score gives a little punch
+// whenever points come in.
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PlayTime>()
+            .register_type::<PlayTime>()
+            .add_systems(OnEnter(InGame), (reset_game_stats, spawn_hud))
+            .add_systems(Update, tick_play_time.run_if(in_state(GameState::Playing)))
+            .add_systems(
+                Update,
+                (
+                    update_score_text.run_if(on_event::<ScoreChanged>),
+                    update_time_text.run_if(
+                        resource_changed::<PlayTime>.and(resource_equals(GameMode::Levels)),
+                    ),
+                    update_countdown_text.run_if(
+                        resource_changed::<Countdown>.and(resource_equals(GameMode::Countdown)),
+                    ),
+                    update_stamina_bar,
+                    update_power_up_icons,
+                )
+                    .run_if(in_state(InGame)),
+            );
+    }
+}
+
+// How long the current game has been played, pauses not included
+#[derive(Resource, Default, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct PlayTime(pub Stopwatch);
+
+#[derive(Component)]
+pub struct Hud;
+
+#[derive(Component)]
+struct ScoreText;
+
+#[derive(Component)]
+struct TimeText;
+
+#[derive(Component)]
+struct PowerUpIcons;
+
+#[derive(Component)]
+struct PowerUpIcon(PowerUpKind);
+
+#[derive(Component)]
+struct PowerUpCountdown;
+
+#[derive(Component)]
+struct StaminaFill;
+
+fn reset_game_stats(mut play_time: ResMut<PlayTime>) {
+    play_time.0.reset();
+}
+
+fn spawn_hud(mut commands: Commands, theme: Res<Theme>) {
+    let font = theme.text(TextRole::Body);
+    commands.spawn((
+        Hud,
+        StateScoped(InGame),
+        Name::new("HUD"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            height: Val::Px(HUD_HEIGHT),
+            padding: UiRect::horizontal(Val::Px(16.0)),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::SpaceBetween,
+            ..Default::default()
+        },
+        theme.surface(Surface::Panel),
+        // Drops in from above the window
+        Tween::new(0.3).slide(Vec2::new(0.0, -HUD_HEIGHT), Vec2::ZERO),
+        children![
+            (
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(theme.gap),
+                    ..Default::default()
+                },
+                children![
+                    (
+                        ScoreText,
+                        Text::default(),
+                        LocalizedText::new("hud-score").with_arg("score", 0),
+                        font.clone(),
+                    ),
+                    (
+                        PowerUpIcons,
+                        Node {
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(theme.gap),
+                            ..Default::default()
+                        },
+                    ),
+                ],
+            ),
+            (TimeText, Text::new("0:00"), font.clone()),
+            (
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(theme.gap / 2.0),
+                    ..Default::default()
+                },
+                children![
+                    (Text::default(), LocalizedText::new("hud-stamina"), font),
+                    (
+                        Node {
+                            width: Val::Px(STAMINA_BAR_WIDTH),
+                            height: Val::Px(10.0),
+                            ..Default::default()
+                        },
+                        theme.surface(Surface::Track),
+                        children![(
+                            StaminaFill,
+                            Node {
+                                width: Val::Percent(100.0),
+                                height: Val::Percent(100.0),
+                                ..Default::default()
+                            },
+                            theme.surface(Surface::Meter),
+                        )],
+                    ),
+                ],
+            ),
+        ],
+    ));
+}
+
+fn tick_play_time(time: Res<Time>, mut play_time: ResMut<PlayTime>) {
+    play_time.0.tick(time.delta());
+}
+
+fn update_score_text(
+    mut commands: Commands,
+    mut changes: EventReader<ScoreChanged>,
+    text: Single<(Entity, &mut LocalizedText), With<ScoreText>>,
+) {
+    let Some(change) = changes.read().last() else {
+        return;
+    };
+    let (entity, mut text) = text.into_inner();
+    text.set_arg("score", change.total);
+    commands.entity(entity).insert(
+        Tween::new(0.2)
+            .with_ease(EaseFunction::BackOut)
+            .scale(1.4, 1.0),
+    );
+}
+
+fn clock(seconds: u64) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+fn update_time_text(play_time: Res<PlayTime>, mut text: Single<&mut Text, With<TimeText>>) {
+    let shown = clock(play_time.0.elapsed().as_secs());
+    // The stopwatch changes every frame, the text only once a second
+    if text.0 != shown {
+        text.0 = shown;
+    }
+}
+
+// Once time is nearly up the countdown turns red and punches every second, along with the ticks
+fn update_countdown_text(
+    mut commands: Commands,
+    countdown: Res<Countdown>,
+    text: Single<(Entity, &mut Text, &mut TextColor), With<TimeText>>,
+) {
+    let shown = clock(countdown.seconds_left().into());
+    let (entity, mut text, mut color) = text.into_inner();
+    if text.0 == shown {
+        return;
+    }
+    text.0 = shown;
+    if countdown.is_warning() {
+        color.0 = WARNING_COLOR;
+        commands.entity(entity).insert(
+            Tween::new(0.2)
+                .with_ease(EaseFunction::BackOut)
+                .scale(1.4, 1.0),
+        );
+    }
+}
+
+// The controlled cat, when its stamina changed or it just became the controlled one
+type StaminaChanged = (
+    With<ControlledBy>,
+    Or<(Changed<Stamina>, Added<ControlledBy>)>,
+);
+
+fn update_stamina_bar(
+    cat: Single<&Stamina, StaminaChanged>,
+    mut fill: Single<&mut Node, With<StaminaFill>>,
+) {
+    fill.width = Val::Percent(cat.fraction() * 100.0);
+}
+
+// One icon per power-up, in the order they were picked up, counting down the whole seconds left
+fn update_power_up_icons(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    cat: Option<Single<&PowerUps, With<ControlledBy>>>,
+    container: Single<Entity, With<PowerUpIcons>>,
+    icons: Query<(Entity, &PowerUpIcon, &Children)>,
+    mut countdowns: Query<&mut Text, With<PowerUpCountdown>>,
+) {
+    let active = cat.as_deref().map_or(&[][..], |power_ups| &power_ups.0[..]);
+    for (entity, icon, _) in &icons {
+        if !active.iter().any(|power_up| power_up.kind == icon.0) {
+            commands.entity(entity).despawn();
+        }
+    }
+    for power_up in active {
+        let seconds = power_up.timer.remaining_secs().ceil() as u32;
+        let shown = seconds.to_string();
+        let icon = icons.iter().find(|(_, icon, _)| icon.0 == power_up.kind);
+        let Some((_, _, children)) = icon else {
+            commands.spawn((
+                PowerUpIcon(power_up.kind),
+                ChildOf(*container),
+                Node {
+                    align_items: AlignItems::Center,
+                    column_gap: Val::Px(theme.gap / 2.0),
+                    ..Default::default()
+                },
+                children![
+                    (
+                        Node {
+                            width: Val::Px(POWER_UP_ICON_SIZE),
+                            height: Val::Px(POWER_UP_ICON_SIZE),
+                            ..Default::default()
+                        },
+                        BackgroundColor(power_up.kind.color()),
+                    ),
+                    (
+                        PowerUpCountdown,
+                        Text::new(shown),
+                        theme.text(TextRole::Body)
+                    ),
+                ],
+            ));
+            continue;
+        };
+        let mut texts = countdowns.iter_many_mut(children);
+        while let Some(mut text) = texts.fetch_next() {
+            if text.0 != shown {
+                text.0 = shown.clone();
+            }
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use std::collections::{HashMap, HashSet};
+
+use bevy::input::InputSystem;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+mod rebinding;
+mod recording;
+mod virtual_controls;
+
+pub use rebinding::{ControlsScreen, Rebinding, is_rebinding, toggle_controls_screen};
+pub use recording::{InputRecording, RecordedFrame};
+pub use virtual_controls::VirtualControls;
+
+const KEYBINDINGS_FILE: &str = "keybindings.ron";
+
+// How far (in logical pixels) a finger has to drag for full speed
+const TOUCH_DRAG_RADIUS: f32 = 120.0;
+// Touches that end sooner and closer than this to where they began count as taps
+const TAP_MAX_SECONDS: f32 = 0.25;
+const TAP_MAX_DISTANCE: f32 = 20.0;
+
+pub struct GameInputPlugin;
+
+impl Plugin for GameInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(config::load_or_default::<Keybindings>(KEYBINDINGS_FILE))
+            .init_resource::<ActionState>()
+            .configure_sets(
+                PreUpdate,
+                (
+                    ActionSystems::Clear,
+                    ActionSystems::Collect,
+                    ActionSystems::Resolve,
+                )
+                    .chain()
+                    .after(InputSystem),
+            )
+            .add_systems(PreUpdate, clear_actions.in_set(ActionSystems::Clear))
+            .add_systems(
+                PreUpdate,
+                (read_keyboard, read_gamepads, read_touches)
+                    .run_if(not(is_rebinding))
+                    .in_set(ActionSystems::Collect),
+            )
+            .add_plugins((
+                rebinding::RebindingPlugin,
+                virtual_controls::VirtualControlsPlugin,
+            ))
+            .init_resource::<InputRecording>()
+            .add_systems(PreUpdate, resolve_actions.in_set(ActionSystems::Resolve))
+            .add_systems(
+                PreUpdate,
+                recording::record_actions.after(ActionSystems::Resolve),
+            )
+            .add_systems(Update, recording::dump_recording)
+            .add_systems(
+                Last,
+                save_keybindings.run_if(
+                    resource_changed::<Keybindings>.and(not(resource_added::<Keybindings>)),
+                ),
+            );
+    }
+}
+
+// Everything the player can ask the cat to do, whatever the device
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CatAction {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Uia,
+    Dash,
+    Sprint,
+    Throw,
+    FollowMouse,
+    Jump,
+    SwitchMode,
+    SwitchCat,
+    ToggleMute,
+}
+
+impl CatAction {
+    pub const ALL: [CatAction; 13] = [
+        CatAction::MoveUp,
+        CatAction::MoveDown,
+        CatAction::MoveLeft,
+        CatAction::MoveRight,
+        CatAction::Uia,
+        CatAction::Dash,
+        CatAction::Sprint,
+        CatAction::Throw,
+        CatAction::FollowMouse,
+        CatAction::Jump,
+        CatAction::SwitchMode,
+        CatAction::SwitchCat,
+        CatAction::ToggleMute,
+    ];
+
+    // Localization key of the action's name
+    pub fn label(self) -> &'static str {
+        match self {
+            CatAction::MoveUp => "action-move-up",
+            CatAction::MoveDown => "action-move-down",
+            CatAction::MoveLeft => "action-move-left",
+            CatAction::MoveRight => "action-move-right",
+            CatAction::Uia => "action-uia",
+            CatAction::Dash => "action-dash",
+            CatAction::Sprint => "action-sprint",
+            CatAction::Throw => "action-throw",
+            CatAction::FollowMouse => "action-follow-mouse",
+            CatAction::Jump => "action-jump",
+            CatAction::SwitchMode => "action-switch-mode",
+            CatAction::SwitchCat => "action-switch-cat",
+            CatAction::ToggleMute => "action-toggle-mute",
+        }
+    }
+
+    // Whether two actions may share an input: UIA only plays top-down and jumping only happens
+    // in platformer mode
+    pub fn can_share_input(self, other: CatAction) -> bool {
+        matches!(
+            (self, other),
+            (CatAction::Uia, CatAction::Jump) | (CatAction::Jump, CatAction::Uia)
+        )
+    }
+}
+
+// Input sources translate raw input into actions in `ActionSystems::Collect`
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ActionSystems {
+    Clear,
+    Collect,
+    Resolve,
+}
+
+// The actions held this frame, which gameplay reads instead of keyboard or gamepad state
+#[derive(Resource, Default, Debug)]
+pub struct ActionState {
+    pressed: HashSet<CatAction>,
+    previous: HashSet<CatAction>,
+    just_pressed: HashSet<CatAction>,
+    // Analog movement, e.g. from a stick, with a length of at most 1
+    axis: Vec2,
+}
+
+impl ActionState {
+    pub fn press(&mut self, action: CatAction) {
+        self.pressed.insert(action);
+    }
+
+    // Only the first non-zero axis of a frame is kept
+    pub fn set_axis(&mut self, axis: Vec2) {
+        if self.axis == Vec2::ZERO {
+            self.axis = axis.clamp_length_max(1.0);
+        }
+    }
+
+    pub fn pressed(&self, action: CatAction) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn just_pressed(&self, action: CatAction) -> bool {
+        self.just_pressed.contains(&action)
+    }
+
+    // Where the player wants to go: held directions win over the analog axis
+    pub fn movement(&self) -> Vec2 {
+        let mut direction = Vec2::ZERO;
+        if self.pressed(CatAction::MoveUp) {
+            direction.y += 1.0;
+        }
+        if self.pressed(CatAction::MoveDown) {
+            direction.y -= 1.0;
+        }
+        if self.pressed(CatAction::MoveLeft) {
+            direction.x -= 1.0;
+        }
+        if self.pressed(CatAction::MoveRight) {
+            direction.x += 1.0;
+        }
+        // Normalize the direction vector to maintain consistent speed
+        let direction = direction.normalize_or_zero();
+        if direction == Vec2::ZERO {
+            self.axis
+        } else {
+            direction
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keybindings {
+    pub move_up: KeyCode,
+    pub move_down: KeyCode,
+    pub move_left: KeyCode,
+    pub move_right: KeyCode,
+    pub uia: KeyCode,
+    pub dash: KeyCode,
+    pub sprint: KeyCode,
+    pub throw: KeyCode,
+    pub follow_mouse: KeyCode,
+    pub jump: KeyCode,
+    pub switch_mode: KeyCode,
+    pub switch_cat: KeyCode,
+    pub toggle_mute: KeyCode,
+    pub gamepad: HashMap<CatAction, GamepadButton>,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self {
+            move_up: KeyCode::KeyW,
+            move_down: KeyCode::KeyS,
+            move_left: KeyCode::KeyA,
+            move_right: KeyCode::KeyD,
+            uia: KeyCode::Space,
+            dash: KeyCode::ShiftLeft,
+            sprint: KeyCode::ControlLeft,
+            throw: KeyCode::KeyE,
+            follow_mouse: KeyCode::KeyF,
+            jump: KeyCode::Space,
+            switch_mode: KeyCode::KeyG,
+            switch_cat: KeyCode::Tab,
+            toggle_mute: KeyCode::KeyM,
+            gamepad: CatAction::ALL
+                .into_iter()
+                .map(|action| (action, default_gamepad_button(action)))
+                .collect(),
+        }
+    }
+}
+
+impl Keybindings {
+    pub fn key(&self, action: CatAction) -> KeyCode {
+        match action {
+            CatAction::MoveUp => self.move_up,
+            CatAction::MoveDown => self.move_down,
+            CatAction::MoveLeft => self.move_left,
+            CatAction::MoveRight => self.move_right,
+            CatAction::Uia => self.uia,
+            CatAction::Dash => self.dash,
+            CatAction::Sprint => self.sprint,
+            CatAction::Throw => self.throw,
+            CatAction::FollowMouse => self.follow_mouse,
+            CatAction::Jump => self.jump,
+            CatAction::SwitchMode => self.switch_mode,
+            CatAction::SwitchCat => self.switch_cat,
+            CatAction::ToggleMute => self.toggle_mute,
+        }
+    }
+
+    pub fn key_mut(&mut self, action: CatAction) -> &mut KeyCode {
+        match action {
+            CatAction::MoveUp => &mut self.move_up,
+            CatAction::MoveDown => &mut self.move_down,
+            CatAction::MoveLeft => &mut self.move_left,
+            CatAction::MoveRight => &mut self.move_right,
+            CatAction::Uia => &mut self.uia,
+            CatAction::Dash => &mut self.dash,
+            CatAction::Sprint => &mut self.sprint,
+            CatAction::Throw => &mut self.throw,
+            CatAction::FollowMouse => &mut self.follow_mouse,
+            CatAction::Jump => &mut self.jump,
+            CatAction::SwitchMode => &mut self.switch_mode,
+            CatAction::SwitchCat => &mut self.switch_cat,
+            CatAction::ToggleMute => &mut self.toggle_mute,
+        }
+    }
+
+    pub fn gamepad_button(&self, action: CatAction) -> GamepadButton {
+        self.gamepad
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| default_gamepad_button(action))
+    }
+}
+
+fn default_gamepad_button(action: CatAction) -> GamepadButton {
+    match action {
+        CatAction::MoveUp => GamepadButton::DPadUp,
+        CatAction::MoveDown => GamepadButton::DPadDown,
+        CatAction::MoveLeft => GamepadButton::DPadLeft,
+        CatAction::MoveRight => GamepadButton::DPadRight,
+        CatAction::Uia => GamepadButton::South,
+        CatAction::Dash => GamepadButton::East,
+        CatAction::Sprint => GamepadButton::RightTrigger2,
+        CatAction::Throw => GamepadButton::West,
+        CatAction::FollowMouse => GamepadButton::Select,
+        CatAction::Jump => GamepadButton::South,
+        CatAction::SwitchMode => GamepadButton::LeftThumb,
+        CatAction::SwitchCat => GamepadButton::RightTrigger,
+        CatAction::ToggleMute => GamepadButton::RightThumb,
+    }
+}
+
+fn clear_actions(mut actions: ResMut<ActionState>) {
+    let pressed = std::mem::take(&mut actions.pressed);
+    actions.previous = pressed;
+    actions.axis = Vec2::ZERO;
+}
+
+fn read_keyboard(
+    mut actions: ResMut<ActionState>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    keybindings: Res<Keybindings>,
+) {
+    for action in CatAction::ALL {
+        if keyboard_input.pressed(keybindings.key(action)) {
+            actions.press(action);
+        }
+    }
+}
+
+fn read_gamepads(
+    mut actions: ResMut<ActionState>,
+    keybindings: Res<Keybindings>,
+    gamepads: Query<&Gamepad>,
+) {
+    for gamepad in &gamepads {
+        for action in CatAction::ALL {
+            if gamepad.pressed(keybindings.gamepad_button(action)) {
+                actions.press(action);
+            }
+        }
+        // The left stick steers, with its tilt scaling the speed
+        let stick = gamepad.left_stick();
+        if stick != Vec2::ZERO {
+            actions.set_axis(stick);
+        }
+    }
+}
+
+// Dragging moves the cat relative to where the finger went down, tapping plays the UIA clip.
+// Touches on the virtual controls are left to them.
+fn read_touches(
+    mut actions: ResMut<ActionState>,
+    touches: Res<Touches>,
+    controls: Res<VirtualControls>,
+    time: Res<Time<Real>>,
+    mut started: Local<HashMap<u64, f32>>,
+) {
+    let now = time.elapsed_secs();
+    for touch in touches.iter_just_pressed() {
+        if !controls.claims(touch.id()) {
+            started.insert(touch.id(), now);
+        }
+    }
+
+    for touch in touches.iter() {
+        if controls.claims(touch.id()) {
+            continue;
+        }
+        let drag = touch.distance();
+        if drag.length() > TAP_MAX_DISTANCE {
+            // Screen coordinates grow downwards, world ones upwards
+            actions.set_axis(Vec2::new(drag.x, -drag.y) / TOUCH_DRAG_RADIUS);
+        }
+    }
+
+    for touch in touches.iter_just_released() {
+        let Some(start) = started.remove(&touch.id()) else {
+            continue;
+        };
+        if now - start <= TAP_MAX_SECONDS && touch.distance().length() <= TAP_MAX_DISTANCE {
+            actions.press(CatAction::Uia);
+        }
+    }
+    for touch in touches.iter_just_canceled() {
+        started.remove(&touch.id());
+    }
+}
+
+fn resolve_actions(mut actions: ResMut<ActionState>) {
+    let just_pressed = actions
+        .pressed
+        .difference(&actions.previous)
+        .copied()
+        .collect();
+    actions.just_pressed = just_pressed;
+}
+
+// Where the cursor points in the world as seen by `camera`, if it's over the window
+pub fn cursor_world_position(
+    window: &Window,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+) -> Option<Vec2> {
+    let cursor = window.cursor_position()?;
+    camera.viewport_to_world_2d(camera_transform, cursor).ok()
+}
+
+fn save_keybindings(keybindings: Res<Keybindings>) {
+    config::save_or_warn(KEYBINDINGS_FILE, &*keybindings);
+}
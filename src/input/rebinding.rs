@@ -0,0 +1,273 @@
+use bevy::input::common_conditions::input_just_pressed;
+use bevy::prelude::*;
+
+use super::{CatAction, Keybindings};
+use crate::localization::{Localization, LocalizedText};
+use crate::theme::{Surface, TextRole, Theme};
+
+// F1 screen listing every action with its key and gamepad button. Clicking a row waits for the
+// next key or button press and binds it to that action, Escape cancels.
+pub(super) struct RebindingPlugin;
+
+impl Plugin for RebindingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rebinding>().add_systems(
+            Update,
+            (
+                toggle_controls_screen.run_if(input_just_pressed(KeyCode::F1)),
+                (
+                    start_listening,
+                    reset_bindings,
+                    // Not on the frame listening starts, or the Enter that pressed the row
+                    // would be bound
+                    capture_binding.run_if(is_rebinding.and(not(resource_changed::<Rebinding>))),
+                    update_binding_texts,
+                )
+                    .chain()
+                    .run_if(any_with_component::<ControlsScreen>),
+            ),
+        );
+    }
+}
+
+// Which action (if any) the next key or button press gets bound to
+#[derive(Resource, Default, Debug)]
+pub struct Rebinding {
+    pub listening: Option<CatAction>,
+    // Feedback about the last change, e.g. which binding had to make way
+    pub message: String,
+}
+
+pub fn is_rebinding(rebinding: Res<Rebinding>) -> bool {
+    rebinding.listening.is_some()
+}
+
+#[derive(Component)]
+pub struct ControlsScreen;
+
+#[derive(Component)]
+struct BindingRow(CatAction);
+
+#[derive(Component)]
+struct BindingText(CatAction);
+
+#[derive(Component)]
+struct ResetBindingsButton;
+
+#[derive(Component)]
+struct RebindingMessage;
+
+pub fn toggle_controls_screen(
+    mut commands: Commands,
+    mut rebinding: ResMut<Rebinding>,
+    theme: Res<Theme>,
+    screens: Query<Entity, With<ControlsScreen>>,
+) {
+    rebinding.listening = None;
+    rebinding.message.clear();
+    if let Ok(screen) = screens.single() {
+        commands.entity(screen).despawn();
+        return;
+    }
+
+    let font = theme.text(TextRole::Small);
+    commands
+        .spawn((
+            ControlsScreen,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(8.0),
+                left: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(theme.gap)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(theme.gap / 3.0),
+                ..Default::default()
+            },
+            theme.surface(Surface::Panel),
+            // Above the settings screen it can be opened from
+            GlobalZIndex(2),
+        ))
+        .with_children(|screen| {
+            screen.spawn((
+                Text::default(),
+                LocalizedText::new("controls-title"),
+                font.clone(),
+            ));
+            for action in CatAction::ALL {
+                screen.spawn((
+                    BindingRow(action),
+                    Button,
+                    Node {
+                        padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+                        min_width: Val::Px(360.0),
+                        ..Default::default()
+                    },
+                    BackgroundColor(theme.button),
+                    children![(BindingText(action), Text::default(), font.clone())],
+                ));
+            }
+            screen.spawn((
+                ResetBindingsButton,
+                Button,
+                Node {
+                    padding: UiRect::axes(Val::Px(8.0), Val::Px(2.0)),
+                    margin: UiRect::top(Val::Px(theme.gap / 2.0)),
+                    ..Default::default()
+                },
+                theme.surface(Surface::Button),
+                children![(
+                    Text::default(),
+                    LocalizedText::new("controls-reset"),
+                    font.clone()
+                )],
+            ));
+            screen.spawn((RebindingMessage, Text::default(), font.clone()));
+        });
+}
+
+fn start_listening(
+    mut rebinding: ResMut<Rebinding>,
+    localization: Res<Localization>,
+    rows: Query<(&Interaction, &BindingRow), Changed<Interaction>>,
+) {
+    for (interaction, row) in &rows {
+        if *interaction == Interaction::Pressed {
+            rebinding.listening = Some(row.0);
+            rebinding.message = localization.format(
+                "controls-listening",
+                &[("action", localization.get(row.0.label()).to_owned())],
+            );
+        }
+    }
+}
+
+fn reset_bindings(
+    mut rebinding: ResMut<Rebinding>,
+    localization: Res<Localization>,
+    mut keybindings: ResMut<Keybindings>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<ResetBindingsButton>)>,
+) {
+    if buttons
+        .iter()
+        .any(|interaction| *interaction == Interaction::Pressed)
+    {
+        *keybindings = Keybindings::default();
+        rebinding.listening = None;
+        rebinding.message = localization.get("controls-restored").to_owned();
+    }
+}
+
+fn capture_binding(
+    mut rebinding: ResMut<Rebinding>,
+    localization: Res<Localization>,
+    mut keybindings: ResMut<Keybindings>,
+    mut keyboard_input: ResMut<ButtonInput<KeyCode>>,
+    mut gamepads: Query<&mut Gamepad>,
+) {
+    let Some(action) = rebinding.listening else {
+        return;
+    };
+
+    // The press is used up, so nothing running later this frame (like Escape pausing the game or
+    // closing the settings) reacts to it as well, now that rebinding is over
+    let key = keyboard_input.get_just_pressed().next().copied();
+    if let Some(key) = key {
+        keyboard_input.clear_just_pressed(key);
+        rebinding.listening = None;
+        if key == KeyCode::Escape {
+            rebinding.message.clear();
+            return;
+        }
+        // Whatever used this key gets the action's old key, so nothing ends up unbound
+        let old_key = keybindings.key(action);
+        let conflicts: Vec<CatAction> = CatAction::ALL
+            .into_iter()
+            .filter(|&other| {
+                other != action && !action.can_share_input(other) && keybindings.key(other) == key
+            })
+            .collect();
+        for &other in &conflicts {
+            *keybindings.key_mut(other) = old_key;
+        }
+        *keybindings.key_mut(action) = key;
+        rebinding.message = swap_message(&localization, action, &format!("{key:?}"), &conflicts);
+        return;
+    }
+
+    let pressed = gamepads.iter_mut().find_map(|mut gamepad| {
+        let button = gamepad.get_just_pressed().next().copied()?;
+        gamepad.digital_mut().clear_just_pressed(button);
+        Some(button)
+    });
+    if let Some(button) = pressed {
+        rebinding.listening = None;
+        let old_button = keybindings.gamepad_button(action);
+        let conflicts: Vec<CatAction> = CatAction::ALL
+            .into_iter()
+            .filter(|&other| {
+                other != action
+                    && !action.can_share_input(other)
+                    && keybindings.gamepad_button(other) == button
+            })
+            .collect();
+        for &other in &conflicts {
+            keybindings.gamepad.insert(other, old_button);
+        }
+        keybindings.gamepad.insert(action, button);
+        rebinding.message = swap_message(&localization, action, &format!("{button:?}"), &conflicts);
+    }
+}
+
+fn swap_message(
+    localization: &Localization,
+    action: CatAction,
+    input: &str,
+    conflicts: &[CatAction],
+) -> String {
+    let mut args = vec![
+        ("action", localization.get(action.label()).to_owned()),
+        ("input", input.to_owned()),
+    ];
+    if conflicts.is_empty() {
+        localization.format("controls-bound", &args)
+    } else {
+        let others: Vec<&str> = conflicts
+            .iter()
+            .map(|other| localization.get(other.label()))
+            .collect();
+        args.push(("others", others.join(", ")));
+        localization.format("controls-swapped", &args)
+    }
+}
+
+fn update_binding_texts(
+    rebinding: Res<Rebinding>,
+    localization: Res<Localization>,
+    theme: Res<Theme>,
+    keybindings: Res<Keybindings>,
+    mut texts: Query<(&BindingText, &mut Text), Without<RebindingMessage>>,
+    mut rows: Query<(&BindingRow, &mut BackgroundColor)>,
+    mut message: Single<&mut Text, With<RebindingMessage>>,
+) {
+    for (BindingText(action), mut text) in &mut texts {
+        let binding = if rebinding.listening == Some(*action) {
+            "...".to_owned()
+        } else {
+            format!(
+                "{:?} / {:?}",
+                keybindings.key(*action),
+                keybindings.gamepad_button(*action)
+            )
+        };
+        text.0 = format!("{:<14} {binding}", localization.get(action.label()));
+    }
+    for (BindingRow(action), mut color) in &mut rows {
+        let listening = rebinding.listening == Some(*action);
+        color.0 = if listening {
+            theme.accent
+        } else {
+            theme.button
+        };
+    }
+    message.0.clone_from(&rebinding.message);
+}
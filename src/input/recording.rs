@@ -0,0 +1,144 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{ActionState, CatAction};
+use crate::config;
+
+// Frames kept by default, a bit over a minute at 60 fps
+const DEFAULT_CAPACITY: usize = 4096;
+
+// The actions of a single frame
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    pub frame: u64,
+    // Seconds since startup
+    pub time: f32,
+    pub pressed: Vec<CatAction>,
+    pub axis: Vec2,
+}
+
+// The most recent frames of player input, oldest first. F9 writes them to config/recordings/.
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+pub struct InputRecording {
+    pub capacity: usize,
+    pub frames: VecDeque<RecordedFrame>,
+    #[serde(skip)]
+    next_frame: u64,
+}
+
+impl Default for InputRecording {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl InputRecording {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            frames: VecDeque::with_capacity(capacity),
+            next_frame: 0,
+        }
+    }
+
+    pub fn push(&mut self, time: f32, actions: &ActionState) {
+        if self.frames.len() >= self.capacity.max(1) {
+            self.frames.pop_front();
+        }
+        let mut pressed: Vec<CatAction> = actions.pressed.iter().copied().collect();
+        // Stable order, so identical frames serialize identically
+        pressed.sort_by_key(|action| CatAction::ALL.iter().position(|a| a == action));
+        self.frames.push_back(RecordedFrame {
+            frame: self.next_frame,
+            time,
+            pressed,
+            axis: actions.axis,
+        });
+        self.next_frame += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+
+    // Writes the buffer to `config/recordings/<file>`
+    pub fn save(&self, file: &str) -> Result<(), config::ConfigError> {
+        config::save(&format!("recordings/{file}"), self)
+    }
+}
+
+pub(super) fn record_actions(
+    actions: Res<ActionState>,
+    time: Res<Time<Real>>,
+    mut recording: ResMut<InputRecording>,
+) {
+    recording.push(time.elapsed_secs(), &actions);
+}
+
+pub(super) fn dump_recording(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    recording: Res<InputRecording>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F9) {
+        return;
+    }
+    let Some(last) = recording.frames.back() else {
+        return;
+    };
+    let file = format!("input-{}.ron", last.frame);
+    match recording.save(&file) {
+        Ok(()) => info!(
+            "saved {} frames of input to recordings/{file}",
+            recording.frames.len()
+        ),
+        Err(error) => warn!("recordings/{file}: {error}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actions(pressed: &[CatAction]) -> ActionState {
+        let mut actions = ActionState::default();
+        for &action in pressed {
+            actions.press(action);
+        }
+        actions
+    }
+
+    #[test]
+    fn push_drops_the_oldest_frames_when_full() {
+        let mut recording = InputRecording::with_capacity(3);
+        for frame in 0..5 {
+            recording.push(frame as f32, &ActionState::default());
+        }
+        let frames: Vec<u64> = recording.frames.iter().map(|frame| frame.frame).collect();
+        assert_eq!(frames, [2, 3, 4]);
+        assert_eq!(recording.frames.front().map(|frame| frame.time), Some(2.0));
+    }
+
+    #[test]
+    fn push_keeps_at_least_one_frame() {
+        let mut recording = InputRecording::with_capacity(0);
+        recording.push(0.0, &ActionState::default());
+        recording.push(1.0, &ActionState::default());
+        assert_eq!(recording.frames.len(), 1);
+        assert_eq!(recording.frames[0].frame, 1);
+    }
+
+    #[test]
+    fn push_records_actions_in_a_stable_order() {
+        let mut recording = InputRecording::default();
+        recording.push(
+            0.0,
+            &actions(&[CatAction::Dash, CatAction::MoveUp, CatAction::Jump]),
+        );
+        assert_eq!(
+            recording.frames[0].pressed,
+            [CatAction::MoveUp, CatAction::Dash, CatAction::Jump]
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use super::{ActionState, ActionSystems, CatAction, is_rebinding, read_touches};
+use crate::localization::LocalizedText;
+use crate::state::InGame;
+use crate::theme::{Surface, TextRole, Theme};
+
+// Logical pixels
+const STICK_RADIUS: f32 = 64.0;
+const KNOB_RADIUS: f32 = 26.0;
+const BUTTON_RADIUS: f32 = 44.0;
+const MARGIN: f32 = 32.0;
+
+// A stick in the bottom left and an action button in the bottom right for touch screens. They
+// show up with the first touch and feed `ActionState` like a gamepad would, and touches that start
+// on them aren't read as drags or taps as well.
+pub(super) struct VirtualControlsPlugin;
+
+impl Plugin for VirtualControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VirtualControls>()
+            .add_systems(OnEnter(InGame), spawn_virtual_controls)
+            .add_systems(
+                PreUpdate,
+                read_virtual_controls
+                    .run_if(not(is_rebinding))
+                    .in_set(ActionSystems::Collect)
+                    .before(read_touches),
+            )
+            .add_systems(Update, update_virtual_controls.run_if(in_state(InGame)));
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct VirtualControls {
+    // Set by the first touch, after which the controls stay on screen
+    pub visible: bool,
+    stick_touch: Option<u64>,
+    // Where the stick is pushed, in screen directions, with a length of at most 1
+    stick: Vec2,
+    button_touch: Option<u64>,
+}
+
+impl VirtualControls {
+    // Whether the touch is steering the stick or holding the button
+    pub fn claims(&self, touch: u64) -> bool {
+        self.stick_touch == Some(touch) || self.button_touch == Some(touch)
+    }
+}
+
+#[derive(Component)]
+struct VirtualControlsRoot;
+
+#[derive(Component)]
+struct VirtualStick;
+
+#[derive(Component)]
+struct VirtualKnob;
+
+#[derive(Component)]
+struct VirtualButton;
+
+fn spawn_virtual_controls(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    controls: Res<VirtualControls>,
+) {
+    let circle = |radius: f32| Node {
+        width: Val::Px(radius * 2.0),
+        height: Val::Px(radius * 2.0),
+        ..Default::default()
+    };
+    commands.spawn((
+        VirtualControlsRoot,
+        StateScoped(InGame),
+        Name::new("Virtual controls"),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(MARGIN),
+            right: Val::Px(MARGIN),
+            bottom: Val::Px(MARGIN),
+            align_items: AlignItems::End,
+            justify_content: JustifyContent::SpaceBetween,
+            ..Default::default()
+        },
+        if controls.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        },
+        Pickable::IGNORE,
+        children![
+            (
+                VirtualStick,
+                circle(STICK_RADIUS),
+                BorderRadius::MAX,
+                theme.surface(Surface::Panel),
+                Pickable::IGNORE,
+                children![(
+                    VirtualKnob,
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(STICK_RADIUS - KNOB_RADIUS),
+                        top: Val::Px(STICK_RADIUS - KNOB_RADIUS),
+                        ..circle(KNOB_RADIUS)
+                    },
+                    BorderRadius::MAX,
+                    theme.surface(Surface::Meter),
+                    Pickable::IGNORE,
+                )],
+            ),
+            (
+                VirtualButton,
+                Node {
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..circle(BUTTON_RADIUS)
+                },
+                BorderRadius::MAX,
+                theme.surface(Surface::Panel),
+                Pickable::IGNORE,
+                children![(
+                    Text::default(),
+                    LocalizedText::new(CatAction::Uia.label()),
+                    theme.text(TextRole::Button),
+                    Pickable::IGNORE,
+                )],
+            ),
+        ],
+    ));
+}
+
+// Center and radius of a round node, in logical pixels like touch positions
+fn circle_of(node: &ComputedNode, transform: &GlobalTransform, scale_factor: f32) -> (Vec2, f32) {
+    (
+        transform.translation().truncate() / scale_factor,
+        node.size().x / scale_factor / 2.0,
+    )
+}
+
+fn read_virtual_controls(
+    mut controls: ResMut<VirtualControls>,
+    mut actions: ResMut<ActionState>,
+    touches: Res<Touches>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    stick: Query<(&ComputedNode, &GlobalTransform), With<VirtualStick>>,
+    button: Query<(&ComputedNode, &GlobalTransform), With<VirtualButton>>,
+) {
+    let scale_factor = window.scale_factor();
+    let stick = stick
+        .single()
+        .ok()
+        .map(|(node, transform)| circle_of(node, transform, scale_factor));
+    let button = button
+        .single()
+        .ok()
+        .map(|(node, transform)| circle_of(node, transform, scale_factor));
+    let hits = |circle: Option<(Vec2, f32)>, position: Vec2| {
+        circle.is_some_and(|(center, radius)| center.distance(position) <= radius)
+    };
+
+    for touch in touches
+        .iter_just_released()
+        .chain(touches.iter_just_canceled())
+    {
+        if controls.stick_touch == Some(touch.id()) {
+            controls.stick_touch = None;
+        }
+        if controls.button_touch == Some(touch.id()) {
+            controls.button_touch = None;
+        }
+    }
+    for touch in touches.iter_just_pressed() {
+        // The first touch only brings the controls up
+        if !controls.visible {
+            controls.visible = true;
+            continue;
+        }
+        if controls.stick_touch.is_none() && hits(stick, touch.position()) {
+            controls.stick_touch = Some(touch.id());
+        } else if controls.button_touch.is_none() && hits(button, touch.position()) {
+            controls.button_touch = Some(touch.id());
+        }
+    }
+
+    controls.stick = match (
+        controls.stick_touch.and_then(|id| touches.get_pressed(id)),
+        stick,
+    ) {
+        (Some(touch), Some((center, radius))) => {
+            ((touch.position() - center) / radius).clamp_length_max(1.0)
+        }
+        _ => Vec2::ZERO,
+    };
+    if controls.stick != Vec2::ZERO {
+        // Screen coordinates grow downwards, world ones upwards
+        actions.set_axis(Vec2::new(controls.stick.x, -controls.stick.y));
+    }
+    // The button is Space on a touch screen: UIA top-down, jump in platformer mode
+    if controls.button_touch.is_some() {
+        actions.press(CatAction::Uia);
+        actions.press(CatAction::Jump);
+    }
+}
+
+fn update_virtual_controls(
+    controls: Res<VirtualControls>,
+    theme: Res<Theme>,
+    mut root: Single<&mut Visibility, With<VirtualControlsRoot>>,
+    mut knob: Single<&mut Node, With<VirtualKnob>>,
+    button: Single<(&mut Surface, &mut BackgroundColor), With<VirtualButton>>,
+) {
+    root.set_if_neq(if controls.visible {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    });
+    let corner = Vec2::splat(STICK_RADIUS - KNOB_RADIUS) * (Vec2::ONE + controls.stick);
+    if knob.left != Val::Px(corner.x) || knob.top != Val::Px(corner.y) {
+        knob.left = Val::Px(corner.x);
+        knob.top = Val::Px(corner.y);
+    }
+    let (mut surface, mut color) = button.into_inner();
+    let held = if controls.button_touch.is_some() {
+        Surface::Accent
+    } else {
+        Surface::Panel
+    };
+    if *surface != held {
+        *surface = held;
+        color.0 = theme.color(held);
+    }
+}
@@ -0,0 +1,21 @@
+use bevy::input::common_conditions::input_toggle_active;
+use bevy::prelude::*;
+use bevy_inspector_egui::bevy_egui::EguiPlugin;
+use bevy_inspector_egui::quick::WorldInspectorPlugin;
+
+// Window listing every entity and resource, with their reflected components editable while the
+// game runs. Toggled with F12.
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EguiPlugin>() {
+            app.add_plugins(EguiPlugin {
+                enable_multipass_for_primary_context: true,
+            });
+        }
+        app.add_plugins(
+            WorldInspectorPlugin::new().run_if(input_toggle_active(false, KeyCode::F12)),
+        );
+    }
+}
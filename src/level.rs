@@ -0,0 +1,379 @@
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::color::HexColorError;
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::accessibility::CLEAR_COLOR;
+use crate::console::{AddConsoleCommandExt, ConsoleOutput, parse_arg};
+use crate::loading::LoadingAssets;
+use crate::localization::{Localization, LocalizedText};
+use crate::mode::GameMode;
+use crate::score::{Score, ScoreChanged};
+use crate::spawner::{Prototype, SpawnArea, Spawner};
+use crate::state::{GameState, InGame};
+use crate::theme::{Surface, TextRole, Theme};
+use crate::tween::Tween;
+
+const LEVELS_PATH: &str = "game.levels.ron";
+// How long the screen between two levels stays up
+const TRANSITION_SECONDS: f32 = 2.5;
+// Power-ups lying around at once
+const MAX_POWER_UPS: usize = 1;
+
+// Levels from assets/game.levels.ron, each faster and busier than the one before. Reaching a
+// level's target score clears the room and shows the next level's name for a moment before play
+// goes on. Each level sets up its own spawners for the fish, power-ups and dogs. Timed games are
+// played on the first level throughout.
+pub struct LevelPlugin;
+
+impl Plugin for LevelPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Levels>()
+            .init_asset_loader::<LevelsLoader>()
+            .init_resource::<Level>()
+            .register_type::<Level>()
+            .add_console_command("level", "level <number>: skip to a level", skip_to_level)
+            .add_systems(Startup, load_levels)
+            .add_systems(OnEnter(InGame), start_first_level)
+            .add_systems(
+                OnEnter(GameState::LevelTransition),
+                (clear_level, spawn_transition_screen),
+            )
+            .add_systems(
+                Update,
+                (
+                    // A timed game stays on the first level
+                    finish_level.run_if(
+                        in_state(GameState::Playing)
+                            .and(resource_equals(GameMode::Levels))
+                            .and(on_event::<ScoreChanged>),
+                    ),
+                    finish_transition.run_if(in_state(GameState::LevelTransition)),
+                    apply_level.run_if(in_state(InGame).and(resource_changed::<Level>)),
+                ),
+            );
+    }
+}
+
+// What a level is like
+#[derive(Clone, Debug, Reflect)]
+pub struct LevelDefinition {
+    // Localization key of the level's name
+    pub name: String,
+    // Score that finishes the level, the last level has none
+    pub target_score: Option<u32>,
+    // Seconds between fish, and how many can be around at once
+    pub fish_interval: f32,
+    pub max_fish: usize,
+    // Seconds between power-ups
+    pub power_up_interval: f32,
+    // How many dogs can be around at once, coming in from the corners this many seconds apart
+    pub dogs: usize,
+    pub dog_interval: f32,
+    // Multiplies the dogs' speeds
+    pub dog_speed: f32,
+    pub background: Color,
+    // How many cats there are to switch between in a game starting on this level
+    pub cats: usize,
+}
+
+impl Default for LevelDefinition {
+    fn default() -> Self {
+        Self {
+            name: "level-garden".to_owned(),
+            target_score: None,
+            fish_interval: 3.0,
+            max_fish: 5,
+            power_up_interval: 12.0,
+            dogs: 2,
+            dog_interval: 2.0,
+            dog_speed: 1.0,
+            background: CLEAR_COLOR,
+            cats: 1,
+        }
+    }
+}
+
+// The level being played
+#[derive(Resource, Clone, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub struct Level {
+    // From 0
+    pub index: usize,
+    pub definition: LevelDefinition,
+}
+
+impl Level {
+    pub fn number(&self) -> usize {
+        self.index + 1
+    }
+}
+
+// Every level in order. Without any (or until they're loaded) the game is one endless default
+// level.
+#[derive(Asset, TypePath, Clone, Debug)]
+pub struct Levels(pub Vec<LevelDefinition>);
+
+// Entities that only belong to the current level, like fish and dogs, and go when it's over
+#[derive(Component, Default)]
+pub struct LevelScoped;
+
+#[derive(Resource)]
+struct LevelsHandle(Handle<Levels>);
+
+// Counts down the screen between two levels
+#[derive(Resource)]
+struct TransitionTimer(Timer);
+
+fn load_levels(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let handle = asset_server.load(LEVELS_PATH);
+    loading.track(handle.clone());
+    commands.insert_resource(LevelsHandle(handle));
+}
+
+fn level_at(levels: &Assets<Levels>, handle: &LevelsHandle, index: usize) -> Option<Level> {
+    let definition = levels.get(&handle.0)?.0.get(index)?.clone();
+    Some(Level { index, definition })
+}
+
+fn start_first_level(
+    mut level: ResMut<Level>,
+    levels: Res<Assets<Levels>>,
+    handle: Res<LevelsHandle>,
+) {
+    *level = level_at(&levels, &handle, 0).unwrap_or_default();
+}
+
+fn finish_level(
+    score: Res<Score>,
+    mut level: ResMut<Level>,
+    levels: Res<Assets<Levels>>,
+    handle: Res<LevelsHandle>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let reached = level
+        .definition
+        .target_score
+        .is_some_and(|target| score.0 >= target);
+    if !reached {
+        return;
+    }
+    if let Some(next) = level_at(&levels, &handle, level.index + 1) {
+        *level = next;
+        next_state.set(GameState::LevelTransition);
+    }
+}
+
+// level <number>: goes straight to a level, through its transition screen
+fn skip_to_level(
+    In(args): In<Vec<String>>,
+    mut level: ResMut<Level>,
+    levels: Res<Assets<Levels>>,
+    handle: Res<LevelsHandle>,
+    in_game: Option<Res<State<InGame>>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) -> ConsoleOutput {
+    let number: usize = parse_arg(&args, 0, "number")?;
+    if in_game.is_none() {
+        return Err("start a game first".to_owned());
+    }
+    let next = number
+        .checked_sub(1)
+        .and_then(|index| level_at(&levels, &handle, index))
+        .ok_or_else(|| format!("there is no level {number}"))?;
+    *level = next;
+    next_state.set(GameState::LevelTransition);
+    Ok(format!("skipping to level {number}"))
+}
+
+// Swaps the previous level's spawners for this one's. They aren't `LevelScoped`, as the level has
+// already changed by the time the transition clears the room.
+fn apply_level(mut commands: Commands, level: Res<Level>, spawners: Query<Entity, With<Spawner>>) {
+    for entity in &spawners {
+        commands.entity(entity).despawn();
+    }
+    let definition = &level.definition;
+    for spawner in [
+        Spawner::new(
+            Prototype::Fish,
+            definition.fish_interval,
+            definition.max_fish,
+        ),
+        Spawner::new(
+            Prototype::PowerUp,
+            definition.power_up_interval,
+            MAX_POWER_UPS,
+        ),
+        Spawner::new(Prototype::Dog, definition.dog_interval, definition.dogs)
+            .with_area(SpawnArea::Corners),
+    ] {
+        commands.spawn((
+            Name::new(format!("{:?} spawner", spawner.prototype)),
+            spawner,
+            StateScoped(InGame),
+        ));
+    }
+}
+
+fn clear_level(mut commands: Commands, scoped: Query<Entity, With<LevelScoped>>) {
+    for entity in &scoped {
+        commands.entity(entity).despawn();
+    }
+}
+
+fn spawn_transition_screen(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    localization: Res<Localization>,
+    level: Res<Level>,
+) {
+    commands.insert_resource(TransitionTimer(Timer::from_seconds(
+        TRANSITION_SECONDS,
+        TimerMode::Once,
+    )));
+    let goal = match level.definition.target_score {
+        Some(target) => LocalizedText::new("level-target").with_arg("score", target),
+        None => LocalizedText::new("level-endless"),
+    };
+    commands.spawn((
+        StateScoped(GameState::LevelTransition),
+        Name::new("Level transition"),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            row_gap: Val::Px(theme.gap),
+            ..Default::default()
+        },
+        theme.surface(Surface::Backdrop),
+        Tween::new(0.3).fade(0.0, 1.0),
+        children![
+            (
+                Text::default(),
+                LocalizedText::new("level-title").with_arg("number", level.number()),
+                theme.text(TextRole::Title),
+            ),
+            (
+                Text::new(localization.get(&level.definition.name)),
+                theme.text(TextRole::Heading),
+            ),
+            (Text::default(), goal, theme.text(TextRole::Body)),
+        ],
+    ));
+}
+
+fn finish_transition(
+    time: Res<Time<Real>>,
+    mut timer: ResMut<TransitionTimer>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if timer.0.tick(time.delta()).just_finished() {
+        next_state.set(GameState::Playing);
+    }
+}
+
+// Colors are written as hex strings, like in the theme
+#[derive(Deserialize)]
+#[serde(default)]
+struct LevelDef {
+    name: String,
+    target_score: Option<u32>,
+    fish_interval: f32,
+    max_fish: usize,
+    power_up_interval: f32,
+    dogs: usize,
+    dog_interval: f32,
+    dog_speed: f32,
+    background: String,
+    cats: usize,
+}
+
+impl Default for LevelDef {
+    fn default() -> Self {
+        let level = LevelDefinition::default();
+        Self {
+            name: level.name,
+            target_score: level.target_score,
+            fish_interval: level.fish_interval,
+            max_fish: level.max_fish,
+            power_up_interval: level.power_up_interval,
+            dogs: level.dogs,
+            dog_interval: level.dog_interval,
+            dog_speed: level.dog_speed,
+            background: level.background.to_srgba().to_hex(),
+            cats: level.cats,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LevelsLoader;
+
+#[derive(Debug, Error)]
+pub enum LevelsLoaderError {
+    #[error("could not read levels: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse levels: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("invalid background color {0:?} in levels: {1}")]
+    Color(String, HexColorError),
+    #[error("invalid {0} {1} in levels, it must be a positive number of seconds")]
+    Interval(&'static str, f32),
+}
+
+// Spawner timers can't run on a negative (or NaN) number of seconds
+fn interval(field: &'static str, seconds: f32) -> Result<f32, LevelsLoaderError> {
+    if seconds.is_finite() && seconds > 0.0 {
+        Ok(seconds)
+    } else {
+        Err(LevelsLoaderError::Interval(field, seconds))
+    }
+}
+
+impl AssetLoader for LevelsLoader {
+    type Asset = Levels;
+    type Settings = ();
+    type Error = LevelsLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let defs: Vec<LevelDef> = ron::de::from_bytes(&bytes)?;
+        let levels = defs
+            .into_iter()
+            .map(|def| {
+                let background = Srgba::hex(&def.background)
+                    .map_err(|error| LevelsLoaderError::Color(def.background.clone(), error))?;
+                Ok(LevelDefinition {
+                    name: def.name,
+                    target_score: def.target_score,
+                    fish_interval: interval("fish_interval", def.fish_interval)?,
+                    max_fish: def.max_fish,
+                    power_up_interval: interval("power_up_interval", def.power_up_interval)?,
+                    dogs: def.dogs,
+                    dog_interval: interval("dog_interval", def.dog_interval)?,
+                    dog_speed: def.dog_speed,
+                    background: background.into(),
+                    cats: def.cats,
+                })
+            })
+            .collect::<Result<_, LevelsLoaderError>>()?;
+        Ok(Levels(levels))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["levels.ron"]
+    }
+}
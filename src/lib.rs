@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+pub mod accessibility;
+pub mod animation;
+pub mod audio;
+pub mod cat;
+pub mod collectible;
+pub mod collision;
+pub mod config;
+pub mod console;
+pub mod diagnostics;
+pub mod dog;
+pub mod health;
+pub mod high_score;
+pub mod hints;
+pub mod hud;
+pub mod input;
+#[cfg(feature = "inspector")]
+pub mod inspector;
+pub mod level;
+pub mod loading;
+pub mod localization;
+pub mod menu;
+pub mod minimap;
+pub mod mode;
+pub mod movement;
+pub mod notifications;
+pub mod obstacle;
+#[cfg(feature = "physics")]
+pub mod physics;
+pub mod power_up;
+pub mod projectile;
+pub mod score;
+pub mod settings;
+pub mod spawner;
+pub mod splash;
+pub mod state;
+pub mod theme;
+pub mod tooltip;
+pub mod tween;
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        // Console first, so the other plugins can register their commands with it
+        app.add_plugins(console::ConsolePlugin)
+            .add_plugins((
+                animation::AnimationPlugin,
+                animation::AnimationDebuggerPlugin,
+                diagnostics::DiagnosticsOverlayPlugin,
+                input::GameInputPlugin,
+                movement::MovementPlugin,
+                collision::CollisionPlugin,
+                cat::CatPlugin,
+                splash::SplashPlugin,
+                loading::LoadingPlugin,
+                hud::HudPlugin,
+                hints::ControlHintsPlugin,
+                menu::MenuPlugin,
+                settings::SettingsPlugin,
+                theme::ThemePlugin,
+                notifications::NotificationsPlugin,
+            ))
+            .add_plugins((
+                health::HealthPlugin,
+                minimap::MinimapPlugin,
+                localization::LocalizationPlugin,
+                tooltip::TooltipPlugin,
+                accessibility::AccessibilityPlugin,
+                tween::TweenPlugin,
+                audio::AudioPlugin,
+                collectible::CollectiblePlugin,
+                score::ScorePlugin,
+                dog::DogPlugin,
+                power_up::PowerUpPlugin,
+                level::LevelPlugin,
+                obstacle::ObstaclePlugin,
+                mode::GameModePlugin,
+            ))
+            .add_plugins((
+                high_score::HighScorePlugin,
+                spawner::SpawnerPlugin,
+                projectile::ProjectilePlugin,
+            ))
+            .init_state::<state::GameState>()
+            .add_computed_state::<state::InGame>()
+            .enable_state_scoped_entities::<state::GameState>()
+            .enable_state_scoped_entities::<state::InGame>()
+            .insert_resource(ClearColor(accessibility::CLEAR_COLOR))
+            .add_systems(Startup, setup_camera);
+
+        #[cfg(feature = "physics")]
+        app.add_plugins(physics::CatPhysicsPlugin);
+
+        #[cfg(feature = "inspector")]
+        app.add_plugins(inspector::InspectorPlugin);
+    }
+}
+
+fn setup_camera(mut commands: Commands) {
+    commands.spawn(Camera2d);
+}
@@ -0,0 +1,100 @@
+use bevy::asset::RecursiveDependencyLoadState;
+use bevy::prelude::*;
+
+use crate::animation::AnimationRegistry;
+use crate::localization::LocalizedText;
+use crate::state::GameState;
+use crate::theme::{Surface, TextRole, Theme};
+
+const SPINNER_FRAMES: [&str; 4] = ["|", "/", "-", "\\"];
+const SPINNER_FRAME_SECONDS: f32 = 0.1;
+
+// Holds the game on a loading screen until every registered animation sheet and tracked asset
+// (textures included) has loaded, then moves on to the main menu
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LoadingAssets>()
+            .add_systems(OnEnter(GameState::Loading), spawn_loading_screen)
+            .add_systems(
+                Update,
+                (spin, finish_loading).run_if(in_state(GameState::Loading)),
+            );
+    }
+}
+
+// Assets besides the animation sheets that have to be ready before the game starts
+#[derive(Resource, Default)]
+pub struct LoadingAssets {
+    handles: Vec<UntypedHandle>,
+}
+
+impl LoadingAssets {
+    pub fn track(&mut self, handle: impl Into<UntypedHandle>) {
+        self.handles.push(handle.into());
+    }
+}
+
+#[derive(Component)]
+struct Spinner;
+
+fn spawn_loading_screen(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        StateScoped(GameState::Loading),
+        Name::new("Loading screen"),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            column_gap: Val::Px(theme.gap),
+            ..Default::default()
+        },
+        theme.surface(Surface::Backdrop),
+        children![
+            (
+                Spinner,
+                Text::new(SPINNER_FRAMES[0]),
+                theme.text(TextRole::Button),
+            ),
+            (
+                Text::default(),
+                LocalizedText::new("loading"),
+                theme.text(TextRole::Button),
+            ),
+        ],
+    ));
+}
+
+fn spin(time: Res<Time<Real>>, mut spinners: Query<&mut Text, With<Spinner>>) {
+    let frame = (time.elapsed_secs() / SPINNER_FRAME_SECONDS) as usize % SPINNER_FRAMES.len();
+    for mut text in &mut spinners {
+        if text.0 != SPINNER_FRAMES[frame] {
+            text.0 = SPINNER_FRAMES[frame].to_owned();
+        }
+    }
+}
+
+fn finish_loading(
+    asset_server: Res<AssetServer>,
+    registry: Res<AnimationRegistry>,
+    loading: Res<LoadingAssets>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let ids = registry
+        .handles()
+        .map(|handle| handle.id().untyped())
+        .chain(loading.handles.iter().map(UntypedHandle::id));
+    for id in ids {
+        match asset_server.recursive_dependency_load_state(id) {
+            RecursiveDependencyLoadState::Loaded => {}
+            // Start anyway, whatever uses a broken asset already warns about it
+            RecursiveDependencyLoadState::Failed(error) => {
+                warn!("failed to load {:?}: {error}", asset_server.get_path(id));
+            }
+            _ => return,
+        }
+    }
+    next_state.set(GameState::Menu);
+}
@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::loading::LoadingAssets;
+use crate::settings::Settings;
+
+// English is built in, so there's text before the files have loaded and for any key a
+// translation is missing
+const ENGLISH: &str = include_str!("../assets/locale/en.locale.ron");
+
+// Every piece of text the player sees is looked up by key in assets/locale/<code>.locale.ron for
+// the `Language` in the settings. UI text goes through `LocalizedText`, which is rewritten
+// whenever the language changes.
+pub struct LocalizationPlugin;
+
+impl Plugin for LocalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Locale>()
+            .init_asset_loader::<LocaleLoader>()
+            .init_resource::<Localization>()
+            .register_type::<Language>()
+            .add_systems(Startup, load_locales)
+            .add_systems(
+                PostUpdate,
+                (update_localization, update_localized_texts)
+                    .chain()
+                    .before(UiSystem::Prepare),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+        }
+    }
+
+    // In the language itself, so it can be found without understanding the current one
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    fn path(self) -> String {
+        format!("locale/{}.locale.ron", self.code())
+    }
+}
+
+// Translations of one language, by key
+#[derive(Asset, TypePath, Clone, Debug, Default)]
+pub struct Locale {
+    pub strings: HashMap<String, String>,
+}
+
+#[derive(Resource)]
+pub struct Localization {
+    language: Language,
+    strings: HashMap<String, String>,
+    english: HashMap<String, String>,
+}
+
+impl Default for Localization {
+    fn default() -> Self {
+        let english: HashMap<String, String> =
+            ron::from_str(ENGLISH).expect("the built-in English locale should parse");
+        Self {
+            language: Language::English,
+            strings: english.clone(),
+            english,
+        }
+    }
+}
+
+impl Localization {
+    pub fn language(&self) -> Language {
+        self.language
+    }
+
+    // The text for `key`, in English if it hasn't been translated, or the key itself if it
+    // doesn't exist at all so it stands out
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.english.get(key))
+            .map_or(key, String::as_str)
+    }
+
+    // `get` with every `{name}` replaced by its argument
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> String {
+        let mut text = self.get(key).to_owned();
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), value);
+        }
+        text
+    }
+}
+
+// Keeps the entity's `Text` set to the translation of `key`
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct LocalizedText {
+    pub key: &'static str,
+    pub args: Vec<(&'static str, String)>,
+}
+
+impl LocalizedText {
+    pub fn new(key: &'static str) -> Self {
+        Self {
+            key,
+            args: Vec::new(),
+        }
+    }
+
+    pub fn with_arg(mut self, name: &'static str, value: impl Display) -> Self {
+        self.set_arg(name, value);
+        self
+    }
+
+    pub fn set_arg(&mut self, name: &'static str, value: impl Display) {
+        let value = value.to_string();
+        match self.args.iter_mut().find(|(arg, _)| *arg == name) {
+            Some((_, old)) => *old = value,
+            None => self.args.push((name, value)),
+        }
+    }
+}
+
+#[derive(Resource)]
+struct LocaleHandles(HashMap<Language, Handle<Locale>>);
+
+fn load_locales(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let handles = Language::ALL
+        .into_iter()
+        .map(|language| {
+            let handle = asset_server.load(language.path());
+            loading.track(handle.clone());
+            (language, handle)
+        })
+        .collect();
+    commands.insert_resource(LocaleHandles(handles));
+}
+
+// Switches to the language in the settings once it has loaded, and picks up edits to its file
+fn update_localization(
+    mut events: EventReader<AssetEvent<Locale>>,
+    settings: Res<Settings>,
+    handles: Res<LocaleHandles>,
+    locales: Res<Assets<Locale>>,
+    mut localization: ResMut<Localization>,
+) {
+    let handle = &handles.0[&settings.language];
+    let reloaded = events
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(handle) || event.is_modified(handle));
+    if !reloaded && localization.language == settings.language {
+        return;
+    }
+    if let Some(locale) = locales.get(handle) {
+        localization.language = settings.language;
+        localization.strings = locale.strings.clone();
+    }
+}
+
+fn update_localized_texts(
+    localization: Res<Localization>,
+    mut texts: Query<(Ref<LocalizedText>, &mut Text)>,
+) {
+    for (localized, mut text) in &mut texts {
+        if !localized.is_changed() && !localization.is_changed() {
+            continue;
+        }
+        let shown = localization.format(localized.key, &localized.args);
+        if text.0 != shown {
+            text.0 = shown;
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LocaleLoader;
+
+#[derive(Debug, Error)]
+pub enum LocaleLoaderError {
+    #[error("could not read locale: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse locale: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for LocaleLoader {
+    type Asset = Locale;
+    type Settings = ();
+    type Error = LocaleLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(Locale {
+            strings: ron::de::from_bytes(&bytes)?,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["locale.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn german() -> Locale {
+        let strings = [("back", "Zurück"), ("game-over-score", "Punkte {score}")];
+        Locale {
+            strings: strings
+                .into_iter()
+                .map(|(key, text)| (key.to_owned(), text.to_owned()))
+                .collect(),
+        }
+    }
+
+    // The game with German picked in the settings and `german` as its loaded file
+    fn world(german: Option<Locale>) -> World {
+        let mut world = World::new();
+        world.insert_resource(Settings {
+            language: Language::German,
+            ..default()
+        });
+        world.init_resource::<Localization>();
+        world.init_resource::<Events<AssetEvent<Locale>>>();
+        let mut locales = Assets::<Locale>::default();
+        let english = locales.add(Locale::default());
+        let german = match german {
+            Some(locale) => locales.add(locale),
+            None => Handle::default(),
+        };
+        world.insert_resource(locales);
+        world.insert_resource(LocaleHandles(HashMap::from([
+            (Language::English, english),
+            (Language::German, german),
+        ])));
+        world.run_system_once(update_localization).unwrap();
+        world
+    }
+
+    #[test]
+    fn english_is_there_before_any_file_loads() {
+        let localization = Localization::default();
+        assert_eq!(localization.get("back"), "Back");
+        assert_eq!(
+            localization.format("game-over-score", &[("score", 12.to_string())]),
+            "Score 12"
+        );
+    }
+
+    #[test]
+    fn the_picked_language_is_used_once_loaded() {
+        let world = world(Some(german()));
+        let localization = world.resource::<Localization>();
+        assert_eq!(localization.get("back"), "Zurück");
+        assert_eq!(
+            localization.format("game-over-score", &[("score", 12.to_string())]),
+            "Punkte 12"
+        );
+    }
+
+    #[test]
+    fn english_stays_until_the_picked_language_loads() {
+        let world = world(None);
+        assert_eq!(world.resource::<Localization>().get("back"), "Back");
+    }
+
+    #[test]
+    fn missing_translations_fall_back_to_english() {
+        let world = world(Some(german()));
+        assert_eq!(world.resource::<Localization>().get("cancel"), "Cancel");
+    }
+
+    #[test]
+    fn unknown_keys_show_as_themselves() {
+        let world = world(Some(german()));
+        assert_eq!(
+            world.resource::<Localization>().get("no-such-key"),
+            "no-such-key"
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, io::Reader};
+use bevy::prelude::*;
+
+use super::navigation::menu_cancel;
+use super::{MenuButton, small_button};
+use crate::loading::LoadingAssets;
+use crate::state::GameState;
+use crate::theme::{Surface, TextRole, Theme};
+
+const CREDITS_PATH: &str = "credits.txt";
+// Logical pixels per second
+const SCROLL_SPEED: f32 = 40.0;
+
+// The text of assets/credits.txt rolling up the screen. Back, Escape or the end of the text
+// return to the main menu.
+pub(super) struct CreditsPlugin;
+
+impl Plugin for CreditsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Credits>()
+            .init_asset_loader::<CreditsLoader>()
+            .add_systems(Startup, load_credits)
+            .add_systems(OnEnter(GameState::Credits), spawn_credits_screen)
+            .add_systems(
+                Update,
+                (scroll_credits, back_to_menu.run_if(menu_cancel))
+                    .run_if(in_state(GameState::Credits)),
+            );
+    }
+}
+
+#[derive(Asset, TypePath, Clone, Debug, Default)]
+pub struct Credits {
+    pub text: String,
+}
+
+#[derive(Resource)]
+struct CreditsHandle(Handle<Credits>);
+
+#[derive(Component)]
+struct CreditsViewport;
+
+// How far the credits have rolled, in logical pixels
+#[derive(Component, Default)]
+struct CreditsScroll(f32);
+
+fn load_credits(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let handle = asset_server.load(CREDITS_PATH);
+    loading.track(handle.clone());
+    commands.insert_resource(CreditsHandle(handle));
+}
+
+fn spawn_credits_screen(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    handle: Res<CreditsHandle>,
+    credits: Res<Assets<Credits>>,
+) {
+    let text = credits
+        .get(&handle.0)
+        .map(|credits| credits.text.clone())
+        .unwrap_or_default();
+    commands.spawn((
+        StateScoped(GameState::Credits),
+        Name::new("Credits"),
+        CreditsViewport,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            overflow: Overflow::clip(),
+            ..Default::default()
+        },
+        theme.surface(Surface::Backdrop),
+        children![
+            (
+                CreditsScroll::default(),
+                Text::new(text),
+                theme.text(TextRole::Body),
+                TextLayout::new_with_justify(JustifyText::Center),
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    // Starts just below the bottom edge
+                    top: Val::Percent(100.0),
+                    ..Default::default()
+                },
+            ),
+            (
+                Node {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(theme.gap),
+                    bottom: Val::Px(theme.gap),
+                    ..Default::default()
+                },
+                children![small_button(&theme, MenuButton::Back, "back")],
+            ),
+        ],
+    ));
+}
+
+fn scroll_credits(
+    time: Res<Time>,
+    mut next_state: ResMut<NextState<GameState>>,
+    viewport: Single<&ComputedNode, With<CreditsViewport>>,
+    text: Single<(&mut Node, &ComputedNode, &mut CreditsScroll)>,
+) {
+    let (mut node, computed, mut scroll) = text.into_inner();
+    let viewport_height = viewport.size().y * viewport.inverse_scale_factor();
+    let text_height = computed.size().y * computed.inverse_scale_factor();
+    // Not laid out yet
+    if viewport_height <= 0.0 || text_height <= 0.0 {
+        return;
+    }
+    scroll.0 += SCROLL_SPEED * time.delta_secs();
+    node.top = Val::Px(viewport_height - scroll.0);
+    if scroll.0 > viewport_height + text_height {
+        next_state.set(GameState::Menu);
+    }
+}
+
+fn back_to_menu(mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(GameState::Menu);
+}
+
+#[derive(Default)]
+pub struct CreditsLoader;
+
+impl AssetLoader for CreditsLoader {
+    type Asset = Credits;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut text = String::new();
+        reader.read_to_string(&mut text).await?;
+        Ok(Credits { text })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["txt"]
+    }
+}
@@ -0,0 +1,186 @@
+use bevy::prelude::*;
+
+use super::{menu_button, screen_fade_in, screen_node, screen_title};
+use crate::high_score::{HighScores, NewHighScore, record_high_score};
+use crate::localization::LocalizedText;
+use crate::mode::{Countdown, GameMode};
+use crate::score::Score;
+use crate::state::GameState;
+use crate::theme::{Surface, TextRole, Theme};
+use crate::tween::Tween;
+
+// Widths of the high score table's columns: place, name, score and date
+const COLUMN_WIDTHS: [f32; 4] = [40.0, 200.0, 80.0, 120.0];
+
+// Final score with Restart and Main menu buttons, or the fish eaten when a timed game ran out of
+// time, above the mode's high scores (and a banner if the game set a new record). Leaving
+// `InGame` has already despawned the cats and the HUD, so restarting goes through the same setup
+// as a new game, in the same mode.
+pub(super) struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::GameOver),
+            spawn_game_over_screen.after(record_high_score),
+        )
+        .add_systems(
+            Update,
+            press_game_over_buttons.run_if(in_state(GameState::GameOver)),
+        );
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameOverButton {
+    Restart,
+    MainMenu,
+}
+
+impl GameOverButton {
+    fn label(self) -> &'static str {
+        match self {
+            GameOverButton::Restart => "game-over-restart",
+            GameOverButton::MainMenu => "game-over-main-menu",
+        }
+    }
+}
+
+fn spawn_game_over_screen(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    score: Res<Score>,
+    mode: Res<GameMode>,
+    countdown: Res<Countdown>,
+    high_scores: Res<HighScores>,
+    new_high_score: Option<Res<NewHighScore>>,
+) {
+    // Fish are worth a point each, so the score is the fish count
+    let (title, result) = if *mode == GameMode::Countdown && countdown.timer.finished() {
+        (
+            LocalizedText::new("results-title"),
+            LocalizedText::new("results-fish").with_arg("fish", score.0),
+        )
+    } else {
+        (
+            LocalizedText::new("game-over-title"),
+            LocalizedText::new("game-over-score").with_arg("score", score.0),
+        )
+    };
+    commands
+        .spawn((
+            StateScoped(GameState::GameOver),
+            Name::new("Game over"),
+            screen_node(&theme),
+            theme.surface(Surface::Overlay),
+            screen_fade_in(),
+        ))
+        .with_children(|screen| {
+            screen.spawn((Text::default(), title, theme.text(TextRole::Title)));
+            if new_high_score.as_ref().is_some_and(|new| new.is_record()) {
+                screen.spawn(new_record_banner(&theme));
+            }
+            screen.spawn(screen_title(&theme, TextRole::Button, result));
+            let new_rank = new_high_score.map(|new| new.rank);
+            spawn_high_score_table(screen, &theme, &high_scores, *mode, new_rank);
+            for button in [GameOverButton::Restart, GameOverButton::MainMenu] {
+                screen.spawn(menu_button(&theme, button, button.label()));
+            }
+        });
+}
+
+fn new_record_banner(theme: &Theme) -> impl Bundle {
+    (
+        Node {
+            padding: theme.button_padding(),
+            ..Default::default()
+        },
+        theme.surface(Surface::Accent),
+        Tween::new(0.4)
+            .with_ease(EaseFunction::BackOut)
+            .scale(0.0, 1.0),
+        children![(
+            Text::default(),
+            LocalizedText::new("high-scores-new-record"),
+            theme.text(TextRole::Button),
+        )],
+    )
+}
+
+// The mode's top ten, with the row of the game that just ended (if it made it) highlighted
+fn spawn_high_score_table(
+    screen: &mut ChildSpawnerCommands,
+    theme: &Theme,
+    high_scores: &HighScores,
+    mode: GameMode,
+    new_rank: Option<usize>,
+) {
+    let heading = if high_scores.top(mode).next().is_some() {
+        "high-scores-title"
+    } else {
+        "high-scores-empty"
+    };
+    screen
+        .spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(theme.gap / 2.0),
+                padding: theme.panel_padding(),
+                margin: UiRect::bottom(Val::Px(theme.gap * 2.0)),
+                ..Default::default()
+            },
+            theme.surface(Surface::Panel),
+        ))
+        .with_children(|table| {
+            table.spawn((
+                Text::default(),
+                LocalizedText::new(heading),
+                theme.text(TextRole::Body),
+            ));
+            for (rank, high_score) in high_scores.top(mode).enumerate() {
+                let color = if new_rank == Some(rank) {
+                    theme.accent
+                } else {
+                    theme.text
+                };
+                let cells = [
+                    format!("{}.", rank + 1),
+                    high_score.name.clone(),
+                    high_score.score.to_string(),
+                    high_score.date.clone(),
+                ];
+                table.spawn(Node::default()).with_children(|row| {
+                    for (cell, width) in cells.into_iter().zip(COLUMN_WIDTHS) {
+                        row.spawn((
+                            Node {
+                                width: Val::Px(width),
+                                ..Default::default()
+                            },
+                            children![(
+                                Text::new(cell),
+                                TextRole::Body,
+                                theme.text_font(TextRole::Body),
+                                TextColor(color),
+                            )],
+                        ));
+                    }
+                });
+            }
+        });
+}
+
+fn press_game_over_buttons(
+    mut next_state: ResMut<NextState<GameState>>,
+    buttons: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        next_state.set(match button {
+            GameOverButton::Restart => GameState::Playing,
+            GameOverButton::MainMenu => GameState::Menu,
+        });
+    }
+}
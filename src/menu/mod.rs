@@ -0,0 +1,208 @@
+use bevy::prelude::*;
+
+use crate::localization::LocalizedText;
+use crate::mode::GameMode;
+use crate::state::GameState;
+use crate::theme::{Surface, TextRole, Theme};
+use crate::tween::Tween;
+
+mod credits;
+mod game_over;
+mod navigation;
+mod pause;
+mod quit;
+mod settings;
+
+pub use credits::Credits;
+pub use game_over::GameOverButton;
+pub use navigation::Focused;
+pub use pause::PauseButton;
+pub use quit::QuitDialog;
+pub use settings::{SettingsScreen, SettingsTab, open_settings_screen};
+
+const SCREEN_FADE_SECONDS: f32 = 0.2;
+
+// The title screen the game starts on, the pause and game over menus, the settings screen and the
+// credits
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            pause::PausePlugin,
+            game_over::GameOverPlugin,
+            settings::SettingsScreenPlugin,
+            credits::CreditsPlugin,
+            quit::QuitDialogPlugin,
+            navigation::MenuNavigationPlugin,
+        ))
+        .add_systems(OnEnter(GameState::Menu), spawn_main_menu)
+        .add_systems(Update, highlight_buttons)
+        .add_systems(
+            Update,
+            press_menu_buttons.run_if(in_state(GameState::Menu).or(in_state(GameState::Credits))),
+        );
+    }
+}
+
+// A button spawned by `menu_button` or `small_button`, highlighted on hover
+#[derive(Component)]
+pub struct MenuEntry;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuButton {
+    // Starts a game in the mode
+    Play(GameMode),
+    Settings,
+    Credits,
+    Quit,
+    // Leaves the credits
+    Back,
+}
+
+impl MenuButton {
+    fn label(self) -> &'static str {
+        match self {
+            MenuButton::Play(mode) => mode.label(),
+            MenuButton::Settings => "menu-settings",
+            MenuButton::Credits => "menu-credits",
+            MenuButton::Quit => "menu-quit",
+            MenuButton::Back => "back",
+        }
+    }
+}
+
+fn spawn_main_menu(mut commands: Commands, theme: Res<Theme>) {
+    commands
+        .spawn((
+            StateScoped(GameState::Menu),
+            Name::new("Main menu"),
+            screen_node(&theme),
+            screen_fade_in(),
+        ))
+        .with_children(|menu| {
+            menu.spawn(screen_title(
+                &theme,
+                TextRole::Title,
+                LocalizedText::new("game-title"),
+            ));
+            let modes = GameMode::ALL.map(MenuButton::Play);
+            let others = [MenuButton::Settings, MenuButton::Credits, MenuButton::Quit];
+            for button in modes.into_iter().chain(others) {
+                menu.spawn(menu_button(&theme, button, button.label()));
+            }
+        });
+}
+
+// How menu screens appear instead of popping up
+pub fn screen_fade_in() -> Tween {
+    Tween::new(SCREEN_FADE_SECONDS).fade(0.0, 1.0)
+}
+
+// Full-window column with everything centered, the layout of every menu screen
+pub fn screen_node(theme: &Theme) -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        flex_direction: FlexDirection::Column,
+        align_items: AlignItems::Center,
+        justify_content: JustifyContent::Center,
+        row_gap: Val::Px(theme.gap),
+        ..Default::default()
+    }
+}
+
+// Heading at the top of a menu screen, set apart from the buttons below
+pub fn screen_title(theme: &Theme, role: TextRole, title: LocalizedText) -> impl Bundle {
+    (
+        Text::default(),
+        title,
+        theme.text(role),
+        Node {
+            margin: UiRect::bottom(Val::Px(theme.gap * 2.0)),
+            ..Default::default()
+        },
+    )
+}
+
+// `label` is a localization key, as for every button
+pub fn menu_button<B: Component>(theme: &Theme, marker: B, label: &'static str) -> impl Bundle {
+    (
+        marker,
+        MenuEntry,
+        Button,
+        Node {
+            width: Val::Px(240.0),
+            padding: theme.button_padding(),
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        },
+        theme.surface(Surface::Button),
+        children![(
+            Text::default(),
+            LocalizedText::new(label),
+            theme.text(TextRole::Button)
+        )],
+    )
+}
+
+// A compact `menu_button` for rows of options
+pub fn small_button<B: Component>(theme: &Theme, marker: B, label: &'static str) -> impl Bundle {
+    (
+        marker,
+        MenuEntry,
+        Button,
+        Node {
+            padding: UiRect::axes(
+                Val::Px(theme.button_padding.x / 2.0),
+                Val::Px(theme.button_padding.y / 2.0),
+            ),
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        },
+        theme.surface(Surface::Button),
+        children![(
+            Text::default(),
+            LocalizedText::new(label),
+            theme.text(TextRole::Body)
+        )],
+    )
+}
+
+type InteractedMenuEntry = (Changed<Interaction>, With<MenuEntry>);
+
+fn highlight_buttons(
+    theme: Res<Theme>,
+    mut buttons: Query<(&Interaction, &mut BackgroundColor), InteractedMenuEntry>,
+) {
+    for (interaction, mut color) in &mut buttons {
+        color.0 = match interaction {
+            Interaction::Pressed => theme.accent,
+            Interaction::Hovered => theme.button_hovered,
+            Interaction::None => theme.button,
+        };
+    }
+}
+
+fn press_menu_buttons(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    buttons: Query<(&Interaction, &MenuButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            MenuButton::Play(mode) => {
+                commands.insert_resource(*mode);
+                next_state.set(GameState::Playing);
+            }
+            MenuButton::Settings => commands.run_system_cached(open_settings_screen),
+            MenuButton::Credits => next_state.set(GameState::Credits),
+            MenuButton::Back => next_state.set(GameState::Menu),
+            MenuButton::Quit => commands.run_system_cached(quit::open_quit_dialog),
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+use crate::console::console_is_open;
+use crate::input::is_rebinding;
+use crate::state::GameState;
+use crate::theme::Theme;
+
+// Not Space, that's jumping
+const CONFIRM_KEYS: [KeyCode; 2] = [KeyCode::Enter, KeyCode::NumpadEnter];
+const CONFIRM_BUTTON: GamepadButton = GamepadButton::South;
+const CANCEL_BUTTON: GamepadButton = GamepadButton::East;
+
+// Moves a focus outline between the buttons of the topmost screen with the arrow keys or the
+// d-pad, wrapping around at the edges. Enter or South presses the focused button the same way a
+// click does, and East goes back like Escape (see `menu_cancel`).
+pub(super) struct MenuNavigationPlugin;
+
+impl Plugin for MenuNavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (release_pressed, navigate)
+                .chain()
+                .after(UiSystem::Focus)
+                .run_if(not(is_rebinding).and(not(console_is_open))),
+        );
+    }
+}
+
+// Escape, or East on a gamepad, closes whatever screen is on top. East dashes while playing, so
+// it only goes back while the game isn't being played.
+pub(super) fn menu_cancel(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    state: Res<State<GameState>>,
+) -> bool {
+    keyboard_input.just_pressed(KeyCode::Escape)
+        || (*state.get() != GameState::Playing
+            && gamepads
+                .iter()
+                .any(|gamepad| gamepad.just_pressed(CANCEL_BUTTON)))
+}
+
+// The button navigation is on
+#[derive(Component)]
+pub struct Focused;
+
+// Pressed by navigation rather than the mouse, so released again a frame later
+#[derive(Component)]
+struct NavigationPress;
+
+fn release_pressed(
+    mut commands: Commands,
+    mut pressed: Query<(Entity, &mut Interaction), With<NavigationPress>>,
+) {
+    for (entity, mut interaction) in &mut pressed {
+        *interaction = Interaction::None;
+        commands.entity(entity).remove::<NavigationPress>();
+    }
+}
+
+// The keyboard and every gamepad, as far as moving through a menu goes
+#[derive(SystemParam)]
+struct NavigationInput<'w, 's> {
+    keyboard_input: Res<'w, ButtonInput<KeyCode>>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl NavigationInput<'_, '_> {
+    fn just_pressed(&self, key: KeyCode, button: GamepadButton) -> bool {
+        self.keyboard_input.just_pressed(key)
+            || self
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.just_pressed(button))
+    }
+
+    // Up is negative, as in UI coordinates
+    fn direction(&self) -> Vec2 {
+        let mut direction = Vec2::ZERO;
+        if self.just_pressed(KeyCode::ArrowUp, GamepadButton::DPadUp) {
+            direction.y -= 1.0;
+        }
+        if self.just_pressed(KeyCode::ArrowDown, GamepadButton::DPadDown) {
+            direction.y += 1.0;
+        }
+        if self.just_pressed(KeyCode::ArrowLeft, GamepadButton::DPadLeft) {
+            direction.x -= 1.0;
+        }
+        if self.just_pressed(KeyCode::ArrowRight, GamepadButton::DPadRight) {
+            direction.x += 1.0;
+        }
+        direction
+    }
+
+    fn confirm(&self) -> bool {
+        self.keyboard_input.any_just_pressed(CONFIRM_KEYS)
+            || self
+                .gamepads
+                .iter()
+                .any(|gamepad| gamepad.just_pressed(CONFIRM_BUTTON))
+    }
+}
+
+type NavigableButton = (
+    Entity,
+    &'static GlobalTransform,
+    &'static InheritedVisibility,
+    Ref<'static, Interaction>,
+);
+
+fn navigate(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    input: NavigationInput,
+    buttons: Query<NavigableButton, With<Button>>,
+    focused: Query<Entity, With<Focused>>,
+    parents: Query<&ChildOf>,
+    z_indices: Query<&GlobalZIndex>,
+) {
+    // Only the buttons of the screen on top, e.g. the settings rather than the menu under them
+    let root_of = |entity| parents.root_ancestor(entity);
+    let layer_of = |root| (z_indices.get(root).map_or(0, |z| z.0), root);
+    let Some(top) = buttons
+        .iter()
+        .filter(|(_, _, visibility, _)| visibility.get())
+        .map(|(entity, ..)| layer_of(root_of(entity)))
+        .max()
+        .map(|(_, root)| root)
+    else {
+        return;
+    };
+    let candidates: Vec<(Entity, Vec2)> = buttons
+        .iter()
+        .filter(|(entity, _, visibility, _)| visibility.get() && root_of(*entity) == top)
+        .map(|(entity, transform, ..)| (entity, transform.translation().truncate()))
+        .collect();
+
+    let current = focused
+        .iter()
+        .find(|entity| candidates.iter().any(|(candidate, _)| candidate == entity));
+    // Hovering with the mouse moves the focus too
+    let hovered = candidates.iter().find(|(entity, _)| {
+        buttons.get(*entity).is_ok_and(|(.., interaction)| {
+            interaction.is_changed() && *interaction == Interaction::Hovered
+        })
+    });
+
+    let direction = input.direction();
+    let next = if let Some(&(entity, _)) = hovered.filter(|(entity, _)| Some(*entity) != current) {
+        Some(entity)
+    } else if direction == Vec2::ZERO {
+        current
+    } else if let Some(position) = current.and_then(|current| {
+        candidates
+            .iter()
+            .find(|(entity, _)| *entity == current)
+            .map(|(_, position)| *position)
+    }) {
+        step(&candidates, position, direction.normalize()).or(current)
+    } else {
+        // Nothing focused yet, start at the top left
+        candidates
+            .iter()
+            .min_by(|(_, a), (_, b)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)))
+            .map(|(entity, _)| *entity)
+    };
+
+    for entity in &focused {
+        if Some(entity) != next {
+            commands.entity(entity).remove::<(Focused, Outline)>();
+        }
+    }
+    let Some(next) = next else {
+        return;
+    };
+    if Some(next) != current {
+        commands
+            .entity(next)
+            .insert((Focused, Outline::new(Val::Px(2.0), Val::ZERO, theme.accent)));
+    }
+
+    if input.confirm() && current == Some(next) {
+        commands
+            .entity(next)
+            .insert((Interaction::Pressed, NavigationPress));
+    }
+}
+
+// The closest button in `direction`, or the furthest one the opposite way to wrap around to
+fn step(candidates: &[(Entity, Vec2)], from: Vec2, direction: Vec2) -> Option<Entity> {
+    // Distance along the direction and across it, the latter counting double so moving down a
+    // column doesn't jump to a closer button in the next one
+    let offsets = candidates.iter().map(|&(entity, position)| {
+        let offset = position - from;
+        (
+            entity,
+            offset.dot(direction),
+            offset.perp_dot(direction).abs(),
+        )
+    });
+    let ahead = offsets
+        .clone()
+        .filter(|&(_, along, _)| along > 1.0)
+        .min_by(|a, b| (a.1 + a.2 * 2.0).total_cmp(&(b.1 + b.2 * 2.0)));
+    let wrapped = || {
+        offsets
+            .clone()
+            .filter(|&(_, along, _)| along < -1.0)
+            .min_by(|a, b| (a.1 + a.2 * 2.0).total_cmp(&(b.1 + b.2 * 2.0)))
+    };
+    ahead.or_else(wrapped).map(|(entity, ..)| entity)
+}
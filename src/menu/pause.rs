@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+
+use super::navigation::menu_cancel;
+use super::{
+    QuitDialog, SettingsScreen, menu_button, open_settings_screen, screen_fade_in, screen_node,
+    screen_title,
+};
+use crate::console::console_is_open;
+use crate::input::is_rebinding;
+use crate::localization::LocalizedText;
+use crate::state::GameState;
+use crate::theme::{Surface, TextRole, Theme};
+
+// Escape stops the game clock and shows Resume/Settings/Quit over the dimmed game
+pub(super) struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Paused), (pause_time, spawn_pause_menu))
+            .add_systems(OnExit(GameState::Paused), resume_time)
+            .add_systems(
+                Update,
+                toggle_pause.run_if(
+                    menu_cancel
+                        .and(in_state(GameState::Playing).or(in_state(GameState::Paused)))
+                        // Escape cancels rebinding or closes the settings instead
+                        .and(not(is_rebinding))
+                        .and(not(console_is_open))
+                        .and(not(any_with_component::<QuitDialog>))
+                        .and(not(any_with_component::<SettingsScreen>)),
+                ),
+            )
+            .add_systems(
+                Update,
+                press_pause_buttons.run_if(in_state(GameState::Paused)),
+            );
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PauseButton {
+    Resume,
+    Settings,
+    Quit,
+}
+
+impl PauseButton {
+    fn label(self) -> &'static str {
+        match self {
+            PauseButton::Resume => "pause-resume",
+            PauseButton::Settings => "menu-settings",
+            PauseButton::Quit => "pause-quit",
+        }
+    }
+}
+
+fn toggle_pause(state: Res<State<GameState>>, mut next_state: ResMut<NextState<GameState>>) {
+    next_state.set(match state.get() {
+        GameState::Paused => GameState::Playing,
+        _ => GameState::Paused,
+    });
+}
+
+// Freezes everything driven by `Time`: fixed-step movement, animations, timers
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn resume_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn spawn_pause_menu(mut commands: Commands, theme: Res<Theme>) {
+    commands
+        .spawn((
+            StateScoped(GameState::Paused),
+            Name::new("Pause menu"),
+            screen_node(&theme),
+            theme.surface(Surface::Overlay),
+            screen_fade_in(),
+        ))
+        .with_children(|menu| {
+            menu.spawn(screen_title(
+                &theme,
+                TextRole::Heading,
+                LocalizedText::new("pause-title"),
+            ));
+            for button in [
+                PauseButton::Resume,
+                PauseButton::Settings,
+                PauseButton::Quit,
+            ] {
+                menu.spawn(menu_button(&theme, button, button.label()));
+            }
+        });
+}
+
+fn press_pause_buttons(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    buttons: Query<(&Interaction, &PauseButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            PauseButton::Resume => next_state.set(GameState::Playing),
+            PauseButton::Settings => commands.run_system_cached(open_settings_screen),
+            PauseButton::Quit => next_state.set(GameState::Menu),
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use bevy::window::WindowCloseRequested;
+
+use super::navigation::menu_cancel;
+use super::{SettingsScreen, screen_fade_in, small_button};
+use crate::console::console_is_open;
+use crate::input::is_rebinding;
+use crate::localization::LocalizedText;
+use crate::state::GameState;
+use crate::theme::{Surface, TextRole, Theme};
+use crate::tween::Tween;
+
+// Asks before quitting when the window is closed, or Quit or Escape is pressed on the main menu.
+// Closing the window needs `WindowPlugin::close_when_requested` turned off, or it's gone before
+// asking.
+pub(super) struct QuitDialogPlugin;
+
+impl Plugin for QuitDialogPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                open_quit_dialog.run_if(on_event::<WindowCloseRequested>),
+                open_quit_dialog.run_if(
+                    menu_cancel
+                        .and(in_state(GameState::Menu))
+                        .and(not(any_with_component::<QuitDialog>))
+                        .and(not(any_with_component::<SettingsScreen>))
+                        .and(not(is_rebinding))
+                        .and(not(console_is_open)),
+                ),
+                (
+                    press_quit_dialog_buttons,
+                    close_quit_dialog.run_if(menu_cancel),
+                )
+                    .run_if(any_with_component::<QuitDialog>),
+            ),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct QuitDialog;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+enum QuitDialogButton {
+    Quit,
+    Cancel,
+}
+
+// Also what the main menu's Quit button opens, so the game only ever exits through `confirm_quit`
+pub(super) fn open_quit_dialog(
+    mut commands: Commands,
+    mut events: EventReader<WindowCloseRequested>,
+    theme: Res<Theme>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    dialogs: Query<(), With<QuitDialog>>,
+) {
+    events.clear();
+    if !dialogs.is_empty() {
+        return;
+    }
+    // The game shouldn't go on behind the question
+    if *state.get() == GameState::Playing {
+        next_state.set(GameState::Paused);
+    }
+    commands.spawn((
+        QuitDialog,
+        Name::new("Quit dialog"),
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        },
+        theme.surface(Surface::Overlay),
+        // Above every other screen
+        GlobalZIndex(20),
+        screen_fade_in(),
+        children![(
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(theme.gap),
+                padding: theme.panel_padding(),
+                ..Default::default()
+            },
+            theme.surface(Surface::Panel),
+            // Pops up from a little smaller
+            Tween::new(0.15).scale(0.9, 1.0),
+            children![
+                (
+                    Text::default(),
+                    LocalizedText::new("quit-title"),
+                    theme.text(TextRole::Heading),
+                ),
+                (
+                    Text::default(),
+                    LocalizedText::new("quit-warning"),
+                    theme.text(TextRole::Body),
+                ),
+                (
+                    Node {
+                        column_gap: Val::Px(theme.gap),
+                        ..Default::default()
+                    },
+                    children![
+                        small_button(&theme, QuitDialogButton::Quit, "menu-quit"),
+                        small_button(&theme, QuitDialogButton::Cancel, "cancel"),
+                    ],
+                ),
+            ],
+        )],
+    ));
+}
+
+fn close_quit_dialog(mut commands: Commands, dialogs: Query<Entity, With<QuitDialog>>) {
+    for dialog in &dialogs {
+        commands.entity(dialog).despawn();
+    }
+}
+
+fn confirm_quit(mut exit: EventWriter<AppExit>) {
+    exit.write(AppExit::Success);
+}
+
+fn press_quit_dialog_buttons(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &QuitDialogButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match button {
+            QuitDialogButton::Quit => commands.run_system_cached(confirm_quit),
+            QuitDialogButton::Cancel => commands.run_system_cached(close_quit_dialog),
+        }
+    }
+}
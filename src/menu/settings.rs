@@ -0,0 +1,673 @@
+use bevy::prelude::*;
+use bevy::ui::RelativeCursorPosition;
+
+use super::navigation::menu_cancel;
+use super::{screen_fade_in, screen_node, small_button};
+use crate::audio::{AudioSettings, PlaySound, SoundId};
+use crate::input::{CatAction, Keybindings, is_rebinding, toggle_controls_screen};
+use crate::localization::{Localization, LocalizedText};
+use crate::settings::{SaveSettings, Settings};
+use crate::theme::{Surface, TextRole, Theme};
+use crate::tooltip::Tooltip;
+
+// How much one press of - or + changes a volume
+const VOLUME_STEP: f32 = 0.1;
+const SLIDER_SIZE: Vec2 = Vec2::new(160.0, 14.0);
+
+// General/Graphics/Audio/Controls tabs over the menu or the paused game, editing `Settings` live.
+// Escape or Back closes it, saving the settings if anything was changed.
+pub(super) struct SettingsScreenPlugin;
+
+impl Plugin for SettingsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(save_on_close).add_systems(
+            Update,
+            (
+                press_settings_buttons,
+                drag_volume_sliders,
+                show_tab,
+                update_setting_texts,
+                close_settings_screen.run_if(menu_cancel.and(not(is_rebinding))),
+            )
+                .chain()
+                .run_if(any_with_component::<SettingsScreen>),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct SettingsScreen;
+
+// The settings as they were when the screen was opened
+#[derive(Component)]
+struct SettingsOnOpen(Settings);
+
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SettingsTab {
+    #[default]
+    General,
+    Graphics,
+    Audio,
+    Controls,
+}
+
+impl SettingsTab {
+    const ALL: [SettingsTab; 4] = [
+        SettingsTab::General,
+        SettingsTab::Graphics,
+        SettingsTab::Audio,
+        SettingsTab::Controls,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            SettingsTab::General => "settings-general",
+            SettingsTab::Graphics => "settings-graphics",
+            SettingsTab::Audio => "settings-audio",
+            SettingsTab::Controls => "settings-controls",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Volume {
+    Master,
+    Music,
+    Effects,
+    Ui,
+}
+
+impl Volume {
+    // Played when letting go of the slider, the music is already playing
+    fn preview(self) -> Option<PlaySound> {
+        match self {
+            Volume::Master | Volume::Effects => Some(PlaySound::new(SoundId::Meow)),
+            Volume::Music => None,
+            Volume::Ui => Some(PlaySound::new(SoundId::Click)),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Volume::Master => "settings-master-volume",
+            Volume::Music => "settings-music-volume",
+            Volume::Effects => "settings-effects-volume",
+            Volume::Ui => "settings-ui-volume",
+        }
+    }
+
+    fn get(self, audio: &AudioSettings) -> f32 {
+        match self {
+            Volume::Master => audio.master,
+            Volume::Music => audio.music,
+            Volume::Effects => audio.effects,
+            Volume::Ui => audio.ui,
+        }
+    }
+
+    fn get_mut(self, audio: &mut AudioSettings) -> &mut f32 {
+        match self {
+            Volume::Master => &mut audio.master,
+            Volume::Music => &mut audio.music,
+            Volume::Effects => &mut audio.effects,
+            Volume::Ui => &mut audio.ui,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+enum SettingsButton {
+    Tab(SettingsTab),
+    Language,
+    PresentMode,
+    Resolution,
+    Fullscreen,
+    UiScale,
+    ReducedMotion,
+    HighContrast,
+    Volume(Volume, f32),
+    Mute,
+    MusicTrack,
+    EditControls,
+    Back,
+}
+
+// Text showing the current value of a setting
+#[derive(Component, Clone, Copy, Debug)]
+enum SettingValue {
+    Language,
+    PresentMode,
+    Resolution,
+    Fullscreen,
+    UiScale,
+    ReducedMotion,
+    HighContrast,
+    Volume(Volume),
+    Mute,
+    MusicTrack,
+    Binding(CatAction),
+}
+
+impl SettingValue {
+    // Localization key of the explanation shown when hovering the setting's row
+    fn tooltip(self) -> Option<&'static str> {
+        match self {
+            SettingValue::Language => Some("settings-language-tooltip"),
+            SettingValue::PresentMode => Some("settings-present-mode-tooltip"),
+            SettingValue::Resolution => Some("settings-resolution-tooltip"),
+            SettingValue::Fullscreen => Some("settings-fullscreen-tooltip"),
+            SettingValue::UiScale => Some("settings-ui-scale-tooltip"),
+            SettingValue::ReducedMotion => Some("settings-reduced-motion-tooltip"),
+            SettingValue::HighContrast => Some("settings-high-contrast-tooltip"),
+            SettingValue::Volume(_) => Some("settings-volume-tooltip"),
+            SettingValue::Mute => Some("settings-mute-tooltip"),
+            SettingValue::MusicTrack => Some("settings-music-track-tooltip"),
+            SettingValue::Binding(_) => None,
+        }
+    }
+}
+
+// A bar between a volume's - and + buttons that can be dragged, playing a sound on that volume's
+// bus when let go
+#[derive(Component, Clone, Copy, Debug)]
+#[require(Interaction, RelativeCursorPosition)]
+struct VolumeSlider {
+    volume: Volume,
+    dragging: bool,
+}
+
+// The filled part of a `VolumeSlider`
+#[derive(Component, Clone, Copy, Debug)]
+struct VolumeFill(Volume);
+
+// The part of the screen below the tabs, refilled when switching tabs
+#[derive(Component)]
+struct TabContent;
+
+// Which tab `TabContent` should show, on the screen entity
+#[derive(Component, Default)]
+struct SelectedTab {
+    tab: SettingsTab,
+    shown: Option<SettingsTab>,
+}
+
+pub fn open_settings_screen(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    settings: Res<Settings>,
+    screens: Query<(), With<SettingsScreen>>,
+) {
+    if !screens.is_empty() {
+        return;
+    }
+    commands
+        .spawn((
+            SettingsScreen,
+            SettingsOnOpen(settings.clone()),
+            SelectedTab::default(),
+            Name::new("Settings"),
+            screen_node(&theme),
+            // Hides whatever menu it was opened from
+            theme.surface(Surface::Backdrop),
+            screen_fade_in(),
+            GlobalZIndex(1),
+        ))
+        .with_children(|screen| {
+            screen.spawn((
+                Text::default(),
+                LocalizedText::new("menu-settings"),
+                theme.text(TextRole::Heading),
+            ));
+            screen
+                .spawn(Node {
+                    column_gap: Val::Px(theme.gap / 2.0),
+                    ..Default::default()
+                })
+                .with_children(|tabs| {
+                    for tab in SettingsTab::ALL {
+                        tabs.spawn(small_button(&theme, SettingsButton::Tab(tab), tab.label()));
+                    }
+                });
+            screen.spawn((
+                TabContent,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(theme.gap / 2.0),
+                    min_width: Val::Px(420.0),
+                    min_height: Val::Px(280.0),
+                    padding: UiRect::all(Val::Px(theme.gap)),
+                    ..Default::default()
+                },
+                theme.surface(Surface::Track),
+            ));
+            screen.spawn(small_button(&theme, SettingsButton::Back, "back"));
+        });
+}
+
+fn close_settings_screen(mut commands: Commands, screens: Query<Entity, With<SettingsScreen>>) {
+    for screen in &screens {
+        commands.entity(screen).despawn();
+    }
+}
+
+// However the screen goes away
+fn save_on_close(
+    trigger: Trigger<OnRemove, SettingsOnOpen>,
+    settings: Res<Settings>,
+    screens: Query<&SettingsOnOpen>,
+    mut saves: EventWriter<SaveSettings>,
+) {
+    if screens
+        .get(trigger.target())
+        .is_ok_and(|on_open| on_open.0 != *settings)
+    {
+        saves.write(SaveSettings);
+    }
+}
+
+fn press_settings_buttons(
+    mut commands: Commands,
+    mut settings: ResMut<Settings>,
+    mut selected: Single<&mut SelectedTab>,
+    buttons: Query<(&Interaction, &SettingsButton), Changed<Interaction>>,
+) {
+    for (interaction, button) in &buttons {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+        match *button {
+            SettingsButton::Tab(tab) => selected.tab = tab,
+            SettingsButton::Language => settings.next_language(),
+            SettingsButton::PresentMode => settings.next_present_mode(),
+            SettingsButton::Resolution => settings.next_resolution(),
+            SettingsButton::Fullscreen => settings.fullscreen = !settings.fullscreen,
+            SettingsButton::UiScale => settings.next_ui_scale(),
+            SettingsButton::ReducedMotion => {
+                settings.accessibility.reduced_motion = !settings.accessibility.reduced_motion;
+            }
+            SettingsButton::HighContrast => {
+                settings.accessibility.high_contrast = !settings.accessibility.high_contrast;
+            }
+            SettingsButton::Volume(volume, step) => {
+                let value = volume.get_mut(&mut settings.audio);
+                // Rounded so repeated steps land on whole percentages
+                *value = ((*value + step) * 100.0).round().clamp(0.0, 100.0) / 100.0;
+            }
+            SettingsButton::Mute => settings.audio.muted = !settings.audio.muted,
+            SettingsButton::MusicTrack => settings.next_music_track(),
+            SettingsButton::EditControls => commands.run_system_cached(toggle_controls_screen),
+            SettingsButton::Back => commands.run_system_cached(close_settings_screen),
+        }
+    }
+}
+
+// While dragging only the live `AudioSettings` change, so the new volume can be heard without
+// saving the settings on every frame. Letting go writes it to the settings for keeps.
+fn drag_volume_sliders(
+    mut settings: ResMut<Settings>,
+    mut audio: ResMut<AudioSettings>,
+    mut sounds: EventWriter<PlaySound>,
+    mut sliders: Query<(&Interaction, &RelativeCursorPosition, &mut VolumeSlider)>,
+) {
+    for (interaction, cursor, mut slider) in &mut sliders {
+        let pressed = *interaction == Interaction::Pressed;
+        if pressed {
+            // Keeps following the pointer outside of the bar until the button is let go
+            if let Some(position) = cursor.normalized {
+                let value = (position.x * 100.0).round().clamp(0.0, 100.0) / 100.0;
+                if slider.volume.get(&audio) != value {
+                    *slider.volume.get_mut(&mut audio) = value;
+                }
+            }
+        } else if slider.dragging {
+            if settings.audio != *audio {
+                settings.audio = *audio;
+            }
+            if let Some(preview) = slider.volume.preview() {
+                sounds.write(preview);
+            }
+        }
+        if slider.dragging != pressed {
+            slider.dragging = pressed;
+        }
+    }
+}
+
+fn show_tab(
+    mut commands: Commands,
+    theme: Res<Theme>,
+    mut selected: Single<&mut SelectedTab>,
+    content: Single<Entity, With<TabContent>>,
+) {
+    if selected.shown == Some(selected.tab) {
+        return;
+    }
+    selected.shown = Some(selected.tab);
+
+    let content = *content;
+    commands.entity(content).despawn_related::<Children>();
+    commands
+        .entity(content)
+        .with_children(|content| match selected.tab {
+            SettingsTab::General => {
+                setting_row(
+                    &theme,
+                    content,
+                    "settings-language",
+                    SettingValue::Language,
+                    |row| {
+                        row.spawn(small_button(
+                            &theme,
+                            SettingsButton::Language,
+                            "settings-change",
+                        ));
+                    },
+                );
+                setting_row(
+                    &theme,
+                    content,
+                    "settings-reduced-motion",
+                    SettingValue::ReducedMotion,
+                    |row| {
+                        row.spawn(small_button(
+                            &theme,
+                            SettingsButton::ReducedMotion,
+                            "settings-toggle",
+                        ));
+                    },
+                );
+                setting_row(
+                    &theme,
+                    content,
+                    "settings-high-contrast",
+                    SettingValue::HighContrast,
+                    |row| {
+                        row.spawn(small_button(
+                            &theme,
+                            SettingsButton::HighContrast,
+                            "settings-toggle",
+                        ));
+                    },
+                );
+            }
+            SettingsTab::Graphics => {
+                setting_row(
+                    &theme,
+                    content,
+                    "settings-present-mode",
+                    SettingValue::PresentMode,
+                    |row| {
+                        row.spawn(small_button(
+                            &theme,
+                            SettingsButton::PresentMode,
+                            "settings-change",
+                        ));
+                    },
+                );
+                setting_row(
+                    &theme,
+                    content,
+                    "settings-resolution",
+                    SettingValue::Resolution,
+                    |row| {
+                        row.spawn(small_button(
+                            &theme,
+                            SettingsButton::Resolution,
+                            "settings-change",
+                        ));
+                    },
+                );
+                setting_row(
+                    &theme,
+                    content,
+                    "settings-fullscreen",
+                    SettingValue::Fullscreen,
+                    |row| {
+                        row.spawn(small_button(
+                            &theme,
+                            SettingsButton::Fullscreen,
+                            "settings-toggle",
+                        ));
+                    },
+                );
+                setting_row(
+                    &theme,
+                    content,
+                    "settings-ui-scale",
+                    SettingValue::UiScale,
+                    |row| {
+                        row.spawn(small_button(
+                            &theme,
+                            SettingsButton::UiScale,
+                            "settings-change",
+                        ));
+                    },
+                );
+            }
+            SettingsTab::Audio => {
+                for volume in [Volume::Master, Volume::Music, Volume::Effects, Volume::Ui] {
+                    setting_row(
+                        &theme,
+                        content,
+                        volume.label(),
+                        SettingValue::Volume(volume),
+                        |row| {
+                            row.spawn(small_button(
+                                &theme,
+                                SettingsButton::Volume(volume, -VOLUME_STEP),
+                                "settings-decrease",
+                            ));
+                            row.spawn((
+                                VolumeSlider {
+                                    volume,
+                                    dragging: false,
+                                },
+                                Node {
+                                    width: Val::Px(SLIDER_SIZE.x),
+                                    height: Val::Px(SLIDER_SIZE.y),
+                                    ..Default::default()
+                                },
+                                BorderRadius::MAX,
+                                theme.surface(Surface::Button),
+                                children![(
+                                    VolumeFill(volume),
+                                    Node {
+                                        width: Val::Percent(0.0),
+                                        height: Val::Percent(100.0),
+                                        ..Default::default()
+                                    },
+                                    BorderRadius::MAX,
+                                    theme.surface(Surface::Meter),
+                                    Pickable::IGNORE,
+                                )],
+                            ));
+                            row.spawn(small_button(
+                                &theme,
+                                SettingsButton::Volume(volume, VOLUME_STEP),
+                                "settings-increase",
+                            ));
+                        },
+                    );
+                }
+                setting_row(
+                    &theme,
+                    content,
+                    "settings-mute",
+                    SettingValue::Mute,
+                    |row| {
+                        row.spawn(small_button(
+                            &theme,
+                            SettingsButton::Mute,
+                            "settings-toggle",
+                        ));
+                    },
+                );
+                setting_row(
+                    &theme,
+                    content,
+                    "settings-music-track",
+                    SettingValue::MusicTrack,
+                    |row| {
+                        row.spawn(small_button(
+                            &theme,
+                            SettingsButton::MusicTrack,
+                            "settings-change",
+                        ));
+                    },
+                );
+            }
+            SettingsTab::Controls => {
+                for action in CatAction::ALL {
+                    setting_row(
+                        &theme,
+                        content,
+                        action.label(),
+                        SettingValue::Binding(action),
+                        |_| {},
+                    );
+                }
+                content.spawn(small_button(
+                    &theme,
+                    SettingsButton::EditControls,
+                    "settings-edit-controls",
+                ));
+            }
+        });
+}
+
+fn setting_row(
+    theme: &Theme,
+    content: &mut ChildSpawnerCommands,
+    label: &'static str,
+    value: SettingValue,
+    buttons: impl FnOnce(&mut ChildSpawnerCommands),
+) {
+    let mut row = content.spawn(Node {
+        align_items: AlignItems::Center,
+        column_gap: Val::Px(theme.gap / 2.0),
+        ..Default::default()
+    });
+    if let Some(tooltip) = value.tooltip() {
+        row.insert(Tooltip(tooltip));
+    }
+    row.with_children(|row| {
+        row.spawn((
+            Text::default(),
+            LocalizedText::new(label),
+            theme.text(TextRole::Body),
+            Node {
+                width: Val::Px(160.0),
+                ..Default::default()
+            },
+        ));
+        row.spawn((
+            value,
+            Text::default(),
+            theme.text(TextRole::Body),
+            Node {
+                min_width: Val::Px(140.0),
+                ..Default::default()
+            },
+        ));
+        buttons(row);
+    });
+}
+
+fn update_setting_texts(
+    settings: Res<Settings>,
+    audio: Res<AudioSettings>,
+    keybindings: Res<Keybindings>,
+    localization: Res<Localization>,
+    mut texts: Query<(&SettingValue, &mut Text)>,
+    mut fills: Query<(&VolumeFill, &mut Node)>,
+) {
+    for (fill, mut node) in &mut fills {
+        let width = Val::Percent(fill.0.get(&audio) * 100.0);
+        if node.width != width {
+            node.width = width;
+        }
+    }
+    for (value, mut text) in &mut texts {
+        let shown = match *value {
+            SettingValue::Language => settings.language.name().to_owned(),
+            SettingValue::PresentMode => format!("{:?}", settings.present_mode),
+            SettingValue::Resolution => {
+                let size = format!("{} x {}", settings.resolution.x, settings.resolution.y);
+                match settings.resolution_preset() {
+                    Some(preset) => format!("{} ({size})", preset.name),
+                    None => size,
+                }
+            }
+            SettingValue::Fullscreen => format!(
+                "{} ({:?})",
+                on_off(&localization, settings.fullscreen),
+                settings.fullscreen_key
+            ),
+            SettingValue::UiScale => format!("{:.0}%", settings.ui_scale * 100.0),
+            SettingValue::ReducedMotion => {
+                on_off(&localization, settings.accessibility.reduced_motion)
+            }
+            SettingValue::HighContrast => {
+                on_off(&localization, settings.accessibility.high_contrast)
+            }
+            SettingValue::Volume(volume) => format!("{:.0}%", volume.get(&audio) * 100.0),
+            SettingValue::Mute => on_off(&localization, settings.audio.muted),
+            SettingValue::MusicTrack => localization.get(settings.music_track.label()).to_owned(),
+            SettingValue::Binding(action) => format!(
+                "{:?} / {:?}",
+                keybindings.key(action),
+                keybindings.gamepad_button(action)
+            ),
+        };
+        if text.0 != shown {
+            text.0 = shown;
+        }
+    }
+}
+
+fn on_off(localization: &Localization, on: bool) -> String {
+    localization.get(if on { "on" } else { "off" }).to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Opens the settings screen, lets `edit` change the settings and closes it again
+    fn saves(edit: impl FnOnce(&mut Settings)) -> usize {
+        let mut world = World::new();
+        world.init_resource::<Settings>();
+        world.init_resource::<Events<SaveSettings>>();
+        world.add_observer(save_on_close);
+        let screen = world
+            .spawn(SettingsOnOpen(world.resource::<Settings>().clone()))
+            .id();
+        edit(&mut world.resource_mut::<Settings>());
+        world.despawn(screen);
+        world.resource::<Events<SaveSettings>>().len()
+    }
+
+    #[test]
+    fn closing_without_changes_saves_nothing() {
+        assert_eq!(saves(|_| {}), 0);
+    }
+
+    #[test]
+    fn closing_with_changes_saves_once() {
+        assert_eq!(
+            saves(|settings| settings.fullscreen = !settings.fullscreen),
+            1
+        );
+    }
+
+    #[test]
+    fn changing_a_setting_back_saves_nothing() {
+        assert_eq!(
+            saves(|settings| {
+                settings.ui_scale *= 2.0;
+                settings.ui_scale /= 2.0;
+            }),
+            0
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+use bevy::window::PrimaryWindow;
+
+use crate::cat::ControlledBy;
+use crate::hud::HUD_HEIGHT;
+use crate::state::InGame;
+use crate::theme::{Surface, Theme};
+
+// Logical pixels, the map is square
+const MINIMAP_SIZE: f32 = 160.0;
+
+// Corner map under the HUD with a dot for every `MinimapIcon`, laid out by projecting world
+// positions onto the map in the UI rather than rendering the world a second time
+pub struct MinimapPlugin;
+
+impl Plugin for MinimapPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Minimap>()
+            .add_systems(OnEnter(InGame), spawn_minimap)
+            .add_systems(
+                PostUpdate,
+                (track_icons, place_dots)
+                    .chain()
+                    .run_if(in_state(InGame))
+                    .before(UiSystem::Layout),
+            );
+    }
+}
+
+// The part of the world the map covers. `None` follows the window, which is all of the world as
+// long as it's a single screen.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Minimap {
+    pub world: Option<Rect>,
+}
+
+// Shows the entity on the minimap as a dot
+#[derive(Component, Clone, Copy, Debug)]
+pub struct MinimapIcon {
+    pub color: Color,
+    // Logical pixels across
+    pub size: f32,
+}
+
+impl MinimapIcon {
+    pub fn new(color: Color) -> Self {
+        Self { color, size: 6.0 }
+    }
+}
+
+#[derive(Component)]
+struct MinimapPanel;
+
+// The dot of the entity with the `MinimapIcon`
+#[derive(Component)]
+struct MinimapDot(Entity);
+
+fn spawn_minimap(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        MinimapPanel,
+        StateScoped(InGame),
+        Name::new("Minimap"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(HUD_HEIGHT + theme.gap),
+            left: Val::Px(theme.gap),
+            width: Val::Px(MINIMAP_SIZE),
+            height: Val::Px(MINIMAP_SIZE),
+            overflow: Overflow::clip(),
+            ..Default::default()
+        },
+        theme.surface(Surface::Panel),
+        Pickable::IGNORE,
+    ));
+}
+
+// Adds dots for new icons and drops those of entities that are gone
+fn track_icons(
+    mut commands: Commands,
+    panel: Single<Entity, With<MinimapPanel>>,
+    icons: Query<(Entity, &MinimapIcon)>,
+    dots: Query<(Entity, &MinimapDot)>,
+) {
+    for (dot, MinimapDot(target)) in &dots {
+        if !icons.contains(*target) {
+            commands.entity(dot).despawn();
+        }
+    }
+    for (entity, icon) in &icons {
+        if dots.iter().any(|(_, MinimapDot(target))| *target == entity) {
+            continue;
+        }
+        commands.entity(*panel).with_child((
+            MinimapDot(entity),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Px(icon.size),
+                height: Val::Px(icon.size),
+                ..Default::default()
+            },
+            BackgroundColor(icon.color),
+            BorderRadius::MAX,
+        ));
+    }
+}
+
+fn place_dots(
+    minimap: Res<Minimap>,
+    theme: Res<Theme>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    icons: Query<(&Transform, &MinimapIcon, Has<ControlledBy>)>,
+    mut dots: Query<(&MinimapDot, &mut Node, &mut BackgroundColor)>,
+) {
+    let world = minimap
+        .world
+        .unwrap_or_else(|| Rect::from_center_size(Vec2::ZERO, window.size()));
+    for (MinimapDot(target), mut node, mut color) in &mut dots {
+        let Ok((transform, icon, controlled)) = icons.get(*target) else {
+            continue;
+        };
+        // World y points up, UI y points down
+        let uv = (transform.translation.truncate() - world.min) / world.size();
+        let offset = Vec2::new(uv.x, 1.0 - uv.y) * MINIMAP_SIZE - icon.size / 2.0;
+        node.left = Val::Px(offset.x);
+        node.top = Val::Px(offset.y);
+        color.0 = if controlled { theme.accent } else { icon.color };
+    }
+}
@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::audio::{PlaySound, SoundId};
+use crate::state::{GameState, InGame};
+
+const COUNTDOWN_SECONDS: f32 = 60.0;
+// The last seconds tick away audibly, and the HUD shows them in a hurry
+pub const WARNING_SECONDS: u32 = 10;
+
+// How a game is played, picked on the main menu: going through the levels until the cat is
+// caught, or eating as many fish as possible before the `Countdown` runs out. Running out of time
+// ends the game like running out of health, and the game over screen shows the results.
+pub struct GameModePlugin;
+
+impl Plugin for GameModePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameMode>()
+            .init_resource::<Countdown>()
+            .register_type::<GameMode>()
+            .register_type::<Countdown>()
+            .add_systems(OnEnter(InGame), reset_countdown)
+            .add_systems(
+                Update,
+                tick_countdown
+                    .run_if(in_state(GameState::Playing).and(resource_equals(GameMode::Countdown))),
+            );
+    }
+}
+
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+pub enum GameMode {
+    #[default]
+    Levels,
+    Countdown,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 2] = [GameMode::Levels, GameMode::Countdown];
+
+    // Localization key of the menu button starting a game in this mode
+    pub fn label(self) -> &'static str {
+        match self {
+            GameMode::Levels => "menu-play",
+            GameMode::Countdown => "menu-countdown",
+        }
+    }
+}
+
+// Time left in a `GameMode::Countdown` game, in virtual time so pausing stops it
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct Countdown {
+    pub timer: Timer,
+}
+
+impl Default for Countdown {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(COUNTDOWN_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+impl Countdown {
+    // Whole seconds left, as shown in the HUD
+    pub fn seconds_left(&self) -> u32 {
+        self.timer.remaining_secs().ceil() as u32
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.seconds_left() <= WARNING_SECONDS
+    }
+}
+
+fn reset_countdown(mut countdown: ResMut<Countdown>) {
+    *countdown = Countdown::default();
+}
+
+fn tick_countdown(
+    time: Res<Time>,
+    mut countdown: ResMut<Countdown>,
+    mut sounds: EventWriter<PlaySound>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let before = countdown.seconds_left();
+    countdown.timer.tick(time.delta());
+    if countdown.timer.just_finished() {
+        next_state.set(GameState::GameOver);
+        return;
+    }
+    if countdown.seconds_left() < before && countdown.is_warning() {
+        sounds.write(PlaySound::new(SoundId::Tick));
+    }
+}
@@ -0,0 +1,162 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::cat::{Cat, ControlledBy};
+use crate::input::ActionState;
+
+// How close the cat has to get to the point it's steering at before that point moves on
+const LOOKAHEAD_RADIUS: f32 = 48.0;
+
+// Demo mode: after a while without any input the cat strolls along a loop around the window,
+// until the player touches anything
+#[derive(Resource, Clone, Debug)]
+pub struct AttractMode {
+    // Seconds without input before the demo starts
+    pub idle_after: f32,
+    // Fastest the steering point moves along the path, in segments per second. It waits for the
+    // cat when it falls behind.
+    pub speed: f32,
+    pub active: bool,
+    idle_time: f32,
+}
+
+impl Default for AttractMode {
+    fn default() -> Self {
+        Self {
+            idle_after: 20.0,
+            speed: 0.6,
+            active: false,
+            idle_time: 0.0,
+        }
+    }
+}
+
+pub(super) fn reset_attract_mode(mut attract: ResMut<AttractMode>) {
+    attract.active = false;
+    attract.idle_time = 0.0;
+}
+
+// A looping path an entity walks along
+#[derive(Component, Clone, Debug)]
+pub struct SplinePath {
+    pub curve: CubicCurve<Vec2>,
+    // Position along the curve, in segments
+    pub t: f32,
+}
+
+impl SplinePath {
+    // A wobbly loop through `points`, closing back on the first one
+    pub fn looping(points: Vec<Vec2>) -> Option<Self> {
+        let curve = CubicCardinalSpline::new_catmull_rom(points)
+            .to_curve_cyclic()
+            .ok()?;
+        Some(Self { curve, t: 0.0 })
+    }
+
+    pub fn segments(&self) -> f32 {
+        self.curve.segments().len() as f32
+    }
+
+    pub fn position(&self) -> Vec2 {
+        self.curve.position(self.t)
+    }
+
+    pub fn advance(&mut self, amount: f32) {
+        self.t = (self.t + amount).rem_euclid(self.segments());
+    }
+}
+
+// A loop around the middle of the window, squashed into its aspect ratio
+fn attract_path(window: &Window) -> Option<SplinePath> {
+    let radii = window.size() * 0.3;
+    let points = (0..8)
+        .map(|i| {
+            let angle = i as f32 / 8.0 * std::f32::consts::TAU;
+            // Alternate between wide and narrow points so it's not just an ellipse
+            let wobble = if i % 2 == 0 { 1.0 } else { 0.7 };
+            Vec2::from_angle(angle) * radii * wobble
+        })
+        .collect();
+    SplinePath::looping(points)
+}
+
+// Every device the player could be using
+#[derive(SystemParam)]
+pub(super) struct AnyInput<'w, 's> {
+    keyboard_input: Res<'w, ButtonInput<KeyCode>>,
+    mouse: Res<'w, ButtonInput<MouseButton>>,
+    cursor_moved: EventReader<'w, 's, CursorMoved>,
+    touches: Res<'w, Touches>,
+    gamepads: Query<'w, 's, &'static Gamepad>,
+}
+
+impl AnyInput<'_, '_> {
+    // Anything the player does counts, whether or not it's bound to an action
+    fn is_active(&mut self) -> bool {
+        self.keyboard_input.get_pressed().next().is_some()
+            || self.mouse.get_pressed().next().is_some()
+            || self.cursor_moved.read().count() > 0
+            || self.touches.iter().next().is_some()
+            || self.gamepads.iter().any(|gamepad| {
+                gamepad.get_pressed().next().is_some() || gamepad.left_stick() != Vec2::ZERO
+            })
+    }
+}
+
+pub(super) fn detect_activity(
+    mut commands: Commands,
+    mut attract: ResMut<AttractMode>,
+    time: Res<Time<Real>>,
+    mut input: AnyInput,
+    window: Single<&Window>,
+    cat: Single<(Entity, &Transform), With<ControlledBy>>,
+) {
+    let active_input = input.is_active();
+
+    let (entity, transform) = *cat;
+    if active_input {
+        attract.idle_time = 0.0;
+        if attract.active {
+            attract.active = false;
+            commands.entity(entity).remove::<SplinePath>();
+        }
+        return;
+    }
+
+    attract.idle_time += time.delta_secs();
+    if attract.active || attract.idle_time < attract.idle_after {
+        return;
+    }
+    let Some(mut path) = attract_path(&window) else {
+        return;
+    };
+    // Join the loop at the point closest to the cat instead of running across the window
+    let position = transform.translation.truncate();
+    let steps = (path.segments() * 16.0) as usize;
+    path.t = (0..steps)
+        .map(|step| step as f32 / 16.0)
+        .min_by(|a, b| {
+            let da = path.curve.position(*a).distance_squared(position);
+            let db = path.curve.position(*b).distance_squared(position);
+            da.total_cmp(&db)
+        })
+        .unwrap_or_default();
+    attract.active = true;
+    commands.entity(entity).insert(path);
+}
+
+// Steers towards a point a little ahead on the path, like a player would
+pub(super) fn follow_spline(
+    mut actions: ResMut<ActionState>,
+    attract: Res<AttractMode>,
+    time: Res<Time>,
+    mut cats: Query<(&Transform, &mut SplinePath), With<Cat>>,
+) {
+    for (transform, mut path) in &mut cats {
+        let offset = path.position() - transform.translation.truncate();
+        if offset.length() < LOOKAHEAD_RADIUS {
+            path.advance(attract.speed * time.delta_secs());
+        }
+        actions.set_axis(offset.normalize_or_zero());
+    }
+}
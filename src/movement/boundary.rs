@@ -0,0 +1,219 @@
+use bevy::prelude::*;
+
+use super::{Dash, Impulse, Velocity};
+use crate::accessibility::{Accessibility, reduced_motion};
+use crate::animation::SquashStretch;
+use crate::collision::Collider;
+
+// What happens when an entity reaches the edge of the window
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub enum BoundaryBehavior {
+    // Stop at the edge
+    #[default]
+    Clamp,
+    // Leave on one side and come back in on the opposite one
+    Wrap,
+    // Bounce off the edge, keeping the speed
+    Bounce,
+}
+
+// Sent (and triggered on the entity) when it runs into a window edge it stops or bounces at, for
+// bumps, sounds and particles. Pushing on against the edge doesn't send it again.
+#[derive(Event, Clone, Debug)]
+pub struct HitBoundary {
+    pub entity: Entity,
+    // Points back into the window, away from the edge that was hit
+    pub normal: Vec2,
+}
+
+type Confined = (
+    Entity,
+    &'static mut Transform,
+    &'static mut Velocity,
+    Option<&'static Collider>,
+    Option<&'static BoundaryBehavior>,
+    Option<&'static mut Dash>,
+    Option<&'static mut Impulse>,
+);
+
+// Moves everything with a `Velocity` by it, handling the window edges according to each entity's
+// `BoundaryBehavior`. Physics does this instead when the `physics` feature is enabled.
+pub fn confine_to_window(
+    mut commands: Commands,
+    mut hits: EventWriter<HitBoundary>,
+    mut query: Query<Confined>,
+    time: Res<Time>,
+    window: Single<&Window>,
+) {
+    for (entity, mut transform, mut velocity, collider, behavior, dash, impulse) in &mut query {
+        if velocity.0 == Vec2::ZERO {
+            continue;
+        }
+        let new_position = transform.translation.truncate() + velocity.0 * time.delta_secs();
+        let behavior = behavior.copied().unwrap_or_default();
+
+        if behavior == BoundaryBehavior::Wrap {
+            // Only wrap once the entity is completely off screen
+            let half_extents = collider.map_or(Vec2::ZERO, |collider| collider.half_extents);
+            let extent = window.size() / 2.0 + half_extents;
+            let wrapped = (new_position + extent).rem_euclid(extent * 2.0) - extent;
+            transform.translation = wrapped.extend(transform.translation.z);
+            continue;
+        }
+
+        // Clamp position to window boundaries
+        let bounds = window_bounds(&window, collider);
+        let clamped = new_position.clamp(bounds.min, bounds.max);
+        let previous = transform.translation.truncate();
+        transform.translation = clamped.extend(transform.translation.z);
+
+        let hit = clamped.cmpne(new_position);
+        if !hit.any() {
+            continue;
+        }
+        // Only edges the entity wasn't already resting against
+        let arrived = hit & previous.cmpne(clamped);
+        if arrived.any() {
+            let normal = Vec2::select(arrived, -(new_position - clamped).signum(), Vec2::ZERO);
+            let event = HitBoundary { entity, normal };
+            commands.trigger_targets(event.clone(), entity);
+            hits.write(event);
+        }
+        let factor = match behavior {
+            BoundaryBehavior::Bounce => {
+                // Flip the part of the velocity that went into the edge
+                let flip = Vec2::select(hit, Vec2::NEG_ONE, Vec2::ONE);
+                if let Some(mut dash) = dash {
+                    dash.direction *= flip;
+                }
+                flip
+            }
+            _ => {
+                // A dash into the window edge ends there instead of pinning the cat against it
+                if let Some(mut dash) = dash {
+                    dash.cancel();
+                }
+                // Don't keep pushing against the window edge
+                Vec2::select(hit, Vec2::ZERO, Vec2::ONE)
+            }
+        };
+        velocity.0 *= factor;
+        if let Some(mut impulse) = impulse {
+            impulse.scale_applied(factor);
+        }
+    }
+}
+
+// Flattens the entity a little against the edge it ran into
+pub(super) fn squash_on_boundary(
+    trigger: Trigger<HitBoundary>,
+    accessibility: Option<Res<Accessibility>>,
+    mut query: Query<&mut SquashStretch>,
+) {
+    if reduced_motion(accessibility) {
+        return;
+    }
+    let Ok(mut squash) = query.get_mut(trigger.target()) else {
+        return;
+    };
+    // Half the squash of stopping, it's a bump rather than a crash
+    let amount = squash.squash / 2.0;
+    let normal = trigger.event().normal;
+    squash.kick(if normal.x.abs() >= normal.y.abs() {
+        Vec2::new(1.0 - amount, 1.0 + amount)
+    } else {
+        Vec2::new(1.0 + amount, 1.0 - amount)
+    });
+}
+
+type Clamped = (
+    &'static mut Transform,
+    Option<&'static Collider>,
+    Option<&'static BoundaryBehavior>,
+);
+
+// Puts entities back inside the window when it shrinks
+pub fn clamp_to_resized_window(mut query: Query<Clamped, With<Velocity>>, window: Single<&Window>) {
+    for (mut transform, collider, behavior) in &mut query {
+        if behavior == Some(&BoundaryBehavior::Wrap) {
+            continue;
+        }
+        let bounds = window_bounds(&window, collider);
+        let clamped = transform
+            .translation
+            .truncate()
+            .clamp(bounds.min, bounds.max);
+        transform.translation = clamped.extend(transform.translation.z);
+    }
+}
+
+// Where the center of an entity can go while keeping all of its collider (or just the center,
+// without one) on screen
+fn window_bounds(window: &Window, collider: Option<&Collider>) -> Rect {
+    let half_extents = collider.map_or(Vec2::ZERO, |collider| collider.half_extents);
+    let half_size = (window.size() / 2.0 - half_extents).max(Vec2::ZERO);
+    Rect::from_center_half_size(Vec2::ZERO, half_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    // A cat just short of the right edge, about to be shoved into it
+    fn world(behavior: BoundaryBehavior) -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(1.0 / 60.0));
+        world.init_resource::<Events<HitBoundary>>();
+        world.spawn(Window::default());
+        let right = Window::default().width() / 2.0;
+        let mut impulse = Impulse::new(Vec2::new(300.0, 0.0));
+        let mut velocity = Velocity::default();
+        impulse.apply(&mut velocity.0);
+        let cat = world
+            .spawn((
+                Transform::from_xyz(right - 1.0, 0.0, 0.0),
+                velocity,
+                impulse,
+                behavior,
+            ))
+            .id();
+        world.run_system_once(confine_to_window).unwrap();
+        (world, cat)
+    }
+
+    // What's left of the velocity once the impulse is taken back out for the next step
+    fn own_velocity(world: &mut World, cat: Entity) -> Vec2 {
+        let mut entity = world.entity_mut(cat);
+        let mut velocity = entity.get::<Velocity>().unwrap().0;
+        entity
+            .get_mut::<Impulse>()
+            .unwrap()
+            .remove_applied(&mut velocity);
+        velocity
+    }
+
+    #[test]
+    fn an_impulse_into_a_wall_doesnt_bounce_back() {
+        let (mut world, cat) = world(BoundaryBehavior::Clamp);
+        assert_eq!(world.get::<Velocity>(cat).unwrap().0, Vec2::ZERO);
+        assert_eq!(own_velocity(&mut world, cat), Vec2::ZERO);
+    }
+
+    #[test]
+    fn an_impulse_bounced_off_a_wall_comes_back_out_whole() {
+        let (mut world, cat) = world(BoundaryBehavior::Bounce);
+        assert_eq!(
+            world.get::<Velocity>(cat).unwrap().0,
+            Vec2::new(-300.0, 0.0)
+        );
+        assert_eq!(own_velocity(&mut world, cat), Vec2::ZERO);
+    }
+}
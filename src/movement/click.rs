@@ -0,0 +1,105 @@
+use bevy::prelude::*;
+
+use super::arrival_factor;
+use crate::cat::ControlledBy;
+use crate::input::{ActionState, CatAction, cursor_world_position};
+use crate::state::InGame;
+
+// Distance from the destination at which the cat stops and the marker disappears
+const ARRIVE_RADIUS: f32 = 8.0;
+// Distance from the destination at which the cat starts slowing down
+const SLOW_RADIUS: f32 = 64.0;
+const MARKER_SIZE: f32 = 14.0;
+
+// Where an entity is walking to on its own, set by right-clicking
+#[derive(Component, Clone, Copy, Debug)]
+pub struct MoveTarget(pub Vec2);
+
+// The indicator shown at the current `MoveTarget`
+#[derive(Component)]
+pub struct DestinationMarker;
+
+pub(super) fn set_move_target(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    cat: Single<Entity, With<ControlledBy>>,
+    markers: Query<Entity, With<DestinationMarker>>,
+) {
+    if !mouse.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let (camera, camera_transform) = *camera;
+    let Some(destination) = cursor_world_position(&window, camera, camera_transform) else {
+        return;
+    };
+
+    for marker in &markers {
+        commands.entity(marker).despawn();
+    }
+    commands.entity(*cat).insert(MoveTarget(destination));
+    commands.spawn((
+        DestinationMarker,
+        StateScoped(InGame),
+        Name::new("Destination"),
+        Sprite::from_color(Color::srgba(1.0, 1.0, 1.0, 0.6), Vec2::splat(MARKER_SIZE)),
+        // Rotated into a diamond, drawn underneath the cat
+        Transform::from_translation(destination.extend(-1.0))
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+    ));
+}
+
+// Steering input towards the target, slowing down on arrival
+pub(super) fn steer_to_target(
+    mut commands: Commands,
+    mut actions: ResMut<ActionState>,
+    cat: Single<(Entity, &Transform, Option<&MoveTarget>), With<ControlledBy>>,
+    markers: Query<Entity, With<DestinationMarker>>,
+) {
+    let (entity, transform, target) = *cat;
+    let Some(target) = target else {
+        return;
+    };
+    let offset = target.0 - transform.translation.truncate();
+    let distance = offset.length();
+    if distance <= ARRIVE_RADIUS {
+        clear_target(&mut commands, entity, &markers);
+        return;
+    }
+    // Slow down towards the destination itself so there's still some speed left at the radius
+    actions.set_axis(offset / distance * arrival_factor(distance, 0.0, SLOW_RADIUS));
+}
+
+// Taking over with the keyboard or gamepad, or switching to another cat, forgets about the
+// destination
+pub(super) fn cancel_move_target(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    cat: Single<(Entity, Has<ControlledBy>), With<MoveTarget>>,
+    markers: Query<Entity, With<DestinationMarker>>,
+) {
+    let steering = [
+        CatAction::MoveUp,
+        CatAction::MoveDown,
+        CatAction::MoveLeft,
+        CatAction::MoveRight,
+    ]
+    .into_iter()
+    .any(|action| actions.pressed(action));
+    let (entity, controlled) = *cat;
+    if steering || !controlled {
+        clear_target(&mut commands, entity, &markers);
+    }
+}
+
+fn clear_target(
+    commands: &mut Commands,
+    entity: Entity,
+    markers: &Query<Entity, With<DestinationMarker>>,
+) {
+    commands.entity(entity).remove::<MoveTarget>();
+    for marker in markers {
+        commands.entity(marker).despawn();
+    }
+}
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+
+use super::{InterpolatedTranslation, MovementConfig, Velocity};
+use crate::cat::ControlledBy;
+use crate::console::{ConsoleOutput, parse_arg};
+
+// speed <value>: both axes, or speed <horizontal> <vertical>
+pub(super) fn set_speed(
+    In(args): In<Vec<String>>,
+    mut config: ResMut<MovementConfig>,
+) -> ConsoleOutput {
+    let horizontal = parse_speed(&args, 0, "speed")?;
+    let vertical = if args.len() > 1 {
+        parse_speed(&args, 1, "vertical speed")?
+    } else {
+        horizontal
+    };
+    config.horizontal_speed = horizontal;
+    config.vertical_speed = vertical;
+    Ok(format!("speed set to {horizontal} x {vertical}"))
+}
+
+// tp <x> <y>: moves the controlled cat, without sliding there
+pub(super) fn teleport(
+    In(args): In<Vec<String>>,
+    mut cat: Query<
+        (&mut Transform, &mut InterpolatedTranslation, &mut Velocity),
+        With<ControlledBy>,
+    >,
+) -> ConsoleOutput {
+    let x = parse_finite(&args, 0, "x")?;
+    let y = parse_finite(&args, 1, "y")?;
+    let (mut transform, mut interpolated, mut velocity) = cat
+        .single_mut()
+        .map_err(|_| "there's no cat to move".to_owned())?;
+    transform.translation.x = x;
+    transform.translation.y = y;
+    interpolated.snap_to(transform.translation);
+    *velocity = Velocity::default();
+    Ok(format!("moved to {x}, {y}"))
+}
+
+// `f32` also parses "NaN" and "inf", which would stick in the cat's transform or, through the saved
+// movement config, outlive a restart
+fn parse_finite(args: &[String], index: usize, name: &str) -> Result<f32, String> {
+    let value: f32 = parse_arg(args, index, name)?;
+    if !value.is_finite() {
+        return Err(format!("{name} has to be a number, not {value}"));
+    }
+    Ok(value)
+}
+
+fn parse_speed(args: &[String], index: usize, name: &str) -> Result<f32, String> {
+    let speed = parse_finite(args, index, name)?;
+    if speed < 0.0 {
+        return Err(format!("{name} can't be negative"));
+    }
+    Ok(speed)
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| (*arg).to_owned()).collect()
+    }
+
+    fn speed(world: &mut World, speed: &[&str]) -> ConsoleOutput {
+        world.run_system_once_with(set_speed, args(speed)).unwrap()
+    }
+
+    fn speed_world() -> World {
+        let mut world = World::new();
+        world.init_resource::<MovementConfig>();
+        world
+    }
+
+    #[test]
+    fn speed_sets_both_axes() {
+        let mut world = speed_world();
+        assert!(speed(&mut world, &["150"]).is_ok());
+        let config = world.resource::<MovementConfig>();
+        assert_eq!(
+            (config.horizontal_speed, config.vertical_speed),
+            (150.0, 150.0)
+        );
+    }
+
+    #[test]
+    fn speed_rejects_numbers_that_are_not_finite() {
+        let mut world = speed_world();
+        let default = MovementConfig::default();
+        for value in ["NaN", "inf", "-inf"] {
+            assert!(speed(&mut world, &[value]).is_err(), "speed {value}");
+            assert!(
+                speed(&mut world, &["100", value]).is_err(),
+                "speed 100 {value}"
+            );
+        }
+        let config = world.resource::<MovementConfig>();
+        assert_eq!(config.horizontal_speed, default.horizontal_speed);
+        assert_eq!(config.vertical_speed, default.vertical_speed);
+    }
+
+    #[test]
+    fn speed_rejects_negative_speeds() {
+        let mut world = speed_world();
+        assert!(speed(&mut world, &["-50"]).is_err());
+        assert!(speed(&mut world, &["50", "-50"]).is_err());
+        let config = world.resource::<MovementConfig>();
+        assert_eq!(
+            config.horizontal_speed,
+            MovementConfig::default().horizontal_speed
+        );
+    }
+
+    #[test]
+    fn teleport_rejects_numbers_that_are_not_finite() {
+        let mut world = World::new();
+        let cat = world
+            .spawn((
+                Transform::default(),
+                InterpolatedTranslation::default(),
+                Velocity::default(),
+                ControlledBy,
+            ))
+            .id();
+        for tp in [["NaN", "0"], ["0", "inf"]] {
+            let output = world.run_system_once_with(teleport, args(&tp)).unwrap();
+            assert!(output.is_err(), "tp {tp:?}");
+        }
+        assert_eq!(world.get::<Transform>(cat).unwrap().translation, Vec3::ZERO);
+        let output = world.run_system_once_with(teleport, args(&["3", "-4"]));
+        assert!(output.unwrap().is_ok());
+        assert_eq!(
+            world.get::<Transform>(cat).unwrap().translation,
+            Vec3::new(3.0, -4.0, 0.0)
+        );
+    }
+}
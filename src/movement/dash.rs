@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+
+use super::ControlledAndUnlocked;
+use crate::accessibility::{Accessibility, reduced_motion};
+use crate::animation::{Facing, SquashStretch};
+use crate::input::{ActionState, CatAction};
+
+// Two presses of the same direction within this many seconds dash that way
+const DOUBLE_TAP_WINDOW: f32 = 0.25;
+
+// A short burst of speed in a fixed direction, overriding regular movement while it lasts
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Dash {
+    pub speed: f32,
+    // Seconds a dash lasts
+    pub duration: f32,
+    pub direction: Vec2,
+    remaining: f32,
+}
+
+impl Default for Dash {
+    fn default() -> Self {
+        Self {
+            speed: 900.0,
+            duration: 0.18,
+            direction: Vec2::ZERO,
+            remaining: 0.0,
+        }
+    }
+}
+
+impl Dash {
+    pub fn is_active(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    pub fn start(&mut self, direction: Vec2) {
+        self.direction = direction.normalize_or(Vec2::X);
+        self.remaining = self.duration;
+    }
+
+    // Cuts the dash short, e.g. when running into something
+    pub fn cancel(&mut self) {
+        self.remaining = 0.0;
+    }
+
+    pub(super) fn tick(&mut self, delta: f32) {
+        self.remaining = (self.remaining - delta).max(0.0);
+    }
+
+    pub fn velocity(&self) -> Vec2 {
+        self.direction * self.speed
+    }
+}
+
+// Time until an ability can be used again
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Cooldown(pub Timer);
+
+impl Cooldown {
+    pub fn from_seconds(seconds: f32) -> Self {
+        let mut timer = Timer::from_seconds(seconds, TimerMode::Once);
+        // Ready right away
+        timer.tick(timer.duration());
+        Self(timer)
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.0.finished()
+    }
+}
+
+impl Default for Cooldown {
+    fn default() -> Self {
+        Self::from_seconds(0.6)
+    }
+}
+
+// Sent (and triggered on the entity) when a dash begins, for trails, sounds and animations
+#[derive(Event, Clone, Debug)]
+pub struct DashStarted {
+    pub entity: Entity,
+    pub direction: Vec2,
+}
+
+pub(super) fn tick_cooldowns(time: Res<Time>, mut query: Query<&mut Cooldown>) {
+    for mut cooldown in &mut query {
+        cooldown.0.tick(time.delta());
+    }
+}
+
+pub(super) fn start_dash(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    time: Res<Time>,
+    mut last_tap: Local<Option<(CatAction, f32)>>,
+    mut started: EventWriter<DashStarted>,
+    cat: Single<(Entity, &mut Dash, &mut Cooldown, &Facing), ControlledAndUnlocked>,
+) {
+    let now = time.elapsed_secs();
+    let mut double_tapped = false;
+    for action in [
+        CatAction::MoveUp,
+        CatAction::MoveDown,
+        CatAction::MoveLeft,
+        CatAction::MoveRight,
+    ] {
+        if !actions.just_pressed(action) {
+            continue;
+        }
+        double_tapped |=
+            last_tap.is_some_and(|(last, at)| last == action && now - at <= DOUBLE_TAP_WINDOW);
+        *last_tap = Some((action, now));
+    }
+
+    let (entity, mut dash, mut cooldown, facing) = cat.into_inner();
+    if !(double_tapped || actions.just_pressed(CatAction::Dash))
+        || dash.is_active()
+        || !cooldown.is_ready()
+    {
+        return;
+    }
+    // Dash where the player is steering, or straight ahead when standing still
+    let direction = Some(actions.movement())
+        .filter(|direction| *direction != Vec2::ZERO)
+        .unwrap_or_else(|| facing.to_vec2());
+    dash.start(direction);
+    cooldown.0.reset();
+    *last_tap = None;
+
+    let event = DashStarted {
+        entity,
+        direction: dash.direction,
+    };
+    commands.trigger_targets(event.clone(), entity);
+    started.write(event);
+}
+
+// Stretches the entity along the dash, the default trail of a dash
+pub(super) fn stretch_on_dash(
+    trigger: Trigger<DashStarted>,
+    accessibility: Option<Res<Accessibility>>,
+    mut query: Query<&mut SquashStretch>,
+) {
+    if reduced_motion(accessibility) {
+        return;
+    }
+    if let Ok(mut squash) = query.get_mut(trigger.target()) {
+        squash.stretch_along(trigger.event().direction);
+    }
+}
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use super::arrival_factor;
+use crate::cat::ControlledBy;
+use crate::input::{ActionState, CatAction, cursor_world_position};
+
+// Steers the cat towards the mouse cursor while enabled, keyboard and gamepad input still win
+#[derive(Resource, Clone, Debug)]
+pub struct MouseFollow {
+    pub enabled: bool,
+    // Radians per second the heading can turn by
+    pub turn_rate: f32,
+    // Distance from the cursor at which the cat stops
+    pub stop_radius: f32,
+    // Distance from the cursor at which the cat starts slowing down
+    pub slow_radius: f32,
+    heading: Vec2,
+}
+
+impl Default for MouseFollow {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            turn_rate: std::f32::consts::TAU,
+            stop_radius: 16.0,
+            slow_radius: 96.0,
+            heading: Vec2::ZERO,
+        }
+    }
+}
+
+pub(super) fn reset_heading(mut follow: ResMut<MouseFollow>) {
+    follow.heading = Vec2::ZERO;
+}
+
+pub(super) fn toggle_mouse_follow(actions: Res<ActionState>, mut follow: ResMut<MouseFollow>) {
+    if actions.just_pressed(CatAction::FollowMouse) {
+        follow.enabled = !follow.enabled;
+        follow.heading = Vec2::ZERO;
+    }
+}
+
+pub(super) fn follow_mouse(
+    mut actions: ResMut<ActionState>,
+    mut follow: ResMut<MouseFollow>,
+    time: Res<Time>,
+    window: Single<&Window>,
+    camera: Single<(&Camera, &GlobalTransform), With<Camera2d>>,
+    cat: Single<&Transform, With<ControlledBy>>,
+) {
+    let (camera, camera_transform) = *camera;
+    let Some(cursor) = cursor_world_position(&window, camera, camera_transform) else {
+        return;
+    };
+
+    let offset = cursor - cat.translation.truncate();
+    let distance = offset.length();
+    if distance <= follow.stop_radius {
+        follow.heading = Vec2::ZERO;
+        return;
+    }
+
+    // Turn gradually instead of snapping towards the cursor
+    let desired = offset / distance;
+    let max_turn = follow.turn_rate * time.delta_secs();
+    follow.heading = if follow.heading == Vec2::ZERO {
+        desired
+    } else {
+        follow.heading.rotate_towards(desired, max_turn)
+    };
+
+    let arrival = arrival_factor(distance, follow.stop_radius, follow.slow_radius);
+    actions.set_axis(follow.heading * arrival);
+}
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+
+// Below this speed (pixels per second) an impulse has worn off
+const MIN_IMPULSE_SPEED: f32 = 1.0;
+
+// A shove from outside (knockback, explosions, ...) added on top of the velocity the player asks
+// for and fading out over time. Insert it, or `add` to an existing one to stack shoves.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Impulse {
+    pub velocity: Vec2,
+    // Fraction of the impulse that's lost per second, exponentially
+    pub decay: f32,
+    // The part of the entity's `Velocity` that came from this impulse last frame
+    applied: Vec2,
+}
+
+impl Impulse {
+    pub fn new(velocity: Vec2) -> Self {
+        Self {
+            velocity,
+            decay: 6.0,
+            applied: Vec2::ZERO,
+        }
+    }
+
+    pub fn with_decay(mut self, decay: f32) -> Self {
+        self.decay = decay;
+        self
+    }
+
+    pub fn add(&mut self, velocity: Vec2) {
+        self.velocity += velocity;
+    }
+
+    pub(super) fn apply(&mut self, velocity: &mut Vec2) {
+        *velocity += self.velocity;
+        self.applied = self.velocity;
+    }
+
+    // The entity's velocity was scaled by `factor` per axis, stopped or flipped at a window edge.
+    // Last frame's contribution went the same way, so taking it back out doesn't push the entity
+    // off the edge on its own.
+    pub(super) fn scale_applied(&mut self, factor: Vec2) {
+        self.applied *= factor;
+    }
+
+    // Takes last frame's contribution back out, so the player's own velocity can be updated
+    pub(super) fn remove_applied(&mut self, velocity: &mut Vec2) {
+        *velocity -= self.applied;
+        self.applied = Vec2::ZERO;
+    }
+}
+
+pub(super) fn decay_impulses(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Impulse)>,
+) {
+    for (entity, mut impulse) in &mut query {
+        if impulse.velocity == Vec2::ZERO && impulse.applied == Vec2::ZERO {
+            commands.entity(entity).remove::<Impulse>();
+            continue;
+        }
+        let factor = (-impulse.decay * time.delta_secs()).exp();
+        impulse.velocity *= factor;
+        if impulse.velocity.length() < MIN_IMPULSE_SPEED {
+            impulse.velocity = Vec2::ZERO;
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+// A jump further than this between two fixed steps is a teleport (e.g. wrapping around the
+// window), which is snapped to instead of sliding across the screen
+const SNAP_DISTANCE: f32 = 200.0;
+
+// Movement only changes translations in `FixedUpdate`. Rendering in between shows the entity part
+// of the way from its previous to its current fixed-step position, so motion stays smooth when
+// the frame rate and the tick rate don't line up.
+pub(super) struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_observer(start_interpolating)
+            .add_systems(FixedFirst, restore_translation)
+            .add_systems(FixedLast, record_translation)
+            .add_systems(
+                PostUpdate,
+                interpolate_translation.before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+// Translations at the last two fixed steps
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct InterpolatedTranslation {
+    previous: Vec3,
+    current: Vec3,
+}
+
+impl InterpolatedTranslation {
+    // Jumps straight to `translation` instead of sliding there over the next step
+    pub fn snap_to(&mut self, translation: Vec3) {
+        self.previous = translation;
+        self.current = translation;
+    }
+}
+
+fn start_interpolating(
+    trigger: Trigger<OnAdd, InterpolatedTranslation>,
+    mut query: Query<(&Transform, &mut InterpolatedTranslation)>,
+) {
+    if let Ok((transform, mut interpolated)) = query.get_mut(trigger.target()) {
+        interpolated.previous = transform.translation;
+        interpolated.current = transform.translation;
+    }
+}
+
+// Undo the interpolation so the fixed step starts from where the entity really is
+fn restore_translation(mut query: Query<(&mut Transform, &mut InterpolatedTranslation)>) {
+    for (mut transform, mut interpolated) in &mut query {
+        transform.translation = interpolated.current;
+        interpolated.previous = interpolated.current;
+    }
+}
+
+fn record_translation(mut query: Query<(&Transform, &mut InterpolatedTranslation)>) {
+    for (transform, mut interpolated) in &mut query {
+        interpolated.current = transform.translation;
+        if interpolated.previous.distance(interpolated.current) > SNAP_DISTANCE {
+            interpolated.previous = interpolated.current;
+        }
+    }
+}
+
+pub fn interpolate_translation(
+    time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &InterpolatedTranslation)>,
+) {
+    let fraction = time.overstep_fraction();
+    for (mut transform, interpolated) in &mut query {
+        transform.translation = interpolated.previous.lerp(interpolated.current, fraction);
+    }
+}
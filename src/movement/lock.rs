@@ -0,0 +1,42 @@
+use bevy::prelude::*;
+
+use crate::cat::ControlledBy;
+
+// Ignores the player's movement input for an entity, during cutscenes, menus or hit-stun.
+// Impulses and gravity still apply, so a locked cat can be knocked back.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct MovementLock {
+    // Lifted automatically once this runs out, held until removed without one
+    pub timeout: Option<Timer>,
+}
+
+// The cat the player controls, while it's free to act on their input
+pub type ControlledAndUnlocked = (With<ControlledBy>, Without<MovementLock>);
+
+impl MovementLock {
+    pub fn until_removed() -> Self {
+        Self { timeout: None }
+    }
+
+    pub fn for_seconds(seconds: f32) -> Self {
+        Self {
+            timeout: Some(Timer::from_seconds(seconds, TimerMode::Once)),
+        }
+    }
+}
+
+pub(super) fn tick_movement_locks(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut MovementLock)>,
+) {
+    for (entity, mut lock) in &mut query {
+        let Some(timeout) = &mut lock.timeout else {
+            continue;
+        };
+        if timeout.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<MovementLock>();
+        }
+    }
+}
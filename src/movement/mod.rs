@@ -0,0 +1,494 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::window::WindowResized;
+use serde::{Deserialize, Serialize};
+
+use crate::animation::{AnimationSystems, Facing};
+use crate::cat::{Cat, ControlledBy};
+use crate::collision::resolve_collisions;
+use crate::config;
+use crate::console::AddConsoleCommandExt;
+use crate::input::{ActionState, ActionSystems};
+use crate::state::{GameState, InGame};
+
+mod attract;
+mod boundary;
+mod click;
+mod console;
+mod dash;
+mod follow;
+mod impulse;
+mod interpolation;
+mod lock;
+mod modifier;
+mod platformer;
+mod sprint;
+mod surface;
+
+pub use attract::{AttractMode, SplinePath};
+pub use boundary::{BoundaryBehavior, HitBoundary, clamp_to_resized_window, confine_to_window};
+pub use click::{DestinationMarker, MoveTarget};
+pub use dash::{Cooldown, Dash, DashStarted};
+pub use follow::MouseFollow;
+pub use impulse::Impulse;
+pub use interpolation::{InterpolatedTranslation, interpolate_translation};
+pub use lock::{ControlledAndUnlocked, MovementLock};
+pub use modifier::{SpeedModifier, SpeedModifiers};
+pub use platformer::{Ground, Jumper, MovementMode, PlatformerSettings, is_platformer};
+pub use sprint::{Sprint, Stamina};
+pub use surface::{Surface, surface_zone};
+
+// Default speed in both directions, see `MovementConfig`
+pub const CAT_SPEED: f32 = 250.0;
+
+const MOVEMENT_CONFIG_FILE: &str = "movement.ron";
+
+pub struct MovementPlugin;
+
+impl Plugin for MovementPlugin {
+    fn build(&self, app: &mut App) {
+        let movement_config = config::load_or_default::<MovementConfig>(MOVEMENT_CONFIG_FILE);
+        app.insert_resource(Time::<Fixed>::from_duration(movement_config.timestep()))
+            .insert_resource(movement_config)
+            .register_type::<MovementConfig>()
+            .register_type::<MovementMode>()
+            .register_type::<PlatformerSettings>()
+            .register_type::<Velocity>()
+            .register_type::<MovementSettings>()
+            .register_type::<Dash>()
+            .register_type::<Cooldown>()
+            .register_type::<Sprint>()
+            .register_type::<Stamina>()
+            .register_type::<Jumper>()
+            .register_type::<MovementLock>()
+            .register_type::<Impulse>()
+            .register_type::<SpeedModifiers>()
+            .register_type::<BoundaryBehavior>()
+            .register_type::<Surface>()
+            .init_resource::<MouseFollow>()
+            .init_resource::<AttractMode>()
+            .init_resource::<MovementMode>()
+            .init_resource::<PlatformerSettings>()
+            .add_event::<DashStarted>()
+            .add_observer(dash::stretch_on_dash)
+            .add_event::<HitBoundary>()
+            .add_observer(boundary::squash_on_boundary)
+            .add_plugins(interpolation::InterpolationPlugin)
+            .add_console_command(
+                "speed",
+                "speed <value> [vertical]: set the movement speed",
+                console::set_speed,
+            )
+            .add_console_command(
+                "tp",
+                "tp <x> <y>: move the controlled cat",
+                console::teleport,
+            )
+            .add_systems(Startup, (platformer::spawn_ground, surface::spawn_surfaces))
+            // A new game starts out of attract mode and without any leftover steering
+            .add_systems(
+                OnEnter(InGame),
+                (attract::reset_attract_mode, follow::reset_heading),
+            )
+            .add_systems(
+                PreUpdate,
+                attract::detect_activity
+                    .in_set(ActionSystems::Clear)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    follow::follow_mouse.run_if(|follow: Res<MouseFollow>| follow.enabled),
+                    click::steer_to_target,
+                    attract::follow_spline,
+                )
+                    .in_set(ActionSystems::Collect)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            // Reacting to input happens every frame, so no press is missed or seen twice...
+            .add_systems(
+                Update,
+                (
+                    platformer::toggle_movement_mode,
+                    follow::toggle_mouse_follow,
+                    click::set_move_target,
+                    click::cancel_move_target,
+                    dash::tick_cooldowns,
+                    dash::start_dash,
+                    platformer::buffer_jump,
+                    sprint::update_sprint,
+                    modifier::collect_speed_modifiers,
+                )
+                    .chain()
+                    .in_set(MovementSystems::Input)
+                    .before(AnimationSystems::SelectClip),
+            )
+            .add_systems(
+                Update,
+                (
+                    apply_tick_rate.run_if(resource_changed::<MovementConfig>),
+                    platformer::resize_ground.run_if(on_event::<WindowResized>),
+                ),
+            )
+            // ...while moving happens at a fixed rate, so it plays the same at any frame rate
+            .configure_sets(
+                FixedUpdate,
+                (
+                    MovementSystems::Move,
+                    MovementSystems::Confine,
+                    MovementSystems::Collide,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .configure_sets(
+                Update,
+                MovementSystems::Input.run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    lock::tick_movement_locks,
+                    impulse::decay_impulses,
+                    modifier::tick_speed_modifiers,
+                    move_cat,
+                    platformer::apply_gravity_and_jump.run_if(is_platformer),
+                )
+                    .chain()
+                    .in_set(MovementSystems::Move),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    clamp_to_resized_window.run_if(on_event::<WindowResized>),
+                    platformer::land_on_ground.run_if(is_platformer),
+                )
+                    .chain()
+                    .in_set(MovementSystems::Confine),
+            )
+            .add_systems(
+                FixedUpdate,
+                surface::track_surfaces
+                    .after(resolve_collisions)
+                    .in_set(MovementSystems::Collide),
+            )
+            .add_systems(
+                Last,
+                save_movement_config.run_if(
+                    resource_changed::<MovementConfig>.and(not(resource_added::<MovementConfig>)),
+                ),
+            );
+
+        #[cfg(not(feature = "physics"))]
+        app.add_systems(
+            FixedUpdate,
+            confine_to_window
+                .before(clamp_to_resized_window)
+                .in_set(MovementSystems::Confine),
+        );
+    }
+}
+
+// Gameplay that moves entities. Input is handled in `Update`, the rest in `FixedUpdate`.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MovementSystems {
+    // React to the controlled cat's input
+    Input,
+    // Decide velocities
+    Move,
+    // Apply them, within the window
+    Confine,
+    // Resolve and report overlaps
+    Collide,
+}
+
+#[derive(Component, Default, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Velocity(pub Vec2);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+pub enum DiagonalMovement {
+    // Same speed in every direction
+    #[default]
+    Normalized,
+    // Each axis moves at its full speed, so diagonals are faster
+    Independent,
+}
+
+// Top walking speeds in pixels per second, tweakable at runtime and saved to config/movement.ron
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct MovementConfig {
+    pub horizontal_speed: f32,
+    pub vertical_speed: f32,
+    pub diagonal: DiagonalMovement,
+    // Gameplay updates per second
+    pub tick_rate: f64,
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            horizontal_speed: CAT_SPEED,
+            vertical_speed: CAT_SPEED,
+            diagonal: DiagonalMovement::Normalized,
+            tick_rate: 64.0,
+        }
+    }
+}
+
+impl MovementConfig {
+    // Velocity for a `direction` of at most unit length
+    pub fn velocity(&self, direction: Vec2) -> Vec2 {
+        let direction = match self.diagonal {
+            DiagonalMovement::Normalized => direction,
+            DiagonalMovement::Independent => {
+                // Stretch the unit circle onto the unit square
+                let longest = direction.abs().max_element();
+                if longest > 0.0 {
+                    direction * direction.length() / longest
+                } else {
+                    direction
+                }
+            }
+        };
+        direction * Vec2::new(self.horizontal_speed, self.vertical_speed)
+    }
+
+    // Time between fixed updates. The file is edited by hand, so a tick rate `Time<Fixed>` can't
+    // run at (anything but a positive number of updates per second) falls back to the default.
+    pub fn timestep(&self) -> Duration {
+        let timestep = Duration::try_from_secs_f64(self.tick_rate.recip()).unwrap_or_default();
+        if self.tick_rate.is_finite() && self.tick_rate > 0.0 && !timestep.is_zero() {
+            return timestep;
+        }
+        let fallback = Self::default().tick_rate;
+        warn!(
+            "{MOVEMENT_CONFIG_FILE}: invalid tick rate {}, using {fallback}",
+            self.tick_rate
+        );
+        Duration::from_secs_f64(fallback.recip())
+    }
+
+    // Speeds clearly above walking play the run animation
+    pub fn run_threshold(&self) -> f32 {
+        self.horizontal_speed.max(self.vertical_speed) * 1.25
+    }
+}
+
+// How quickly an entity picks up and loses speed, in pixels per second squared
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct MovementSettings {
+    pub acceleration: f32,
+    // Deceleration applied once there is no more input
+    pub friction: f32,
+    // The surfaces currently walked on, all of which apply
+    pub surfaces: Vec<Surface>,
+}
+
+impl Default for MovementSettings {
+    fn default() -> Self {
+        Self {
+            acceleration: 2000.0,
+            friction: 1500.0,
+            surfaces: Vec::new(),
+        }
+    }
+}
+
+impl MovementSettings {
+    pub fn acceleration(&self) -> f32 {
+        self.surfaces
+            .iter()
+            .fold(self.acceleration, |value, surface| {
+                value * surface.acceleration
+            })
+    }
+
+    pub fn friction(&self) -> f32 {
+        self.surfaces
+            .iter()
+            .fold(self.friction, |value, surface| value * surface.friction)
+    }
+
+    pub fn speed_factor(&self) -> f32 {
+        self.surfaces
+            .iter()
+            .fold(1.0, |value, surface| value * surface.speed)
+    }
+}
+
+type MoverItem<'a> = (
+    &'a mut Facing,
+    &'a mut Velocity,
+    &'a MovementSettings,
+    Option<&'a mut Dash>,
+    Option<&'a Sprint>,
+    Option<&'a mut Impulse>,
+    Option<&'a SpeedModifiers>,
+    Has<MovementLock>,
+    Has<ControlledBy>,
+);
+
+pub fn move_cat(
+    actions: Res<ActionState>,
+    movement_config: Res<MovementConfig>,
+    mode: Res<MovementMode>,
+    mut cats: Query<MoverItem, With<Cat>>,
+    time: Res<Time>,
+) {
+    for (
+        mut facing,
+        mut velocity,
+        settings,
+        dash,
+        sprint,
+        mut impulse,
+        modifiers,
+        locked,
+        controlled,
+    ) in &mut cats
+    {
+        // Work on the player's own velocity, the impulse is added back on top at the end
+        if let Some(impulse) = &mut impulse {
+            impulse.remove_applied(&mut velocity.0);
+        }
+        // A locked cat, or one the player isn't controlling, coasts to a halt as if nothing
+        // was pressed
+        let mut direction = if locked || !controlled {
+            Vec2::ZERO
+        } else {
+            actions.movement()
+        };
+        let falling_speed = velocity.0.y;
+        if *mode == MovementMode::Platformer {
+            // Only walk sideways, gravity and jumping take care of the rest
+            direction = Vec2::new(direction.x, 0.0).normalize_or_zero() * direction.length();
+        }
+
+        if let Some(mut dash) = dash.filter(|dash| dash.is_active()) {
+            velocity.0 = dash.velocity();
+            dash.tick(time.delta_secs());
+        } else {
+            // Ease towards the requested velocity, or coast to a halt without input
+            let speed_factor = sprint.map_or(1.0, Sprint::speed_factor)
+                * settings.speed_factor()
+                * modifiers.map_or(1.0, SpeedModifiers::multiplier);
+            let (target, rate) = if direction != Vec2::ZERO {
+                (
+                    movement_config.velocity(direction) * speed_factor,
+                    settings.acceleration(),
+                )
+            } else {
+                (Vec2::ZERO, settings.friction())
+            };
+            velocity.0 = velocity.0.move_towards(target, rate * time.delta_secs());
+            if *mode == MovementMode::Platformer {
+                velocity.0.y = falling_speed;
+            }
+        }
+
+        if let Some(impulse) = &mut impulse {
+            impulse.apply(&mut velocity.0);
+        }
+
+        if let Some(new_facing) = Facing::from_direction(direction) {
+            facing.set_if_neq(new_facing);
+        }
+    }
+}
+
+fn apply_tick_rate(movement_config: Res<MovementConfig>, mut time: ResMut<Time<Fixed>>) {
+    time.set_timestep(movement_config.timestep());
+}
+
+fn save_movement_config(movement_config: Res<MovementConfig>) {
+    config::save_or_warn(MOVEMENT_CONFIG_FILE, &*movement_config);
+}
+
+// Scales speed down from 1 at `slow_radius` to 0 at `stop_radius` away from a destination
+fn arrival_factor(distance: f32, stop_radius: f32, slow_radius: f32) -> f32 {
+    ((distance - stop_radius) / (slow_radius - stop_radius).max(f32::EPSILON)).clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn movement_config(diagonal: DiagonalMovement) -> MovementConfig {
+        MovementConfig {
+            horizontal_speed: 200.0,
+            vertical_speed: 100.0,
+            diagonal,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn velocity_scales_each_axis_by_its_speed() {
+        let config = movement_config(DiagonalMovement::Normalized);
+        assert_eq!(config.velocity(Vec2::X), Vec2::new(200.0, 0.0));
+        assert_eq!(config.velocity(Vec2::NEG_Y), Vec2::new(0.0, -100.0));
+        assert_eq!(config.velocity(Vec2::ZERO), Vec2::ZERO);
+    }
+
+    #[test]
+    fn velocity_keeps_normalized_diagonals() {
+        let config = movement_config(DiagonalMovement::Normalized);
+        let diagonal = Vec2::ONE.normalize();
+        assert_eq!(
+            config.velocity(diagonal),
+            diagonal * Vec2::new(200.0, 100.0)
+        );
+    }
+
+    #[test]
+    fn velocity_gives_independent_diagonals_full_speed_on_both_axes() {
+        let config = movement_config(DiagonalMovement::Independent);
+        let velocity = config.velocity(Vec2::new(1.0, -1.0).normalize());
+        assert!(velocity.abs_diff_eq(Vec2::new(200.0, -100.0), 1e-3));
+        // Straight lines are left as they are
+        assert_eq!(config.velocity(Vec2::Y), Vec2::new(0.0, 100.0));
+    }
+
+    #[test]
+    fn timestep_follows_the_tick_rate() {
+        let config = MovementConfig {
+            tick_rate: 50.0,
+            ..Default::default()
+        };
+        assert_eq!(config.timestep(), Duration::from_millis(20));
+    }
+
+    #[test]
+    fn timestep_falls_back_to_the_default_tick_rate() {
+        let fallback = Duration::from_secs_f64(MovementConfig::default().tick_rate.recip());
+        // Zero, negative, not a number, and too slow or too fast for a `Duration`
+        for tick_rate in [0.0, -64.0, f64::NAN, f64::INFINITY, 1e-300, 1e300] {
+            let config = MovementConfig {
+                tick_rate,
+                ..Default::default()
+            };
+            assert_eq!(config.timestep(), fallback, "tick rate {tick_rate}");
+        }
+    }
+
+    #[test]
+    fn arrival_factor_slows_down_between_the_radii() {
+        assert_eq!(arrival_factor(100.0, 10.0, 50.0), 1.0);
+        assert_eq!(arrival_factor(50.0, 10.0, 50.0), 1.0);
+        assert_eq!(arrival_factor(30.0, 10.0, 50.0), 0.5);
+        assert_eq!(arrival_factor(10.0, 10.0, 50.0), 0.0);
+        assert_eq!(arrival_factor(0.0, 10.0, 50.0), 0.0);
+    }
+
+    #[test]
+    fn arrival_factor_stops_dead_without_a_slow_radius() {
+        assert_eq!(arrival_factor(11.0, 10.0, 10.0), 1.0);
+        assert_eq!(arrival_factor(10.0, 10.0, 10.0), 0.0);
+    }
+}
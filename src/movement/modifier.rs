@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+// Scales an entity's speed for a while, e.g. from a power-up (> 1) or a debuff (< 1). Inserting
+// one adds it to the entity's `SpeedModifiers`, so several can be active at once.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct SpeedModifier {
+    pub multiplier: f32,
+    pub duration: Timer,
+    // Modifiers from the same source don't stack: a new one makes the active one last longer
+    pub source: Option<String>,
+}
+
+impl SpeedModifier {
+    // Pickups and config files feed `seconds`, so one that isn't a duration ends right away
+    // instead of panicking
+    pub fn new(multiplier: f32, seconds: f32) -> Self {
+        let duration = Duration::try_from_secs_f32(seconds).unwrap_or_else(|_| {
+            warn!("a speed modifier can't last {seconds} seconds");
+            Duration::ZERO
+        });
+        Self {
+            multiplier,
+            duration: Timer::new(duration, TimerMode::Once),
+            source: None,
+        }
+    }
+
+    pub fn with_source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn extend(&mut self, seconds: f32) {
+        let Ok(extra) = Duration::try_from_secs_f32(seconds) else {
+            warn!("can't extend a speed modifier by {seconds} seconds");
+            return;
+        };
+        let duration = self.duration.duration() + extra;
+        self.duration.set_duration(duration);
+    }
+}
+
+// Every speed modifier currently affecting an entity
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct SpeedModifiers(pub Vec<SpeedModifier>);
+
+impl SpeedModifiers {
+    pub fn add(&mut self, modifier: SpeedModifier) {
+        let same_source = self
+            .0
+            .iter_mut()
+            .find(|active| active.source.is_some() && active.source == modifier.source);
+        match same_source {
+            Some(active) => active.extend(modifier.duration.remaining_secs()),
+            None => self.0.push(modifier),
+        }
+    }
+
+    pub fn multiplier(&self) -> f32 {
+        self.0
+            .iter()
+            .fold(1.0, |value, modifier| value * modifier.multiplier)
+    }
+}
+
+pub(super) fn collect_speed_modifiers(
+    mut commands: Commands,
+    mut query: Query<(Entity, &SpeedModifier, Option<&mut SpeedModifiers>)>,
+) {
+    for (entity, modifier, stack) in &mut query {
+        match stack {
+            Some(mut stack) => stack.add(modifier.clone()),
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(SpeedModifiers(vec![modifier.clone()]));
+            }
+        }
+        commands.entity(entity).remove::<SpeedModifier>();
+    }
+}
+
+pub(super) fn tick_speed_modifiers(time: Res<Time>, mut query: Query<&mut SpeedModifiers>) {
+    for mut stack in &mut query {
+        if stack.0.is_empty() {
+            continue;
+        }
+        for modifier in &mut stack.0 {
+            modifier.duration.tick(time.delta());
+        }
+        stack.0.retain(|modifier| !modifier.duration.finished());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_lasts_its_seconds() {
+        let modifier = SpeedModifier::new(2.0, 1.5);
+        assert_eq!(modifier.duration.duration(), Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn new_ends_right_away_on_seconds_that_are_not_a_duration() {
+        for seconds in [-1.0, f32::NAN, f32::INFINITY] {
+            let modifier = SpeedModifier::new(2.0, seconds);
+            assert_eq!(modifier.duration.duration(), Duration::ZERO, "{seconds}");
+        }
+    }
+}
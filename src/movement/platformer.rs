@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+
+use super::{MovementLock, Velocity};
+use crate::cat::{Cat, ControlledBy};
+use crate::collision::Collider;
+use crate::input::{ActionState, CatAction};
+
+// Thickness of the ground strip along the bottom of the window
+const GROUND_HEIGHT: f32 = 48.0;
+
+// Top-down moves freely in all directions, platformer only walks sideways and falls
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub enum MovementMode {
+    #[default]
+    TopDown,
+    Platformer,
+}
+
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct PlatformerSettings {
+    // Pixels per second squared
+    pub gravity: f32,
+    // Upwards speed right after jumping
+    pub jump_speed: f32,
+    // Seconds after walking off an edge during which jumping still works
+    pub coyote_time: f32,
+    // Seconds a jump pressed just before landing is remembered for
+    pub jump_buffer: f32,
+}
+
+impl Default for PlatformerSettings {
+    fn default() -> Self {
+        Self {
+            gravity: 2400.0,
+            jump_speed: 950.0,
+            coyote_time: 0.1,
+            jump_buffer: 0.12,
+        }
+    }
+}
+
+// Ground contact and jump timing of an entity in platformer mode
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Jumper {
+    pub grounded: bool,
+    since_grounded: f32,
+    since_jump_pressed: Option<f32>,
+}
+
+// The strip the cat stands on in platformer mode
+#[derive(Component)]
+pub struct Ground;
+
+pub fn is_platformer(mode: Res<MovementMode>) -> bool {
+    *mode == MovementMode::Platformer
+}
+
+pub(super) fn toggle_movement_mode(
+    actions: Res<ActionState>,
+    mut mode: ResMut<MovementMode>,
+    mut ground: Query<&mut Visibility, With<Ground>>,
+) {
+    if !actions.just_pressed(CatAction::SwitchMode) {
+        return;
+    }
+    *mode = match *mode {
+        MovementMode::TopDown => MovementMode::Platformer,
+        MovementMode::Platformer => MovementMode::TopDown,
+    };
+    for mut visibility in &mut ground {
+        *visibility = match *mode {
+            MovementMode::TopDown => Visibility::Hidden,
+            MovementMode::Platformer => Visibility::Inherited,
+        };
+    }
+}
+
+pub(super) fn spawn_ground(mut commands: Commands, window: Single<&Window>) {
+    commands.spawn((
+        Ground,
+        Name::new("Ground"),
+        Sprite::from_color(Color::srgb(0.35, 0.5, 0.3), Vec2::ONE),
+        ground_transform(&window),
+        Visibility::Hidden,
+    ));
+}
+
+pub(super) fn resize_ground(
+    window: Single<&Window>,
+    mut ground: Query<&mut Transform, With<Ground>>,
+) {
+    for mut transform in &mut ground {
+        *transform = ground_transform(&window);
+    }
+}
+
+fn ground_transform(window: &Window) -> Transform {
+    let y = -window.height() / 2.0 + GROUND_HEIGHT / 2.0;
+    Transform::from_xyz(0.0, y, -2.0).with_scale(Vec3::new(window.width(), GROUND_HEIGHT, 1.0))
+}
+
+// Height of the ground's top surface
+fn ground_level(window: &Window) -> f32 {
+    -window.height() / 2.0 + GROUND_HEIGHT
+}
+
+// Remembers a jump press until the next fixed step gets to it
+pub(super) fn buffer_jump(
+    actions: Res<ActionState>,
+    mode: Res<MovementMode>,
+    cat: Single<(&mut Jumper, Has<MovementLock>), With<ControlledBy>>,
+) {
+    let (mut jumper, locked) = cat.into_inner();
+    if *mode == MovementMode::Platformer && actions.just_pressed(CatAction::Jump) && !locked {
+        jumper.since_jump_pressed = Some(0.0);
+    }
+}
+
+pub(super) fn apply_gravity_and_jump(
+    settings: Res<PlatformerSettings>,
+    time: Res<Time>,
+    mut cats: Query<(&mut Velocity, &mut Jumper), With<Cat>>,
+) {
+    let delta = time.delta_secs();
+    for (mut velocity, mut jumper) in &mut cats {
+        if let Some(since) = &mut jumper.since_jump_pressed {
+            *since += delta;
+        }
+        if jumper.grounded {
+            jumper.since_grounded = 0.0;
+        } else {
+            jumper.since_grounded += delta;
+        }
+
+        let buffered = jumper
+            .since_jump_pressed
+            .is_some_and(|since| since <= settings.jump_buffer);
+        let can_jump = jumper.grounded || jumper.since_grounded <= settings.coyote_time;
+        if buffered && can_jump {
+            velocity.0.y = settings.jump_speed;
+            jumper.grounded = false;
+            // Use up the coyote time so the same jump can't be triggered twice in the air
+            jumper.since_grounded = settings.coyote_time + delta;
+            jumper.since_jump_pressed = None;
+        } else {
+            velocity.0.y -= settings.gravity * delta;
+        }
+    }
+}
+
+// Stops the fall on the ground, after the cat has been moved by its velocity
+pub(super) fn land_on_ground(
+    window: Single<&Window>,
+    mut cats: Query<
+        (
+            &mut Transform,
+            &mut Velocity,
+            &mut Jumper,
+            Option<&Collider>,
+        ),
+        With<Cat>,
+    >,
+) {
+    for (mut transform, mut velocity, mut jumper, collider) in &mut cats {
+        let half_height = collider.map_or(0.0, |collider| collider.half_extents.y);
+        let floor = ground_level(&window) + half_height;
+        jumper.grounded = transform.translation.y <= floor && velocity.0.y <= 0.0;
+        if jumper.grounded {
+            transform.translation.y = floor;
+            velocity.0.y = 0.0;
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+use super::MovementLock;
+use crate::cat::{Cat, ControlledBy};
+use crate::input::{ActionState, CatAction};
+
+// Running faster while the sprint action is held, for as long as there is stamina left
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Sprint {
+    // Applied to the `MovementConfig` speeds
+    pub multiplier: f32,
+    pub is_sprinting: bool,
+}
+
+impl Default for Sprint {
+    fn default() -> Self {
+        Self {
+            multiplier: 1.6,
+            is_sprinting: false,
+        }
+    }
+}
+
+impl Sprint {
+    pub fn speed_factor(&self) -> f32 {
+        if self.is_sprinting {
+            self.multiplier
+        } else {
+            1.0
+        }
+    }
+}
+
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Stamina {
+    pub current: f32,
+    pub max: f32,
+    // Per second while sprinting
+    pub drain: f32,
+    // Per second while not sprinting
+    pub regen: f32,
+    // Fraction of `max` needed to start sprinting again after running out
+    pub recover_at: f32,
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self {
+            current: 100.0,
+            max: 100.0,
+            drain: 35.0,
+            regen: 20.0,
+            recover_at: 0.25,
+        }
+    }
+}
+
+impl Stamina {
+    // 0.0 (empty) to 1.0 (full), e.g. for a HUD bar
+    pub fn fraction(&self) -> f32 {
+        if self.max <= 0.0 {
+            0.0
+        } else {
+            (self.current / self.max).clamp(0.0, 1.0)
+        }
+    }
+}
+
+type Sprinter = (
+    &'static mut Sprint,
+    &'static mut Stamina,
+    Has<MovementLock>,
+    Has<ControlledBy>,
+);
+
+pub(super) fn update_sprint(
+    actions: Res<ActionState>,
+    time: Res<Time>,
+    mut cats: Query<Sprinter, With<Cat>>,
+) {
+    for (mut sprint, mut stamina, locked, controlled) in &mut cats {
+        let wants_sprint = controlled
+            && !locked
+            && actions.pressed(CatAction::Sprint)
+            && actions.movement() != Vec2::ZERO;
+        // Keep sprinting until empty, but only start again once a bit has come back
+        let can_sprint = if sprint.is_sprinting {
+            stamina.current > 0.0
+        } else {
+            stamina.fraction() >= stamina.recover_at
+        };
+        sprint.is_sprinting = wants_sprint && can_sprint;
+
+        let delta = if sprint.is_sprinting {
+            -stamina.drain
+        } else {
+            stamina.regen
+        } * time.delta_secs();
+        stamina.current = (stamina.current + delta).clamp(0.0, stamina.max);
+    }
+}
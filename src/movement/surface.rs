@@ -0,0 +1,113 @@
+use bevy::prelude::*;
+
+use super::MovementSettings;
+use crate::collision::{Collider, Collision, Sensor};
+
+// Ground that changes how things move across it, scaling `MovementSettings` while overlapped.
+// Needs a `Collider` (and `Sensor`, so it doesn't block) to be noticed.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Surface {
+    pub acceleration: f32,
+    pub friction: f32,
+    pub speed: f32,
+}
+
+impl Default for Surface {
+    fn default() -> Self {
+        Self {
+            acceleration: 1.0,
+            friction: 1.0,
+            speed: 1.0,
+        }
+    }
+}
+
+impl Surface {
+    // Slow to get going and even slower to stop
+    pub const ICE: Self = Self {
+        acceleration: 0.25,
+        friction: 0.08,
+        speed: 1.1,
+    };
+    // Sluggish, but stops right away
+    pub const MUD: Self = Self {
+        acceleration: 0.6,
+        friction: 2.0,
+        speed: 0.5,
+    };
+    // Good grip
+    pub const CARPET: Self = Self {
+        acceleration: 1.5,
+        friction: 1.5,
+        speed: 0.9,
+    };
+}
+
+// A visible patch of `surface`, drawn underneath everything that walks on it
+pub fn surface_zone(
+    name: &'static str,
+    surface: Surface,
+    color: Color,
+    center: Vec2,
+    size: Vec2,
+) -> impl Bundle {
+    (
+        Name::new(name),
+        surface,
+        Collider::from_size(size),
+        Sensor,
+        Sprite::from_color(color, size),
+        Transform::from_translation(center.extend(-3.0)),
+    )
+}
+
+pub(super) fn spawn_surfaces(mut commands: Commands) {
+    commands.spawn(surface_zone(
+        "Ice",
+        Surface::ICE,
+        Color::srgba(0.8, 0.95, 1.0, 0.8),
+        Vec2::new(-300.0, -280.0),
+        Vec2::new(260.0, 160.0),
+    ));
+    commands.spawn(surface_zone(
+        "Mud",
+        Surface::MUD,
+        Color::srgba(0.4, 0.28, 0.15, 0.8),
+        Vec2::new(300.0, -280.0),
+        Vec2::new(260.0, 160.0),
+    ));
+    commands.spawn(surface_zone(
+        "Carpet",
+        Surface::CARPET,
+        Color::srgba(0.6, 0.2, 0.25, 0.8),
+        Vec2::new(0.0, 300.0),
+        Vec2::new(320.0, 140.0),
+    ));
+}
+
+// Rebuilds every entity's stack of surfaces from this frame's overlaps
+pub(super) fn track_surfaces(
+    mut collisions: EventReader<Collision>,
+    surfaces: Query<&Surface>,
+    mut settings: Query<(Entity, &mut MovementSettings)>,
+) {
+    let overlaps: Vec<(Entity, Surface)> = collisions
+        .read()
+        .filter_map(|collision| {
+            let surface = surfaces.get(collision.other).ok()?;
+            Some((collision.entity, *surface))
+        })
+        .collect();
+
+    for (entity, mut settings) in &mut settings {
+        let stack: Vec<Surface> = overlaps
+            .iter()
+            .filter(|(on, _)| *on == entity)
+            .map(|(_, surface)| *surface)
+            .collect();
+        if settings.surfaces != stack {
+            settings.surfaces = stack;
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+
+use crate::theme::{Surface, TextRole, Theme};
+use crate::tween::Tween;
+
+// How far (in logical pixels) toasts slide in from the right
+const TOAST_SLIDE: f32 = 40.0;
+
+// Short messages like "Settings saved" stacking in the bottom right corner and fading out on
+// their own. Any system can show one by sending a `Notify` event.
+pub struct NotificationsPlugin;
+
+impl Plugin for NotificationsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Notifications>()
+            .add_event::<Notify>()
+            .add_systems(Startup, spawn_toast_stack)
+            .add_systems(Update, (show_toasts, fade_toasts).chain());
+    }
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct Notify(pub String);
+
+impl Notify {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct Notifications {
+    // Seconds a toast stays up, fade included
+    pub duration: f32,
+    // Seconds at the end of `duration` it takes to fade out
+    pub fade: f32,
+    // Older toasts are dropped beyond this many
+    pub max_visible: usize,
+}
+
+impl Default for Notifications {
+    fn default() -> Self {
+        Self {
+            duration: 3.0,
+            fade: 0.5,
+            max_visible: 5,
+        }
+    }
+}
+
+#[derive(Component)]
+struct ToastStack;
+
+#[derive(Component)]
+struct Toast {
+    message: String,
+    // Real time, so toasts keep fading while the game is paused
+    timer: Timer,
+}
+
+fn spawn_toast_stack(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        ToastStack,
+        Name::new("Toasts"),
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(theme.gap),
+            bottom: Val::Px(theme.gap),
+            // Newest at the bottom
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            row_gap: Val::Px(theme.gap / 2.0),
+            ..Default::default()
+        },
+        GlobalZIndex(30),
+        Pickable::IGNORE,
+    ));
+}
+
+fn show_toasts(
+    mut commands: Commands,
+    mut events: EventReader<Notify>,
+    notifications: Res<Notifications>,
+    theme: Res<Theme>,
+    stack: Single<(Entity, Option<&Children>), With<ToastStack>>,
+    mut toasts: Query<&mut Toast>,
+) {
+    let (stack, children) = *stack;
+    let mut shown: Vec<Entity> = children.map(|c| c.to_vec()).unwrap_or_default();
+    for Notify(message) in events.read() {
+        // The same message again just stays up longer instead of stacking
+        if let Some(mut toast) = shown
+            .last()
+            .and_then(|&last| toasts.get_mut(last).ok())
+            .filter(|toast| toast.message == *message)
+        {
+            toast.timer.reset();
+            continue;
+        }
+        let toast = commands
+            .spawn((
+                Toast {
+                    message: message.clone(),
+                    timer: Timer::from_seconds(notifications.duration, TimerMode::Once),
+                },
+                Node {
+                    padding: UiRect::axes(
+                        Val::Px(theme.button_padding.x / 2.0),
+                        Val::Px(theme.button_padding.y / 2.0),
+                    ),
+                    ..Default::default()
+                },
+                theme.surface(Surface::Panel),
+                // Slides in from the right edge
+                Tween::new(0.25)
+                    .slide(Vec2::new(TOAST_SLIDE, 0.0), Vec2::ZERO)
+                    .fade(0.0, 1.0),
+                children![(Text::new(message.clone()), theme.text(TextRole::Body))],
+            ))
+            .id();
+        commands.entity(stack).add_child(toast);
+        shown.push(toast);
+    }
+    let excess = shown.len().saturating_sub(notifications.max_visible);
+    for &old in &shown[..excess] {
+        commands.entity(old).despawn();
+    }
+}
+
+fn fade_toasts(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    notifications: Res<Notifications>,
+    theme: Res<Theme>,
+    mut toasts: Query<(Entity, &mut Toast, &mut BackgroundColor, &Children)>,
+    mut texts: Query<&mut TextColor>,
+) {
+    for (entity, mut toast, mut background, children) in &mut toasts {
+        toast.timer.tick(time.delta());
+        if toast.timer.finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let alpha = (toast.timer.remaining_secs() / notifications.fade).min(1.0);
+        background.0 = theme.panel.with_alpha(theme.panel.alpha() * alpha);
+        for &child in children {
+            if let Ok(mut color) = texts.get_mut(child) {
+                color.0 = theme.text.with_alpha(theme.text.alpha() * alpha);
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::color::HexColorError;
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::collision::{Aabb, Collider};
+use crate::loading::LoadingAssets;
+use crate::state::InGame;
+
+const LAYOUT_PATH: &str = "room.layout.ron";
+// Above the floor and the pickups, below the cats
+const OBSTACLE_Z: f32 = -0.5;
+
+// Furniture from assets/room.layout.ron, put in the room for every game. Obstacles are solid
+// colliders without a `Velocity`, so collision keeps everything that moves out of them.
+pub struct ObstaclePlugin;
+
+impl Plugin for ObstaclePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<RoomLayout>()
+            .init_asset_loader::<RoomLayoutLoader>()
+            .register_type::<Obstacle>()
+            .add_systems(Startup, load_layout)
+            .add_systems(OnEnter(InGame), spawn_obstacles)
+            .add_systems(Update, reload_obstacles.run_if(in_state(InGame)));
+    }
+}
+
+// Something in the way
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Obstacle;
+
+#[derive(Clone, Debug)]
+pub struct ObstacleDefinition {
+    pub name: String,
+    // Center, in pixels from the middle of the window
+    pub position: Vec2,
+    pub size: Vec2,
+    pub color: Color,
+}
+
+#[derive(Asset, TypePath, Clone, Debug)]
+pub struct RoomLayout {
+    pub obstacles: Vec<ObstacleDefinition>,
+}
+
+#[derive(Resource)]
+struct LayoutHandle(Handle<RoomLayout>);
+
+// Boxes of every obstacle, for keeping things from being spawned inside them
+pub fn obstacle_boxes<'a>(
+    obstacles: impl IntoIterator<Item = (&'a Transform, &'a Collider)>,
+) -> Vec<Aabb> {
+    obstacles
+        .into_iter()
+        .map(|(transform, collider)| Aabb::new(transform.translation.truncate(), collider))
+        .collect()
+}
+
+fn load_layout(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let handle = asset_server.load(LAYOUT_PATH);
+    loading.track(handle.clone());
+    commands.insert_resource(LayoutHandle(handle));
+}
+
+fn obstacle(definition: &ObstacleDefinition) -> impl Bundle {
+    (
+        Obstacle,
+        StateScoped(InGame),
+        Name::new(definition.name.clone()),
+        Sprite::from_color(definition.color, definition.size),
+        Transform::from_translation(definition.position.extend(OBSTACLE_Z)),
+        Collider::from_size(definition.size),
+    )
+}
+
+fn spawn_obstacles(
+    mut commands: Commands,
+    layouts: Res<Assets<RoomLayout>>,
+    handle: Res<LayoutHandle>,
+) {
+    let Some(layout) = layouts.get(&handle.0) else {
+        return;
+    };
+    for definition in &layout.obstacles {
+        commands.spawn(obstacle(definition));
+    }
+}
+
+// Needs the `dev` feature (bevy's `file_watcher`) for changes on disk to be picked up
+fn reload_obstacles(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<RoomLayout>>,
+    layouts: Res<Assets<RoomLayout>>,
+    handle: Res<LayoutHandle>,
+    obstacles: Query<Entity, With<Obstacle>>,
+) {
+    let modified = events.read().any(|event| event.is_modified(&handle.0));
+    if !modified {
+        return;
+    }
+    for entity in &obstacles {
+        commands.entity(entity).despawn();
+    }
+    spawn_obstacles(commands, layouts, handle);
+}
+
+// Colors are written as hex strings, like in the theme
+#[derive(Deserialize)]
+struct RoomLayoutDef {
+    obstacles: Vec<ObstacleDef>,
+}
+
+#[derive(Deserialize)]
+struct ObstacleDef {
+    name: String,
+    position: (f32, f32),
+    size: (f32, f32),
+    color: String,
+}
+
+#[derive(Default)]
+pub struct RoomLayoutLoader;
+
+#[derive(Debug, Error)]
+pub enum RoomLayoutLoaderError {
+    #[error("could not read room layout: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse room layout: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("invalid color {0:?} in room layout: {1}")]
+    Color(String, HexColorError),
+}
+
+impl AssetLoader for RoomLayoutLoader {
+    type Asset = RoomLayout;
+    type Settings = ();
+    type Error = RoomLayoutLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let def: RoomLayoutDef = ron::de::from_bytes(&bytes)?;
+        let obstacles = def
+            .obstacles
+            .into_iter()
+            .map(|def| {
+                let color = Srgba::hex(&def.color)
+                    .map_err(|error| RoomLayoutLoaderError::Color(def.color.clone(), error))?;
+                Ok(ObstacleDefinition {
+                    name: def.name,
+                    position: def.position.into(),
+                    size: def.size.into(),
+                    color: color.into(),
+                })
+            })
+            .collect::<Result<_, RoomLayoutLoaderError>>()?;
+        Ok(RoomLayout { obstacles })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["layout.ron"]
+    }
+}
@@ -0,0 +1,115 @@
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy::window::WindowResized;
+
+use crate::cat::Cat;
+use crate::collision;
+use crate::dog::Dog;
+use crate::movement::{MovementSystems, Velocity};
+use crate::obstacle::Obstacle;
+
+// How thick the invisible walls around the window are
+const WALL_THICKNESS: f32 = 64.0;
+
+// Everything that gets a dynamic body
+type Body = Or<(With<Cat>, With<Dog>)>;
+
+// Lets avian2d move the cats and dogs: movement still decides their velocities, the physics step
+// applies them and keeps everyone out of the walls around the window (and anything else with a
+// collider)
+pub struct CatPhysicsPlugin;
+
+impl Plugin for CatPhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(PhysicsPlugins::default())
+            .insert_resource(Gravity(Vec2::ZERO))
+            .add_systems(Startup, spawn_walls)
+            .add_systems(Update, respawn_walls.run_if(on_event::<WindowResized>))
+            .add_systems(Update, (add_body, add_obstacle_body))
+            .add_systems(
+                FixedUpdate,
+                (
+                    read_velocity.before(MovementSystems::Move),
+                    write_velocity.after(MovementSystems::Move),
+                ),
+            );
+    }
+}
+
+// One of the static colliders along the window edges
+#[derive(Component)]
+pub struct Wall;
+
+fn spawn_walls(mut commands: Commands, window: Single<&Window>) {
+    let size = window.size();
+    let half = size / 2.0 + WALL_THICKNESS / 2.0;
+    let walls = [
+        (Vec2::new(0.0, half.y), Vec2::new(size.x, WALL_THICKNESS)),
+        (Vec2::new(0.0, -half.y), Vec2::new(size.x, WALL_THICKNESS)),
+        (Vec2::new(-half.x, 0.0), Vec2::new(WALL_THICKNESS, size.y)),
+        (Vec2::new(half.x, 0.0), Vec2::new(WALL_THICKNESS, size.y)),
+    ];
+    for (position, extents) in walls {
+        commands.spawn((
+            Wall,
+            Name::new("Wall"),
+            RigidBody::Static,
+            Collider::rectangle(extents.x, extents.y),
+            Transform::from_translation(position.extend(0.0)),
+        ));
+    }
+}
+
+// Moves the walls to the new window edges
+fn respawn_walls(
+    mut commands: Commands,
+    window: Single<&Window>,
+    walls: Query<Entity, With<Wall>>,
+) {
+    for wall in &walls {
+        commands.entity(wall).despawn();
+    }
+    spawn_walls(commands, window);
+}
+
+fn add_body(
+    mut commands: Commands,
+    bodies: Query<(Entity, &Transform, &collision::Collider), (Body, Without<RigidBody>)>,
+) {
+    for (entity, transform, collider) in &bodies {
+        // Avian scales colliders along with the transform, so undo the scale baked into ours
+        let size = collider.half_extents * 2.0 / transform.scale.truncate();
+        commands.entity(entity).insert((
+            RigidBody::Dynamic,
+            Collider::rectangle(size.x, size.y),
+            LockedAxes::ROTATION_LOCKED,
+            LinearVelocity::ZERO,
+        ));
+    }
+}
+
+// Furniture is as solid as the walls
+fn add_obstacle_body(
+    mut commands: Commands,
+    obstacles: Query<(Entity, &collision::Collider), (With<Obstacle>, Without<RigidBody>)>,
+) {
+    for (entity, collider) in &obstacles {
+        let size = collider.half_extents * 2.0;
+        commands
+            .entity(entity)
+            .insert((RigidBody::Static, Collider::rectangle(size.x, size.y)));
+    }
+}
+
+// Start from where physics left the body, e.g. stopped by a wall
+fn read_velocity(mut query: Query<(&LinearVelocity, &mut Velocity), Body>) {
+    for (linear_velocity, mut velocity) in &mut query {
+        velocity.0 = linear_velocity.0;
+    }
+}
+
+fn write_velocity(mut query: Query<(&Velocity, &mut LinearVelocity), Body>) {
+    for (velocity, mut linear_velocity) in &mut query {
+        linear_velocity.0 = velocity.0;
+    }
+}
@@ -0,0 +1,349 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+
+use crate::audio::{PlaySound, SoundId};
+use crate::cat::Cat;
+use crate::collectible::Collectible;
+use crate::collision::{Collider, Collision, Sensor};
+use crate::health::Invulnerable;
+use crate::level::LevelScoped;
+use crate::minimap::MinimapIcon;
+use crate::movement::{MovementSystems, SpeedModifier};
+use crate::spawner::{Prototype, Spawn, SpawnedBy};
+use crate::state::{GameState, InGame};
+
+pub const PICKUP_SIZE: Vec2 = Vec2::splat(20.0);
+// Under the fish, so a fish on top of a power-up can still be seen
+const PICKUP_Z: f32 = -1.5;
+const SPEED_MULTIPLIER: f32 = 1.6;
+// Tells the boosts apart from other speed modifiers, see `SpeedModifier::source`
+const SPEED_SOURCE: &str = "power-up-speed";
+// Fish closer than this to a cat with a magnet fly towards it, in pixels per second
+const MAGNET_RADIUS: f32 = 260.0;
+const MAGNET_SPEED: f32 = 320.0;
+
+// Pickups brought in now and then by a `Spawner`, giving the cat that touches them a power for a
+// few seconds: running faster, not getting hurt, or pulling in the fish around it. Picking up one
+// that's already active makes it last longer. The controlled cat's powers show in the HUD.
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PowerUpKind>()
+            .register_type::<PowerUpPickup>()
+            .register_type::<PowerUps>()
+            .add_systems(
+                FixedUpdate,
+                (pick_up_power_ups, tick_power_ups)
+                    .chain()
+                    .after(MovementSystems::Collide)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                Update,
+                (spawn_power_ups, pull_fish).run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum PowerUpKind {
+    Speed,
+    Invincibility,
+    Magnet,
+}
+
+impl PowerUpKind {
+    pub const ALL: [PowerUpKind; 3] = [
+        PowerUpKind::Speed,
+        PowerUpKind::Invincibility,
+        PowerUpKind::Magnet,
+    ];
+
+    pub fn color(self) -> Color {
+        match self {
+            PowerUpKind::Speed => Color::srgb(0.3, 0.8, 1.0),
+            PowerUpKind::Invincibility => Color::srgb(1.0, 0.85, 0.2),
+            PowerUpKind::Magnet => Color::srgb(0.85, 0.3, 0.9),
+        }
+    }
+
+    // How long one pickup lasts
+    pub fn seconds(self) -> f32 {
+        match self {
+            PowerUpKind::Speed => 6.0,
+            PowerUpKind::Invincibility => 5.0,
+            PowerUpKind::Magnet => 8.0,
+        }
+    }
+}
+
+// Something a cat picks up by touching it, giving it a power
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+#[require(Sensor)]
+pub struct PowerUpPickup(pub PowerUpKind);
+
+// The powers an entity has and how much longer each of them lasts. The effects themselves are
+// other components (a `SpeedModifier`, `Invulnerable`, `Magnet`), which go away with the power.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct PowerUps(pub Vec<ActivePowerUp>);
+
+#[derive(Clone, Debug, Reflect)]
+pub struct ActivePowerUp {
+    pub kind: PowerUpKind,
+    pub timer: Timer,
+}
+
+impl PowerUps {
+    pub fn get(&self, kind: PowerUpKind) -> Option<&ActivePowerUp> {
+        self.0.iter().find(|active| active.kind == kind)
+    }
+
+    // Starts `kind`, or makes it last longer if it's already active
+    fn add(&mut self, kind: PowerUpKind) {
+        match self.0.iter_mut().find(|active| active.kind == kind) {
+            Some(active) => {
+                let Ok(extra) = Duration::try_from_secs_f32(kind.seconds()) else {
+                    warn!("can't extend {kind:?} by {} seconds", kind.seconds());
+                    return;
+                };
+                let duration = active.timer.duration() + extra;
+                active.timer.set_duration(duration);
+            }
+            None => self.0.push(ActivePowerUp {
+                kind,
+                timer: Timer::from_seconds(kind.seconds(), TimerMode::Once),
+            }),
+        }
+    }
+}
+
+// Pulls in fish while the magnet power lasts
+#[derive(Component)]
+struct Magnet;
+
+fn power_up_pickup(kind: PowerUpKind, position: Vec2) -> impl Bundle {
+    (
+        PowerUpPickup(kind),
+        StateScoped(InGame),
+        LevelScoped,
+        Name::new(format!("Power-up {kind:?}")),
+        Sprite::from_color(kind.color(), PICKUP_SIZE),
+        // A diamond, unlike anything else lying around
+        Transform::from_translation(position.extend(PICKUP_Z))
+            .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_4)),
+        Collider::from_size(PICKUP_SIZE),
+        MinimapIcon::new(kind.color()),
+    )
+}
+
+// A power picked at random
+fn spawn_power_ups(mut commands: Commands, mut spawns: EventReader<Spawn>) {
+    let mut rng = rand::thread_rng();
+    for spawn in spawns.read() {
+        if spawn.prototype != Prototype::PowerUp {
+            continue;
+        }
+        let Some(&kind) = PowerUpKind::ALL.choose(&mut rng) else {
+            continue;
+        };
+        commands.spawn((
+            power_up_pickup(kind, spawn.position),
+            SpawnedBy(spawn.spawner),
+        ));
+    }
+}
+
+fn pick_up_power_ups(
+    mut commands: Commands,
+    mut collisions: EventReader<Collision>,
+    mut sounds: EventWriter<PlaySound>,
+    pickups: Query<&PowerUpPickup>,
+    mut cats: Query<(Option<&mut PowerUps>, Option<&mut Invulnerable>), With<Cat>>,
+) {
+    // Like fish, a pickup goes to the first cat that reaches it
+    let mut picked = HashSet::new();
+    for collision in collisions.read() {
+        let Ok(&PowerUpPickup(kind)) = pickups.get(collision.other) else {
+            continue;
+        };
+        let Ok((power_ups, invulnerable)) = cats.get_mut(collision.entity) else {
+            continue;
+        };
+        if !picked.insert(collision.other) {
+            continue;
+        }
+        commands.entity(collision.other).despawn();
+        sounds.write(PlaySound::new(SoundId::Pickup).on(collision.entity));
+
+        let mut cat = commands.entity(collision.entity);
+        match power_ups {
+            Some(mut power_ups) => power_ups.add(kind),
+            None => {
+                let mut power_ups = PowerUps::default();
+                power_ups.add(kind);
+                cat.insert(power_ups);
+            }
+        }
+        match kind {
+            PowerUpKind::Speed => {
+                cat.insert(
+                    SpeedModifier::new(SPEED_MULTIPLIER, kind.seconds()).with_source(SPEED_SOURCE),
+                );
+            }
+            PowerUpKind::Invincibility => match invulnerable {
+                Some(mut invulnerable) => {
+                    invulnerable.extend(kind.seconds());
+                    invulnerable.blink = false;
+                }
+                None => {
+                    cat.insert(Invulnerable::new(kind.seconds()).without_blink());
+                }
+            },
+            PowerUpKind::Magnet => {
+                cat.insert(Magnet);
+            }
+        }
+    }
+}
+
+// The speed modifier and invulnerability run out by themselves, with the same timing
+fn tick_power_ups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut PowerUps)>,
+) {
+    for (entity, mut power_ups) in &mut query {
+        for active in &mut power_ups.0 {
+            active.timer.tick(time.delta());
+        }
+        let expired = power_ups.0.iter().any(|active| active.timer.finished());
+        if !expired {
+            continue;
+        }
+        power_ups.0.retain(|active| !active.timer.finished());
+        if power_ups.get(PowerUpKind::Magnet).is_none() {
+            commands.entity(entity).remove::<Magnet>();
+        }
+    }
+}
+
+fn pull_fish(
+    time: Res<Time>,
+    magnets: Query<&Transform, (With<Magnet>, Without<Collectible>)>,
+    mut fish: Query<&mut Transform, With<Collectible>>,
+) {
+    for mut fish in &mut fish {
+        let position = fish.translation.truncate();
+        let closest = magnets
+            .iter()
+            .map(|magnet| magnet.translation.truncate())
+            .filter(|magnet| magnet.distance(position) < MAGNET_RADIUS)
+            .min_by(|a, b| a.distance(position).total_cmp(&b.distance(position)));
+        let Some(magnet) = closest else {
+            continue;
+        };
+        let step = (magnet - position).clamp_length_max(MAGNET_SPEED * time.delta_secs());
+        fish.translation += step.extend(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn timer(power_ups: &PowerUps, kind: PowerUpKind) -> &Timer {
+        &power_ups.get(kind).unwrap().timer
+    }
+
+    #[test]
+    fn add_starts_a_power_up_for_its_seconds() {
+        let mut power_ups = PowerUps::default();
+        power_ups.add(PowerUpKind::Speed);
+        assert_eq!(
+            timer(&power_ups, PowerUpKind::Speed).duration(),
+            Duration::from_secs(6)
+        );
+        assert!(power_ups.get(PowerUpKind::Magnet).is_none());
+    }
+
+    #[test]
+    fn add_again_makes_it_last_longer() {
+        let mut power_ups = PowerUps::default();
+        power_ups.add(PowerUpKind::Magnet);
+        power_ups.0[0].timer.tick(Duration::from_secs(3));
+        power_ups.add(PowerUpKind::Magnet);
+        assert_eq!(power_ups.0.len(), 1);
+        let timer = timer(&power_ups, PowerUpKind::Magnet);
+        assert_eq!(timer.duration(), Duration::from_secs(16));
+        assert_eq!(timer.remaining(), Duration::from_secs(13));
+    }
+
+    #[test]
+    fn different_powers_run_side_by_side() {
+        let mut power_ups = PowerUps::default();
+        power_ups.add(PowerUpKind::Speed);
+        power_ups.add(PowerUpKind::Invincibility);
+        assert_eq!(power_ups.0.len(), 2);
+        assert_eq!(
+            timer(&power_ups, PowerUpKind::Invincibility).duration(),
+            Duration::from_secs(5)
+        );
+    }
+
+    #[test]
+    fn powers_wear_off_one_by_one() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        let mut power_ups = PowerUps::default();
+        power_ups.add(PowerUpKind::Speed);
+        power_ups.add(PowerUpKind::Magnet);
+        let cat = world.spawn((power_ups, Magnet)).id();
+
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs(7));
+        world.run_system_once(tick_power_ups).unwrap();
+        let power_ups = world.get::<PowerUps>(cat).unwrap();
+        assert!(power_ups.get(PowerUpKind::Speed).is_none());
+        assert!(power_ups.get(PowerUpKind::Magnet).is_some());
+        assert!(world.get::<Magnet>(cat).is_some());
+
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs(1));
+        world.run_system_once(tick_power_ups).unwrap();
+        assert!(world.get::<PowerUps>(cat).unwrap().0.is_empty());
+        assert!(world.get::<Magnet>(cat).is_none());
+    }
+
+    #[test]
+    fn invincibility_extends_the_i_frames_of_a_hit() {
+        let mut world = World::new();
+        world.init_resource::<Events<Collision>>();
+        world.init_resource::<Events<PlaySound>>();
+        let cat = world.spawn((Cat, Invulnerable::new(1.0))).id();
+        let pickup = world.spawn(PowerUpPickup(PowerUpKind::Invincibility)).id();
+        world.send_event(Collision {
+            entity: cat,
+            other: pickup,
+            normal: Vec2::X,
+        });
+        world.run_system_once(pick_up_power_ups).unwrap();
+
+        let invulnerable = world.get::<Invulnerable>(cat).unwrap();
+        assert_eq!(invulnerable.timer.duration(), Duration::from_secs(6));
+        // Invincibility doesn't blink, even if the hit did
+        assert!(!invulnerable.blink);
+        let power_ups = world.get::<PowerUps>(cat).unwrap();
+        assert!(power_ups.get(PowerUpKind::Invincibility).is_some());
+        assert!(world.get_entity(pickup).is_err());
+    }
+}
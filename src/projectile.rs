@@ -0,0 +1,243 @@
+use std::collections::HashSet;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::accessibility::{Accessibility, reduced_motion};
+use crate::animation::{Facing, SquashStretch};
+use crate::audio::{PlaySound, SoundId};
+use crate::collision::{Aabb, Collider, Collision, Sensor};
+use crate::dog::{Dog, DogBehavior};
+use crate::input::{ActionState, CatAction};
+use crate::level::LevelScoped;
+use crate::movement::{
+    ControlledAndUnlocked, Impulse, InterpolatedTranslation, MovementSystems, Velocity,
+};
+use crate::obstacle::{Obstacle, obstacle_boxes};
+use crate::state::{GameState, InGame};
+
+const YARN_SIZE: Vec2 = Vec2::splat(12.0);
+const YARN_COLOR: Color = Color::srgb(0.95, 0.45, 0.65);
+// Above the cats, it's in the air
+const YARN_Z: f32 = 0.5;
+// Turns per second while flying
+const YARN_SPIN: f32 = 3.0;
+// How hard a dog that's hit is shoved away, in pixels per second
+const YARN_KNOCKBACK: f32 = 400.0;
+// How hard throwing pushes the cat back
+const RECOIL_SPEED: f32 = 120.0;
+
+// Balls of yarn the controlled cat throws the way it's facing. One flies straight until it hits
+// a dog (which is shoved away and gives up its chase), runs into an obstacle or the window edge,
+// or just runs out of time. Throwing pushes the cat back a little and squashes it.
+pub struct ProjectilePlugin;
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Projectile>()
+            .register_type::<YarnThrower>()
+            .add_event::<YarnThrown>()
+            .add_observer(recoil_on_throw)
+            .add_systems(
+                Update,
+                (tick_throwers, throw_yarn)
+                    .chain()
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    move_projectiles.in_set(MovementSystems::Move),
+                    hit_dogs
+                        .after(MovementSystems::Collide)
+                        .run_if(in_state(GameState::Playing)),
+                ),
+            );
+    }
+}
+
+// Flies on by itself, without a `Velocity`, so nothing else moves or stops it
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+#[require(Sensor)]
+pub struct Projectile {
+    pub velocity: Vec2,
+    // Gone once it runs out
+    pub lifetime: Timer,
+}
+
+// Lets an entity throw yarn
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct YarnThrower {
+    // Pixels per second
+    pub speed: f32,
+    // Seconds a ball of yarn flies
+    pub lifetime: f32,
+    // Until the next throw
+    pub cooldown: Timer,
+}
+
+impl Default for YarnThrower {
+    fn default() -> Self {
+        let mut cooldown = Timer::from_seconds(0.5, TimerMode::Once);
+        // Ready right away
+        cooldown.tick(cooldown.duration());
+        Self {
+            speed: 650.0,
+            lifetime: 1.2,
+            cooldown,
+        }
+    }
+}
+
+// Sent (and triggered on the entity) when it throws a ball of yarn, for the recoil
+#[derive(Event, Clone, Debug)]
+pub struct YarnThrown {
+    pub entity: Entity,
+    pub projectile: Entity,
+    pub direction: Vec2,
+}
+
+fn yarn_ball(position: Vec2, velocity: Vec2, lifetime: f32) -> impl Bundle {
+    (
+        Projectile {
+            velocity,
+            lifetime: Timer::from_seconds(lifetime, TimerMode::Once),
+        },
+        StateScoped(InGame),
+        LevelScoped,
+        Name::new("Yarn ball"),
+        Sprite::from_color(YARN_COLOR, YARN_SIZE),
+        Transform::from_translation(position.extend(YARN_Z)),
+        Collider::from_size(YARN_SIZE),
+        // Moved in `FixedUpdate` like the cats and dogs, so drawn between the steps the same way
+        InterpolatedTranslation::default(),
+    )
+}
+
+fn tick_throwers(time: Res<Time>, mut query: Query<&mut YarnThrower>) {
+    for mut thrower in &mut query {
+        thrower.cooldown.tick(time.delta());
+    }
+}
+
+fn throw_yarn(
+    mut commands: Commands,
+    actions: Res<ActionState>,
+    mut thrown: EventWriter<YarnThrown>,
+    cat: Single<(Entity, &Transform, &Facing, &mut YarnThrower), ControlledAndUnlocked>,
+) {
+    let (entity, transform, facing, mut thrower) = cat.into_inner();
+    if !actions.just_pressed(CatAction::Throw) || !thrower.cooldown.finished() {
+        return;
+    }
+    thrower.cooldown.reset();
+    let direction = facing.to_vec2();
+    let projectile = commands
+        .spawn(yarn_ball(
+            transform.translation.truncate(),
+            direction * thrower.speed,
+            thrower.lifetime,
+        ))
+        .id();
+
+    let event = YarnThrown {
+        entity,
+        projectile,
+        direction,
+    };
+    commands.trigger_targets(event.clone(), entity);
+    thrown.write(event);
+}
+
+// Knocked back a step from the throw, squashed along it
+fn recoil_on_throw(
+    trigger: Trigger<YarnThrown>,
+    mut commands: Commands,
+    accessibility: Option<Res<Accessibility>>,
+    mut query: Query<(Option<&mut SquashStretch>, Option<&mut Impulse>)>,
+) {
+    let Ok((squash, impulse)) = query.get_mut(trigger.target()) else {
+        return;
+    };
+    let direction = trigger.event().direction;
+    let recoil = -direction * RECOIL_SPEED;
+    match impulse {
+        Some(mut impulse) => impulse.add(recoil),
+        None => {
+            commands
+                .entity(trigger.target())
+                .insert(Impulse::new(recoil));
+        }
+    }
+    let reduced_motion = reduced_motion(accessibility);
+    if let Some(mut squash) = squash
+        && !reduced_motion
+    {
+        squash.squash_along(direction);
+    }
+}
+
+type ObstacleFilter = (With<Obstacle>, Without<Projectile>);
+
+// Falls out of the air at obstacles and the window edges
+fn move_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut projectiles: Query<(Entity, &mut Projectile, &mut Transform, &Collider)>,
+    obstacles: Query<(&Transform, &Collider), ObstacleFilter>,
+) {
+    let obstacles = obstacle_boxes(obstacles);
+    let half_window = window.size() / 2.0;
+    for (entity, mut projectile, mut transform, collider) in &mut projectiles {
+        transform.translation += (projectile.velocity * time.delta_secs()).extend(0.0);
+        transform.rotate_z(YARN_SPIN * std::f32::consts::TAU * time.delta_secs());
+
+        let position = transform.translation.truncate();
+        let aabb = Aabb::new(position, collider);
+        let expired = projectile.lifetime.tick(time.delta()).finished();
+        let off_screen = position.abs().cmpgt(half_window).any();
+        let blocked = obstacles
+            .iter()
+            .any(|obstacle| aabb.penetration(obstacle).is_some());
+        if expired || off_screen || blocked {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn hit_dogs(
+    mut commands: Commands,
+    mut collisions: EventReader<Collision>,
+    mut sounds: EventWriter<PlaySound>,
+    projectiles: Query<(), With<Projectile>>,
+    mut dogs: Query<(&mut Velocity, &mut DogBehavior), With<Dog>>,
+) {
+    // A ball of yarn only hits once, even if it reaches two dogs in one step
+    let mut spent = HashSet::new();
+    for collision in collisions.read() {
+        // `normal` points from `other` towards `entity`
+        for (dog, projectile, away) in [
+            (collision.entity, collision.other, collision.normal),
+            (collision.other, collision.entity, -collision.normal),
+        ] {
+            if !projectiles.contains(projectile) {
+                continue;
+            }
+            let Ok((mut velocity, mut behavior)) = dogs.get_mut(dog) else {
+                continue;
+            };
+            if !spent.insert(projectile) {
+                continue;
+            }
+            // It may have flown into an obstacle this same step
+            commands.entity(projectile).try_despawn();
+            sounds.write(PlaySound::new(SoundId::Bump).on(dog));
+            // It slows back down by itself as it turns for home
+            velocity.0 = away * YARN_KNOCKBACK;
+            *behavior = DogBehavior::GiveUp;
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+
+use crate::state::InGame;
+
+// Points scored in the current game. Anything that awards points does it with
+// `commands.add_score(amount)`, and everything showing the score listens for `ScoreChanged`.
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Score>()
+            .register_type::<Score>()
+            .add_event::<ScoreChanged>()
+            .add_systems(OnEnter(InGame), reset_score);
+    }
+}
+
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Resource)]
+pub struct Score(pub u32);
+
+// Sent whenever points are added, after `Score` has been updated
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ScoreChanged {
+    pub total: u32,
+    pub added: u32,
+}
+
+pub trait AddScoreExt {
+    fn add_score(&mut self, amount: u32);
+}
+
+impl AddScoreExt for Commands<'_, '_> {
+    fn add_score(&mut self, amount: u32) {
+        self.queue(move |world: &mut World| {
+            let mut score = world.resource_mut::<Score>();
+            score.0 += amount;
+            let total = score.0;
+            world.send_event(ScoreChanged {
+                total,
+                added: amount,
+            });
+        });
+    }
+}
+
+fn reset_score(mut score: ResMut<Score>) {
+    *score = Score::default();
+}
@@ -0,0 +1,335 @@
+use bevy::prelude::*;
+use bevy::window::{MonitorSelection, PresentMode, PrimaryWindow, WindowMode};
+use serde::{Deserialize, Serialize};
+
+use crate::accessibility::Accessibility;
+use crate::audio::{AudioSettings, MusicTrack};
+use crate::config;
+use crate::console::console_is_open;
+use crate::input::is_rebinding;
+use crate::localization::{Language, Localization};
+use crate::notifications::Notify;
+
+const SETTINGS_FILE: &str = "settings.ron";
+
+// Window sizes the settings screen cycles through
+pub const RESOLUTION_PRESETS: [ResolutionPreset; 5] = [
+    ResolutionPreset::new("Square", 1024, 1024),
+    ResolutionPreset::new("HD", 1280, 720),
+    ResolutionPreset::new("Steam Deck", 1280, 800),
+    ResolutionPreset::new("HD+", 1600, 900),
+    ResolutionPreset::new("Full HD", 1920, 1080),
+];
+
+pub const UI_SCALES: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+
+pub const PRESENT_MODES: [PresentMode; 3] = [
+    PresentMode::AutoVsync,
+    PresentMode::AutoNoVsync,
+    PresentMode::Fifo,
+];
+
+// Player preferences from config/settings.ron, applied to the window as soon as they change and
+// written back when edited in game. Keybindings live in their own file, see `Keybindings`.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        let mut settings = config::load_or_default::<Settings>(SETTINGS_FILE);
+        settings.snap_to_options();
+        app.insert_resource(settings)
+            .register_type::<Settings>()
+            .add_event::<SaveSettings>()
+            .add_systems(
+                Update,
+                (
+                    // Not while the key is typed into the console or being bound to an action
+                    toggle_fullscreen.run_if(
+                        fullscreen_key_just_pressed
+                            .and(not(console_is_open))
+                            .and(not(is_rebinding)),
+                    ),
+                    apply_window_settings.run_if(resource_changed::<Settings>),
+                )
+                    .chain(),
+            )
+            .add_systems(
+                Last,
+                save_settings.run_if(
+                    resource_changed::<Settings>
+                        .and(not(resource_added::<Settings>))
+                        .or(on_event::<SaveSettings>),
+                ),
+            );
+    }
+}
+
+// Asks for the settings to be saved right away, with a toast saying whether that worked. Changes
+// are saved quietly as they're made, this is for when the player is done editing them.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct SaveSettings;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResolutionPreset {
+    pub name: &'static str,
+    pub size: UVec2,
+}
+
+impl ResolutionPreset {
+    const fn new(name: &'static str, width: u32, height: u32) -> Self {
+        Self {
+            name,
+            size: UVec2::new(width, height),
+        }
+    }
+}
+
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize, Reflect)]
+#[reflect(Resource)]
+#[serde(default)]
+pub struct Settings {
+    // Who high scores are set by
+    pub player_name: String,
+    pub language: Language,
+    pub present_mode: PresentMode,
+    // Window size when not fullscreen
+    pub resolution: UVec2,
+    // Borderless fullscreen on the current monitor
+    pub fullscreen: bool,
+    pub fullscreen_key: KeyCode,
+    // Multiplies the size of all UI
+    pub ui_scale: f32,
+    pub audio: AudioSettings,
+    pub music_track: MusicTrack,
+    pub accessibility: Accessibility,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            player_name: default_player_name(),
+            language: Language::default(),
+            present_mode: PresentMode::AutoVsync,
+            resolution: RESOLUTION_PRESETS[0].size,
+            fullscreen: false,
+            fullscreen_key: KeyCode::F11,
+            ui_scale: 1.0,
+            audio: AudioSettings::default(),
+            music_track: MusicTrack::default(),
+            accessibility: Accessibility::default(),
+        }
+    }
+}
+
+// Whoever is logged in, until a name is set in config/settings.ron
+fn default_player_name() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "Player".to_owned())
+}
+
+impl Settings {
+    pub fn next_language(&mut self) {
+        self.language = next_in(&Language::ALL, self.language);
+    }
+
+    pub fn next_music_track(&mut self) {
+        self.music_track = next_in(&MusicTrack::ALL, self.music_track);
+    }
+
+    pub fn next_present_mode(&mut self) {
+        self.present_mode = next_in(&PRESENT_MODES, self.present_mode);
+    }
+
+    pub fn next_resolution(&mut self) {
+        let sizes = RESOLUTION_PRESETS.map(|preset| preset.size);
+        self.resolution = next_in(&sizes, self.resolution);
+    }
+
+    // The preset matching the window size, if it's one of them
+    pub fn resolution_preset(&self) -> Option<ResolutionPreset> {
+        RESOLUTION_PRESETS
+            .into_iter()
+            .find(|preset| preset.size == self.resolution)
+    }
+
+    pub fn next_ui_scale(&mut self) {
+        self.ui_scale = next_in(&UI_SCALES, self.ui_scale);
+    }
+
+    // The file is edited by hand, but the window and the UI only get the sizes and scales the
+    // settings screen offers, so anything else is moved to the closest of them
+    fn snap_to_options(&mut self) {
+        let ui_scale = if self.ui_scale.is_finite() {
+            closest(&UI_SCALES, |scale| (scale - self.ui_scale).abs())
+        } else {
+            Self::default().ui_scale
+        };
+        if ui_scale != self.ui_scale {
+            warn!(
+                "{SETTINGS_FILE}: UI scale {} isn't one of {UI_SCALES:?}, using {ui_scale}",
+                self.ui_scale
+            );
+            self.ui_scale = ui_scale;
+        }
+        if self.resolution_preset().is_none() {
+            let sizes = RESOLUTION_PRESETS.map(|preset| preset.size);
+            let resolution = closest(&sizes, |size| {
+                size.as_vec2().distance_squared(self.resolution.as_vec2())
+            });
+            warn!(
+                "{SETTINGS_FILE}: resolution {} isn't a preset, using {resolution}",
+                self.resolution
+            );
+            self.resolution = resolution;
+        }
+    }
+}
+
+// The option after `current`, or the first one if `current` isn't among them
+fn next_in<T: Copy + PartialEq>(options: &[T], current: T) -> T {
+    let index = options.iter().position(|option| *option == current);
+    options[index.map_or(0, |index| (index + 1) % options.len())]
+}
+
+// The option `distance` is smallest for
+fn closest<T: Copy>(options: &[T], distance: impl Fn(T) -> f32) -> T {
+    options
+        .iter()
+        .copied()
+        .min_by(|a, b| distance(*a).total_cmp(&distance(*b)))
+        .expect("there are options to choose from")
+}
+
+fn fullscreen_key_just_pressed(
+    settings: Res<Settings>,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+) -> bool {
+    keyboard_input.just_pressed(settings.fullscreen_key)
+}
+
+fn toggle_fullscreen(mut settings: ResMut<Settings>) {
+    settings.fullscreen = !settings.fullscreen;
+}
+
+// The resize that follows a mode or resolution change sends `WindowResized`, which is what
+// moves the cats back inside the window
+fn apply_window_settings(
+    settings: Res<Settings>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    mut ui_scale: ResMut<UiScale>,
+) {
+    if ui_scale.0 != settings.ui_scale {
+        ui_scale.0 = settings.ui_scale;
+    }
+    if window.present_mode != settings.present_mode {
+        window.present_mode = settings.present_mode;
+    }
+    let mode = if settings.fullscreen {
+        WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+    } else {
+        WindowMode::Windowed
+    };
+    if window.mode != mode {
+        window.mode = mode;
+    }
+    let size = settings.resolution.as_vec2();
+    if !settings.fullscreen && window.resolution.size() != size {
+        window.resolution.set(size.x, size.y);
+        // Stay centered like the window started out, instead of growing from the top left corner
+        window.position.center(MonitorSelection::Current);
+    }
+}
+
+fn save_settings(
+    settings: Res<Settings>,
+    localization: Res<Localization>,
+    mut requests: EventReader<SaveSettings>,
+    mut notify: EventWriter<Notify>,
+) {
+    let requested = requests.read().count() > 0;
+    let result = config::save(SETTINGS_FILE, &*settings);
+    if let Err(error) = &result {
+        warn!("{SETTINGS_FILE}: {error}");
+    }
+    if !requested {
+        return;
+    }
+    let toast = match result {
+        Ok(()) => "toast-settings-saved",
+        Err(_) => "toast-settings-failed",
+    };
+    notify.write(Notify::new(localization.get(toast)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_in_moves_to_the_following_option() {
+        assert_eq!(next_in(&[1, 2, 3], 1), 2);
+        assert_eq!(next_in(&[1, 2, 3], 2), 3);
+    }
+
+    #[test]
+    fn next_in_wraps_around() {
+        assert_eq!(next_in(&[1, 2, 3], 3), 1);
+    }
+
+    #[test]
+    fn next_in_starts_over_from_an_unknown_option() {
+        assert_eq!(next_in(&[1, 2, 3], 7), 1);
+        assert_eq!(next_in(&UI_SCALES, 1.1), UI_SCALES[0]);
+    }
+
+    #[test]
+    fn snap_to_options_keeps_offered_options() {
+        let mut settings = Settings {
+            resolution: RESOLUTION_PRESETS[3].size,
+            ui_scale: UI_SCALES[2],
+            ..Default::default()
+        };
+        settings.snap_to_options();
+        assert_eq!(settings.resolution, RESOLUTION_PRESETS[3].size);
+        assert_eq!(settings.ui_scale, UI_SCALES[2]);
+    }
+
+    #[test]
+    fn snap_to_options_moves_ui_scales_to_the_closest_one() {
+        for (ui_scale, snapped) in [(0.0, 0.75), (-3.0, 0.75), (1.3, 1.25), (10.0, 2.0)] {
+            let mut settings = Settings {
+                ui_scale,
+                ..Default::default()
+            };
+            settings.snap_to_options();
+            assert_eq!(settings.ui_scale, snapped, "{ui_scale}");
+        }
+    }
+
+    #[test]
+    fn snap_to_options_falls_back_to_the_default_ui_scale_for_nan() {
+        let mut settings = Settings {
+            ui_scale: f32::NAN,
+            ..Default::default()
+        };
+        settings.snap_to_options();
+        assert_eq!(settings.ui_scale, 1.0);
+    }
+
+    #[test]
+    fn snap_to_options_moves_resolutions_to_the_closest_preset() {
+        for (resolution, snapped) in [
+            (UVec2::ZERO, UVec2::new(1024, 1024)),
+            (UVec2::new(1280, 790), UVec2::new(1280, 800)),
+            (UVec2::new(3840, 2160), UVec2::new(1920, 1080)),
+        ] {
+            let mut settings = Settings {
+                resolution,
+                ..Default::default()
+            };
+            settings.snap_to_options();
+            assert_eq!(settings.resolution, snapped, "{resolution}");
+        }
+    }
+}
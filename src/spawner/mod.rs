@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use serde::Deserialize;
+
+use crate::collectible::{FISH_SIZE, random_position};
+use crate::collision::Collider;
+use crate::dog::DOG_SIZE;
+use crate::obstacle::{Obstacle, obstacle_boxes};
+use crate::power_up::PICKUP_SIZE;
+use crate::state::GameState;
+
+mod wave;
+
+pub use wave::{Wave, WaveDirector, Waves};
+
+// The corners of the room, as fractions of the half window, for `SpawnArea::Corners`
+const CORNERS: [Vec2; 4] = [
+    Vec2::new(0.7, 0.6),
+    Vec2::new(-0.7, -0.6),
+    Vec2::new(-0.7, 0.6),
+    Vec2::new(0.7, -0.6),
+];
+
+// Entities that bring more fish, power-ups or dogs into the room every so often, up to a limit.
+// A spawner only picks when and where: it sends `Spawn` and the plugin owning the prototype
+// spawns it. The `WaveDirector` makes all of them busier as a level goes on.
+pub struct SpawnerPlugin;
+
+impl Plugin for SpawnerPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Spawner>()
+            .register_type::<SpawnedBy>()
+            .add_event::<Spawn>()
+            .add_plugins(wave::WavePlugin)
+            .add_systems(Update, run_spawners.run_if(in_state(GameState::Playing)));
+    }
+}
+
+// What a spawner brings in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Reflect)]
+pub enum Prototype {
+    Fish,
+    PowerUp,
+    Dog,
+}
+
+impl Prototype {
+    // Roughly how much room one takes, so none is spawned inside an obstacle
+    fn size(self) -> Vec2 {
+        match self {
+            Prototype::Fish => FISH_SIZE,
+            Prototype::PowerUp => PICKUP_SIZE,
+            Prototype::Dog => DOG_SIZE,
+        }
+    }
+}
+
+// Where a spawner puts what it spawns
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+pub enum SpawnArea {
+    // Anywhere the cats can reach, out of the obstacles
+    #[default]
+    Room,
+    // Each corner of the room in turn, away from the cats starting in the middle
+    Corners,
+}
+
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Spawner {
+    pub prototype: Prototype,
+    // Seconds between spawns, before the wave speeds it up
+    pub interval: f32,
+    // No more are spawned while this many from the spawner are around, plus the wave's extra
+    pub max_alive: usize,
+    pub area: SpawnArea,
+    // Virtual time, so nothing piles up while the game is paused
+    timer: Timer,
+    // How many it has spawned so far, for taking turns between corners
+    spawned: usize,
+}
+
+impl Spawner {
+    pub fn new(prototype: Prototype, interval: f32, max_alive: usize) -> Self {
+        Self {
+            prototype,
+            interval,
+            max_alive,
+            area: SpawnArea::default(),
+            timer: Timer::from_seconds(interval, TimerMode::Repeating),
+            spawned: 0,
+        }
+    }
+
+    pub fn with_area(mut self, area: SpawnArea) -> Self {
+        self.area = area;
+        self
+    }
+}
+
+// Asks the plugin owning `prototype` to spawn one at `position`. Whatever it spawns should carry
+// `SpawnedBy(spawner)`, or the spawner never stops.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Spawn {
+    pub prototype: Prototype,
+    pub position: Vec2,
+    pub spawner: Entity,
+}
+
+// The spawner an entity came from, counting towards its `max_alive` while it's around
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct SpawnedBy(pub Entity);
+
+fn run_spawners(
+    time: Res<Time>,
+    director: Res<WaveDirector>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    mut spawners: Query<(Entity, &mut Spawner)>,
+    spawned: Query<&SpawnedBy>,
+    obstacles: Query<(&Transform, &Collider), With<Obstacle>>,
+    mut spawns: EventWriter<Spawn>,
+) {
+    let mut alive = HashMap::new();
+    for SpawnedBy(spawner) in &spawned {
+        *alive.entry(*spawner).or_insert(0) += 1;
+    }
+    let wave = &director.wave;
+    let obstacles = obstacle_boxes(obstacles);
+    for (entity, mut spawner) in &mut spawners {
+        let interval = Duration::from_secs_f32(spawner.interval * wave.interval);
+        if spawner.timer.duration() != interval {
+            spawner.timer.set_duration(interval);
+        }
+        let max_alive = spawner.max_alive + wave.extra(spawner.prototype);
+        if !spawner.timer.tick(time.delta()).just_finished()
+            || alive.get(&entity).copied().unwrap_or(0) >= max_alive
+        {
+            continue;
+        }
+        let position = match spawner.area {
+            SpawnArea::Room => random_position(&window, spawner.prototype.size(), &obstacles),
+            SpawnArea::Corners => CORNERS[spawner.spawned % CORNERS.len()] * window.size() / 2.0,
+        };
+        spawner.spawned += 1;
+        spawns.write(Spawn {
+            prototype: spawner.prototype,
+            position,
+            spawner: entity,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn world(wave: Wave) -> World {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.insert_resource(WaveDirector {
+            wave,
+            ..Default::default()
+        });
+        world.init_resource::<Events<Spawn>>();
+        world.spawn((Window::default(), PrimaryWindow));
+        world
+    }
+
+    // What the spawners send over the next `seconds`
+    fn run(world: &mut World, seconds: f32) -> Vec<Spawn> {
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs_f32(seconds));
+        world.run_system_once(run_spawners).unwrap();
+        world.resource_mut::<Events<Spawn>>().drain().collect()
+    }
+
+    #[test]
+    fn a_spawner_goes_off_every_interval() {
+        let mut world = world(Wave::default());
+        let spawner = world.spawn(Spawner::new(Prototype::Fish, 2.0, 5)).id();
+        assert!(run(&mut world, 1.0).is_empty());
+        let spawns = run(&mut world, 1.0);
+        assert_eq!(spawns.len(), 1);
+        assert_eq!(spawns[0].prototype, Prototype::Fish);
+        assert_eq!(spawns[0].spawner, spawner);
+        assert!(run(&mut world, 1.0).is_empty());
+        assert_eq!(run(&mut world, 1.0).len(), 1);
+    }
+
+    #[test]
+    fn the_wave_speeds_spawners_up() {
+        let mut world = world(Wave {
+            interval: 0.5,
+            ..Default::default()
+        });
+        world.spawn(Spawner::new(Prototype::Dog, 2.0, 5));
+        assert_eq!(run(&mut world, 1.0).len(), 1);
+    }
+
+    #[test]
+    fn a_spawner_waits_while_enough_of_its_own_are_around() {
+        let mut world = world(Wave::default());
+        let spawner = world.spawn(Spawner::new(Prototype::Fish, 1.0, 1)).id();
+        world.spawn(SpawnedBy(spawner));
+        // Someone else's don't count
+        world.spawn(SpawnedBy(Entity::PLACEHOLDER));
+        assert!(run(&mut world, 1.0).is_empty());
+    }
+
+    #[test]
+    fn the_wave_lets_more_be_around() {
+        let mut wave = Wave::default();
+        wave.extra.insert(Prototype::Fish, 1);
+        let mut world = world(wave);
+        let spawner = world.spawn(Spawner::new(Prototype::Fish, 1.0, 1)).id();
+        world.spawn(SpawnedBy(spawner));
+        assert_eq!(run(&mut world, 1.0).len(), 1);
+    }
+
+    #[test]
+    fn corner_spawners_take_turns() {
+        let mut world = world(Wave::default());
+        world.spawn(Spawner::new(Prototype::Dog, 1.0, 5).with_area(SpawnArea::Corners));
+        let half_window = Window::default().size() / 2.0;
+        for corner in [CORNERS[0], CORNERS[1], CORNERS[2], CORNERS[3], CORNERS[0]] {
+            let spawns = run(&mut world, 1.0);
+            assert_eq!(spawns[0].position, corner * half_window);
+        }
+    }
+}
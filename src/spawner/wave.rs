@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::Prototype;
+use crate::level::Level;
+use crate::loading::LoadingAssets;
+use crate::localization::Localization;
+use crate::notifications::Notify;
+use crate::state::{GameState, InGame};
+
+const WAVES_PATH: &str = "game.waves.ron";
+
+// Starts the waves from assets/game.waves.ron over with every level, moving on to the next one as
+// the level goes on
+pub(super) struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Waves>()
+            .init_asset_loader::<WavesLoader>()
+            .init_resource::<WaveDirector>()
+            .register_type::<WaveDirector>()
+            .add_systems(Startup, load_waves)
+            .add_systems(
+                Update,
+                (
+                    restart_waves.run_if(in_state(InGame).and(resource_changed::<Level>)),
+                    advance_waves.run_if(in_state(GameState::Playing)),
+                )
+                    .chain(),
+            );
+    }
+}
+
+// How busy the spawners are from some point in a level on
+#[derive(Clone, Debug, Deserialize, Reflect)]
+#[serde(default)]
+pub struct Wave {
+    // Seconds into the level it starts
+    pub after: f32,
+    // Multiplies every spawner's interval, below 1 to spawn more often
+    pub interval: f32,
+    // How many more of each prototype can be around at once
+    pub extra: HashMap<Prototype, usize>,
+}
+
+impl Default for Wave {
+    fn default() -> Self {
+        Self {
+            after: 0.0,
+            interval: 1.0,
+            extra: HashMap::new(),
+        }
+    }
+}
+
+impl Wave {
+    pub fn extra(&self, prototype: Prototype) -> usize {
+        self.extra.get(&prototype).copied().unwrap_or(0)
+    }
+}
+
+// Every wave, in the order they start. Without any (or until they're loaded) the spawners keep
+// the pace their level gives them.
+#[derive(Asset, TypePath, Clone, Debug)]
+pub struct Waves(pub Vec<Wave>);
+
+// The wave the current level is on
+#[derive(Resource, Clone, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub struct WaveDirector {
+    // From 0
+    pub index: usize,
+    pub wave: Wave,
+    // Since the level started, pauses not included
+    pub elapsed: Stopwatch,
+}
+
+#[derive(Resource)]
+struct WavesHandle(Handle<Waves>);
+
+fn load_waves(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let handle = asset_server.load(WAVES_PATH);
+    loading.track(handle.clone());
+    commands.insert_resource(WavesHandle(handle));
+}
+
+fn restart_waves(
+    mut director: ResMut<WaveDirector>,
+    waves: Res<Assets<Waves>>,
+    handle: Res<WavesHandle>,
+) {
+    let first = waves
+        .get(&handle.0)
+        .and_then(|waves| waves.0.first())
+        .cloned();
+    *director = WaveDirector {
+        wave: first.unwrap_or_default(),
+        ..Default::default()
+    };
+}
+
+// A new wave is announced with a toast
+fn advance_waves(
+    time: Res<Time>,
+    mut director: ResMut<WaveDirector>,
+    waves: Res<Assets<Waves>>,
+    handle: Res<WavesHandle>,
+    localization: Res<Localization>,
+    mut notify: EventWriter<Notify>,
+) {
+    director.elapsed.tick(time.delta());
+    let Some(waves) = waves.get(&handle.0) else {
+        return;
+    };
+    let Some(next) = waves.0.get(director.index + 1) else {
+        return;
+    };
+    if director.elapsed.elapsed_secs() < next.after {
+        return;
+    }
+    director.index += 1;
+    director.wave = next.clone();
+    let number = (director.index + 1).to_string();
+    notify.write(Notify::new(
+        localization.format("toast-wave", &[("number", number)]),
+    ));
+}
+
+#[derive(Default)]
+pub struct WavesLoader;
+
+#[derive(Debug, Error)]
+pub enum WavesLoaderError {
+    #[error("could not read waves: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse waves: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    // Spawner timers can't run on a negative (or NaN) number of seconds
+    #[error("invalid interval {1} in wave {0}, it must be a positive number")]
+    Interval(usize, f32),
+    #[error("invalid start {1} of wave {0}, it must be a number of seconds")]
+    After(usize, f32),
+}
+
+impl AssetLoader for WavesLoader {
+    type Asset = Waves;
+    type Settings = ();
+    type Error = WavesLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let waves: Vec<Wave> = ron::de::from_bytes(&bytes)?;
+        for (index, wave) in waves.iter().enumerate() {
+            if !(wave.interval.is_finite() && wave.interval > 0.0) {
+                return Err(WavesLoaderError::Interval(index, wave.interval));
+            }
+            if !(wave.after.is_finite() && wave.after >= 0.0) {
+                return Err(WavesLoaderError::After(index, wave.after));
+            }
+        }
+        Ok(Waves(waves))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["waves.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::RunSystemOnce;
+
+    use super::*;
+
+    fn wave(after: f32, interval: f32) -> Wave {
+        Wave {
+            after,
+            interval,
+            ..Default::default()
+        }
+    }
+
+    // A level that has just started with `waves`
+    fn world(waves: Vec<Wave>) -> World {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<WaveDirector>();
+        world.init_resource::<Localization>();
+        world.init_resource::<Events<Notify>>();
+        let mut assets = Assets::<Waves>::default();
+        let handle = assets.add(Waves(waves));
+        world.insert_resource(assets);
+        world.insert_resource(WavesHandle(handle));
+        world.run_system_once(restart_waves).unwrap();
+        world
+    }
+
+    fn elapse(world: &mut World, seconds: u64) {
+        world
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs(seconds));
+        world.run_system_once(advance_waves).unwrap();
+    }
+
+    fn index(world: &World) -> usize {
+        world.resource::<WaveDirector>().index
+    }
+
+    #[test]
+    fn a_level_starts_on_the_first_wave() {
+        let world = world(vec![wave(0.0, 0.8), wave(10.0, 0.5)]);
+        assert_eq!(index(&world), 0);
+        assert_eq!(world.resource::<WaveDirector>().wave.interval, 0.8);
+    }
+
+    #[test]
+    fn without_waves_spawners_keep_their_pace() {
+        let mut world = world(Vec::new());
+        elapse(&mut world, 30);
+        assert_eq!(index(&world), 0);
+        assert_eq!(world.resource::<WaveDirector>().wave.interval, 1.0);
+    }
+
+    #[test]
+    fn the_next_wave_starts_once_its_time_has_come() {
+        let mut world = world(vec![wave(0.0, 1.0), wave(10.0, 0.5)]);
+        elapse(&mut world, 9);
+        assert_eq!(index(&world), 0);
+        elapse(&mut world, 1);
+        assert_eq!(index(&world), 1);
+        assert_eq!(world.resource::<WaveDirector>().wave.interval, 0.5);
+        // Announced with a toast
+        assert_eq!(world.resource::<Events<Notify>>().len(), 1);
+    }
+
+    #[test]
+    fn a_long_frame_moves_on_one_wave_at_a_time() {
+        let mut world = world(vec![wave(0.0, 1.0), wave(10.0, 0.5), wave(20.0, 0.25)]);
+        elapse(&mut world, 25);
+        assert_eq!(index(&world), 1);
+        elapse(&mut world, 0);
+        assert_eq!(index(&world), 2);
+    }
+
+    #[test]
+    fn the_last_wave_lasts_until_the_level_ends() {
+        let mut world = world(vec![wave(0.0, 1.0), wave(10.0, 0.5)]);
+        elapse(&mut world, 10);
+        elapse(&mut world, 100);
+        assert_eq!(index(&world), 1);
+    }
+
+    #[test]
+    fn a_new_level_starts_the_waves_over() {
+        let mut world = world(vec![wave(0.0, 1.0), wave(10.0, 0.5)]);
+        elapse(&mut world, 10);
+        world.run_system_once(restart_waves).unwrap();
+        let director = world.resource::<WaveDirector>();
+        assert_eq!(director.index, 0);
+        assert_eq!(director.wave.interval, 1.0);
+        assert_eq!(director.elapsed.elapsed(), Duration::ZERO);
+    }
+}
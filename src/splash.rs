@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+
+use crate::localization::LocalizedText;
+use crate::state::GameState;
+use crate::theme::{Surface, Theme};
+
+const SPLASH_SECONDS: f32 = 3.0;
+// Part of the splash spent fading in, and again fading out
+const FADE_FRACTION: f32 = 0.3;
+
+// Fades the logo in and out before loading continues to the menu. Any key, click or gamepad
+// button skips it.
+pub struct SplashPlugin;
+
+impl Plugin for SplashPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Splash), spawn_splash)
+            .add_systems(
+                Update,
+                (fade_splash, skip_splash).run_if(in_state(GameState::Splash)),
+            );
+    }
+}
+
+#[derive(Component)]
+struct SplashLogo;
+
+#[derive(Resource)]
+struct SplashTimer(Timer);
+
+fn spawn_splash(mut commands: Commands, theme: Res<Theme>) {
+    commands.insert_resource(SplashTimer(Timer::from_seconds(
+        SPLASH_SECONDS,
+        TimerMode::Once,
+    )));
+    commands.spawn((
+        StateScoped(GameState::Splash),
+        Name::new("Splash"),
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        },
+        theme.surface(Surface::Backdrop),
+        children![(
+            SplashLogo,
+            Text::default(),
+            LocalizedText::new("game-title"),
+            TextFont {
+                font: theme.font.clone(),
+                font_size: theme.title_size * 1.5,
+                ..Default::default()
+            },
+            TextColor(theme.text.with_alpha(0.0)),
+        )],
+    ));
+}
+
+fn fade_splash(
+    time: Res<Time<Real>>,
+    mut timer: ResMut<SplashTimer>,
+    mut logo: Single<&mut TextColor, With<SplashLogo>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    timer.0.tick(time.delta());
+    let t = timer.0.fraction();
+    let alpha = (t / FADE_FRACTION)
+        .min((1.0 - t) / FADE_FRACTION)
+        .clamp(0.0, 1.0);
+    logo.0.set_alpha(alpha);
+    if timer.0.finished() {
+        next_state.set(GameState::Loading);
+    }
+}
+
+fn skip_splash(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let pressed = keyboard_input.get_just_pressed().next().is_some()
+        || mouse.get_just_pressed().next().is_some()
+        || gamepads
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some());
+    if pressed {
+        next_state.set(GameState::Loading);
+    }
+}
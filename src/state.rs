@@ -0,0 +1,35 @@
+use bevy::prelude::*;
+
+// The screen the game is on. Gameplay only runs while `Playing`.
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GameState {
+    #[default]
+    Splash,
+    Loading,
+    Menu,
+    // Rolling credits, reached from the main menu
+    Credits,
+    Playing,
+    Paused,
+    // Between two levels, showing what the next one is about
+    LevelTransition,
+    // The game has ended, e.g. the cat ran out of health or time
+    GameOver,
+}
+
+// In a game, paused, between levels or not. Things that should last until quitting to the menu,
+// like the HUD, are scoped to this instead of `GameState::Playing`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InGame;
+
+impl ComputedStates for InGame {
+    type SourceStates = GameState;
+
+    fn compute(state: GameState) -> Option<Self> {
+        matches!(
+            state,
+            GameState::Playing | GameState::Paused | GameState::LevelTransition
+        )
+        .then_some(InGame)
+    }
+}
@@ -0,0 +1,339 @@
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::color::HexColorError;
+use bevy::prelude::*;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::accessibility::Accessibility;
+use crate::loading::LoadingAssets;
+
+const THEME_PATH: &str = "ui.theme.ron";
+const HIGH_CONTRAST_THEME_PATH: &str = "ui-high-contrast.theme.ron";
+
+// Colors, font and spacing of all the UI, from assets/ui.theme.ron. UI is built from the `Theme`
+// resource, which holds the built-in look until the file has loaded. With the `dev` feature,
+// saving the file restyles whatever is on screen. High contrast swaps in
+// assets/ui-high-contrast.theme.ron instead.
+pub struct ThemePlugin;
+
+impl Plugin for ThemePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<Theme>()
+            .init_asset_loader::<ThemeLoader>()
+            .init_resource::<Theme>()
+            .add_systems(Startup, load_theme)
+            .add_systems(
+                Update,
+                (
+                    update_theme,
+                    apply_theme.run_if(resource_changed::<Theme>.and(not(resource_added::<Theme>))),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Asset, Resource, TypePath, Clone, Debug)]
+pub struct Theme {
+    pub font: Handle<Font>,
+    pub title_size: f32,
+    pub heading_size: f32,
+    pub button_size: f32,
+    pub body_size: f32,
+    pub small_size: f32,
+    pub text: Color,
+    // Behind screens that cover everything, like the splash
+    pub backdrop: Color,
+    // Dims the game behind menus
+    pub overlay: Color,
+    // Boxes on top of the game, like the HUD or the debug panels
+    pub panel: Color,
+    // Insets inside panels, like the empty part of a bar
+    pub track: Color,
+    pub button: Color,
+    pub button_hovered: Color,
+    // Pressed buttons, and whatever is waiting for input
+    pub accent: Color,
+    // Filled part of bars
+    pub meter: Color,
+    pub button_padding: Vec2,
+    pub panel_padding: f32,
+    pub gap: f32,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            font: Handle::default(),
+            title_size: 64.0,
+            heading_size: 48.0,
+            button_size: 28.0,
+            body_size: 20.0,
+            small_size: 14.0,
+            text: Color::WHITE,
+            backdrop: Color::BLACK,
+            overlay: Color::srgba(0.0, 0.0, 0.0, 0.6),
+            panel: Color::srgba(0.0, 0.0, 0.0, 0.7),
+            track: Color::srgba(1.0, 1.0, 1.0, 0.1),
+            button: Color::srgba(1.0, 1.0, 1.0, 0.08),
+            button_hovered: Color::srgba(1.0, 1.0, 1.0, 0.2),
+            accent: Color::srgba(1.0, 0.85, 0.3, 0.35),
+            meter: Color::srgb(0.35, 0.8, 0.95),
+            button_padding: Vec2::new(16.0, 8.0),
+            panel_padding: 8.0,
+            gap: 12.0,
+        }
+    }
+}
+
+// How big a piece of text is. Kept on the text so a new theme can restyle it.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextRole {
+    Title,
+    Heading,
+    Button,
+    Body,
+    Small,
+}
+
+// Which theme color a node's background is
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Surface {
+    Backdrop,
+    Overlay,
+    Panel,
+    Track,
+    Button,
+    Accent,
+    Meter,
+}
+
+impl Theme {
+    pub fn font_size(&self, role: TextRole) -> f32 {
+        match role {
+            TextRole::Title => self.title_size,
+            TextRole::Heading => self.heading_size,
+            TextRole::Button => self.button_size,
+            TextRole::Body => self.body_size,
+            TextRole::Small => self.small_size,
+        }
+    }
+
+    pub fn color(&self, surface: Surface) -> Color {
+        match surface {
+            Surface::Backdrop => self.backdrop,
+            Surface::Overlay => self.overlay,
+            Surface::Panel => self.panel,
+            Surface::Track => self.track,
+            Surface::Button => self.button,
+            Surface::Accent => self.accent,
+            Surface::Meter => self.meter,
+        }
+    }
+
+    pub fn text_font(&self, role: TextRole) -> TextFont {
+        TextFont {
+            font: self.font.clone(),
+            font_size: self.font_size(role),
+            ..Default::default()
+        }
+    }
+
+    // Font, size and color for a `Text`
+    pub fn text(&self, role: TextRole) -> (TextRole, TextFont, TextColor) {
+        (role, self.text_font(role), TextColor(self.text))
+    }
+
+    pub fn surface(&self, surface: Surface) -> (Surface, BackgroundColor) {
+        (surface, BackgroundColor(self.color(surface)))
+    }
+
+    pub fn button_padding(&self) -> UiRect {
+        UiRect::axes(
+            Val::Px(self.button_padding.x),
+            Val::Px(self.button_padding.y),
+        )
+    }
+
+    pub fn panel_padding(&self) -> UiRect {
+        UiRect::all(Val::Px(self.panel_padding))
+    }
+}
+
+#[derive(Resource)]
+struct ThemeHandles {
+    normal: Handle<Theme>,
+    high_contrast: Handle<Theme>,
+}
+
+impl ThemeHandles {
+    fn get(&self, accessibility: &Accessibility) -> &Handle<Theme> {
+        if accessibility.high_contrast {
+            &self.high_contrast
+        } else {
+            &self.normal
+        }
+    }
+}
+
+fn load_theme(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut loading: ResMut<LoadingAssets>,
+) {
+    let handles = ThemeHandles {
+        normal: asset_server.load(THEME_PATH),
+        high_contrast: asset_server.load(HIGH_CONTRAST_THEME_PATH),
+    };
+    loading.track(handles.normal.clone());
+    loading.track(handles.high_contrast.clone());
+    commands.insert_resource(handles);
+}
+
+// Switches themes when high contrast is toggled, and picks up edits to the file in use
+fn update_theme(
+    mut events: EventReader<AssetEvent<Theme>>,
+    handles: Res<ThemeHandles>,
+    accessibility: Res<Accessibility>,
+    themes: Res<Assets<Theme>>,
+    mut theme: ResMut<Theme>,
+) {
+    let handle = handles.get(&accessibility);
+    let reloaded = events
+        .read()
+        .any(|event| event.is_loaded_with_dependencies(handle) || event.is_modified(handle));
+    if (reloaded || accessibility.is_changed())
+        && let Some(loaded) = themes.get(handle)
+    {
+        *theme = loaded.clone();
+    }
+}
+
+fn apply_theme(
+    theme: Res<Theme>,
+    mut texts: Query<(&TextRole, &mut TextFont, &mut TextColor)>,
+    mut surfaces: Query<(&Surface, &mut BackgroundColor)>,
+) {
+    for (role, mut font, mut color) in &mut texts {
+        font.font = theme.font.clone();
+        font.font_size = theme.font_size(*role);
+        color.0 = theme.text;
+    }
+    for (surface, mut color) in &mut surfaces {
+        color.0 = theme.color(*surface);
+    }
+}
+
+// Colors are written as hex strings, e.g. "#000000b3"
+#[derive(Deserialize)]
+#[serde(default)]
+struct ThemeDef {
+    font: Option<String>,
+    title_size: f32,
+    heading_size: f32,
+    button_size: f32,
+    body_size: f32,
+    small_size: f32,
+    text: String,
+    backdrop: String,
+    overlay: String,
+    panel: String,
+    track: String,
+    button: String,
+    button_hovered: String,
+    accent: String,
+    meter: String,
+    button_padding: (f32, f32),
+    panel_padding: f32,
+    gap: f32,
+}
+
+impl Default for ThemeDef {
+    fn default() -> Self {
+        let theme = Theme::default();
+        let hex = |color: Color| color.to_srgba().to_hex();
+        Self {
+            font: None,
+            title_size: theme.title_size,
+            heading_size: theme.heading_size,
+            button_size: theme.button_size,
+            body_size: theme.body_size,
+            small_size: theme.small_size,
+            text: hex(theme.text),
+            backdrop: hex(theme.backdrop),
+            overlay: hex(theme.overlay),
+            panel: hex(theme.panel),
+            track: hex(theme.track),
+            button: hex(theme.button),
+            button_hovered: hex(theme.button_hovered),
+            accent: hex(theme.accent),
+            meter: hex(theme.meter),
+            button_padding: theme.button_padding.into(),
+            panel_padding: theme.panel_padding,
+            gap: theme.gap,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ThemeLoader;
+
+#[derive(Debug, Error)]
+pub enum ThemeLoaderError {
+    #[error("could not read theme: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("could not parse theme: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("invalid color {0:?} in theme: {1}")]
+    Color(String, HexColorError),
+}
+
+fn parse_color(hex: &str) -> Result<Color, ThemeLoaderError> {
+    Srgba::hex(hex)
+        .map(Color::from)
+        .map_err(|error| ThemeLoaderError::Color(hex.to_owned(), error))
+}
+
+impl AssetLoader for ThemeLoader {
+    type Asset = Theme;
+    type Settings = ();
+    type Error = ThemeLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let def: ThemeDef = ron::de::from_bytes(&bytes)?;
+        Ok(Theme {
+            font: def
+                .font
+                .map(|path| load_context.load(path))
+                .unwrap_or_default(),
+            title_size: def.title_size,
+            heading_size: def.heading_size,
+            button_size: def.button_size,
+            body_size: def.body_size,
+            small_size: def.small_size,
+            text: parse_color(&def.text)?,
+            backdrop: parse_color(&def.backdrop)?,
+            overlay: parse_color(&def.overlay)?,
+            panel: parse_color(&def.panel)?,
+            track: parse_color(&def.track)?,
+            button: parse_color(&def.button)?,
+            button_hovered: parse_color(&def.button_hovered)?,
+            accent: parse_color(&def.accent)?,
+            meter: parse_color(&def.meter)?,
+            button_padding: def.button_padding.into(),
+            panel_padding: def.panel_padding,
+            gap: def.gap,
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+use crate::localization::LocalizedText;
+use crate::theme::{Surface, TextRole, Theme};
+
+// Hover text for any UI node with a `Tooltip`. Once the pointer has rested on it for a moment a
+// small panel shows up next to the cursor, follows it, and flips to the other side of the cursor
+// rather than running off the edge of the window.
+pub struct TooltipPlugin;
+
+impl Plugin for TooltipPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tooltips>()
+            .add_systems(Startup, spawn_tooltip_popup)
+            .add_systems(Update, (hover_tooltips, place_tooltip_popup).chain());
+    }
+}
+
+// Localization key of the text shown while the node is hovered
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+#[require(Interaction)]
+pub struct Tooltip(pub &'static str);
+
+#[derive(Resource, Clone, Debug)]
+pub struct Tooltips {
+    // Seconds the pointer has to rest on a node before its tooltip shows
+    pub delay: f32,
+    // Logical pixels from the cursor to the corner of the popup
+    pub offset: Vec2,
+}
+
+impl Default for Tooltips {
+    fn default() -> Self {
+        Self {
+            delay: 0.5,
+            offset: Vec2::new(16.0, 16.0),
+        }
+    }
+}
+
+#[derive(Component)]
+struct TooltipPopup;
+
+// The node the pointer is resting on, and for how long. Real time, so tooltips work in the pause
+// menu too.
+#[derive(Default)]
+struct Hover {
+    target: Option<Entity>,
+    timer: Timer,
+}
+
+fn spawn_tooltip_popup(mut commands: Commands, theme: Res<Theme>) {
+    commands.spawn((
+        TooltipPopup,
+        Name::new("Tooltip"),
+        Node {
+            position_type: PositionType::Absolute,
+            max_width: Val::Px(320.0),
+            padding: UiRect::axes(
+                Val::Px(theme.button_padding.x / 2.0),
+                Val::Px(theme.button_padding.y / 2.0),
+            ),
+            ..Default::default()
+        },
+        theme.surface(Surface::Panel),
+        Visibility::Hidden,
+        GlobalZIndex(40),
+        Pickable::IGNORE,
+        children![(
+            Text::default(),
+            LocalizedText::new(""),
+            theme.text(TextRole::Small),
+            Pickable::IGNORE,
+        )],
+    ));
+}
+
+fn hover_tooltips(
+    mut hover: Local<Hover>,
+    time: Res<Time<Real>>,
+    tooltips: Res<Tooltips>,
+    targets: Query<(Entity, &Interaction, &Tooltip)>,
+    popup: Single<(&mut Visibility, &Children), With<TooltipPopup>>,
+    mut texts: Query<&mut LocalizedText>,
+) {
+    // Pressing a node hides its tooltip until it's released and the delay has passed again
+    let hovered = targets
+        .iter()
+        .find(|(_, interaction, _)| **interaction == Interaction::Hovered);
+    let (mut visibility, children) = popup.into_inner();
+    let Some((target, _, tooltip)) = hovered else {
+        hover.target = None;
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    if hover.target != Some(target) {
+        hover.target = Some(target);
+        hover.timer = Timer::from_seconds(tooltips.delay, TimerMode::Once);
+        visibility.set_if_neq(Visibility::Hidden);
+    }
+    hover.timer.tick(time.delta());
+    if !hover.timer.finished() {
+        return;
+    }
+    for &child in children {
+        if let Ok(mut text) = texts.get_mut(child)
+            && text.key != tooltip.0
+        {
+            text.key = tooltip.0;
+        }
+    }
+    visibility.set_if_neq(Visibility::Inherited);
+}
+
+fn place_tooltip_popup(
+    tooltips: Res<Tooltips>,
+    ui_scale: Res<UiScale>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    popup: Single<(&mut Node, &ComputedNode, &Visibility), With<TooltipPopup>>,
+) {
+    let (mut node, computed, visibility) = popup.into_inner();
+    if *visibility == Visibility::Hidden {
+        return;
+    }
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+    // Everything in UI units, which are logical pixels divided by the UI scale
+    let cursor = cursor / ui_scale.0;
+    let bounds = window.size() / ui_scale.0;
+    let size = computed.size() * computed.inverse_scale_factor();
+    let mut corner = cursor + tooltips.offset;
+    if corner.x + size.x > bounds.x {
+        corner.x = cursor.x - tooltips.offset.x - size.x;
+    }
+    if corner.y + size.y > bounds.y {
+        corner.y = cursor.y - tooltips.offset.y - size.y;
+    }
+    let corner = corner.max(Vec2::ZERO);
+    node.left = Val::Px(corner.x);
+    node.top = Val::Px(corner.y);
+}
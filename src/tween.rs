@@ -0,0 +1,143 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use bevy::ui::UiSystem;
+
+use crate::accessibility::{Accessibility, reduced_motion};
+use crate::theme::{Surface, TextRole, Theme};
+
+// Eases UI nodes in and out instead of popping them on and off screen. A `Tween` slides a node
+// by an offset, scales it and fades it together with everything inside it, e.g.
+// `Tween::new(0.3).slide(Vec2::new(0.0, -40.0), Vec2::ZERO).fade(0.0, 1.0)`.
+pub struct TweenPlugin;
+
+impl Plugin for TweenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<TweenFinished>()
+            .add_systems(PostUpdate, run_tweens.before(UiSystem::Layout));
+    }
+}
+
+#[derive(Component, Clone, Debug)]
+pub struct Tween {
+    // Real time, so menus animate while the game is paused
+    timer: Timer,
+    ease: EaseFunction,
+    // Logical pixels, set as the node's `left` and `top`
+    offset: Option<(Vec2, Vec2)>,
+    scale: Option<(f32, f32)>,
+    // Multiplies the theme's alpha of the node's and its descendants' backgrounds and text
+    alpha: Option<(f32, f32)>,
+    despawn_when_done: bool,
+}
+
+impl Tween {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+            ease: EaseFunction::CubicOut,
+            offset: None,
+            scale: None,
+            alpha: None,
+            despawn_when_done: false,
+        }
+    }
+
+    pub fn with_ease(mut self, ease: EaseFunction) -> Self {
+        self.ease = ease;
+        self
+    }
+
+    pub fn slide(mut self, from: Vec2, to: Vec2) -> Self {
+        self.offset = Some((from, to));
+        self
+    }
+
+    pub fn scale(mut self, from: f32, to: f32) -> Self {
+        self.scale = Some((from, to));
+        self
+    }
+
+    pub fn fade(mut self, from: f32, to: f32) -> Self {
+        self.alpha = Some((from, to));
+        self
+    }
+
+    // For tweens that take something off screen
+    pub fn despawn_when_done(mut self) -> Self {
+        self.despawn_when_done = true;
+        self
+    }
+
+    fn progress(&self) -> f32 {
+        self.ease.sample_clamped(self.timer.fraction())
+    }
+}
+
+// Sent when a tween has run its course and been removed
+#[derive(Event, Clone, Copy, Debug)]
+pub struct TweenFinished {
+    pub entity: Entity,
+}
+
+// The themed colors of a node and everything inside it
+#[derive(SystemParam)]
+struct Fadeable<'w, 's> {
+    theme: Res<'w, Theme>,
+    children: Query<'w, 's, &'static Children>,
+    surfaces: Query<'w, 's, (&'static Surface, &'static mut BackgroundColor)>,
+    texts: Query<'w, 's, &'static mut TextColor, With<TextRole>>,
+}
+
+impl Fadeable<'_, '_> {
+    fn set_alpha(&mut self, entity: Entity, alpha: f32) {
+        let theme = &self.theme;
+        for faded in std::iter::once(entity).chain(self.children.iter_descendants(entity)) {
+            if let Ok((surface, mut color)) = self.surfaces.get_mut(faded) {
+                let base = theme.color(*surface);
+                color.0 = base.with_alpha(base.alpha() * alpha);
+            }
+            if let Ok(mut color) = self.texts.get_mut(faded) {
+                color.0 = theme.text.with_alpha(theme.text.alpha() * alpha);
+            }
+        }
+    }
+}
+
+fn run_tweens(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    accessibility: Option<Res<Accessibility>>,
+    mut tweens: Query<(Entity, &mut Tween, &mut Node, &mut Transform)>,
+    mut fadeable: Fadeable,
+    mut finished: EventWriter<TweenFinished>,
+) {
+    // Fades are fine with reduced motion, moving and growing things just jump to where they end
+    let reduced_motion = reduced_motion(accessibility);
+    for (entity, mut tween, mut node, mut transform) in &mut tweens {
+        tween.timer.tick(time.delta());
+        let t = tween.progress();
+        let moving_t = if reduced_motion { 1.0 } else { t };
+
+        if let Some((from, to)) = tween.offset {
+            let offset = from.lerp(to, moving_t);
+            node.left = Val::Px(offset.x);
+            node.top = Val::Px(offset.y);
+        }
+        if let Some((from, to)) = tween.scale {
+            let scale = from.lerp(to, moving_t);
+            transform.scale = Vec3::new(scale, scale, 1.0);
+        }
+        if let Some((from, to)) = tween.alpha {
+            fadeable.set_alpha(entity, from.lerp(to, t));
+        }
+
+        if tween.timer.finished() {
+            if tween.despawn_when_done {
+                commands.entity(entity).despawn();
+            } else {
+                commands.entity(entity).remove::<Tween>();
+            }
+            finished.write(TweenFinished { entity });
+        }
+    }
+}